@@ -7,7 +7,6 @@ use bevy::{
     DefaultPlugins,
 };
 use kayak_ui::prelude::{widgets::*, KStyle, *};
-use morphorm::{PositionType, Units};
 
 #[derive(Component, Default)]
 pub struct MyQuad {
@@ -56,8 +56,6 @@ pub struct MyResource(pub u32);
 
 fn startup(mut commands: Commands) {
     let mut context = Context::new();
-    context.add_widget_system(MyWidget::default().get_name(), my_widget_1_update);
-    context.add_widget_system(MyWidget2::default().get_name(), my_widget_2_update);
     let entity = commands
         .spawn((
             MyWidget { foo: 0 },
@@ -80,6 +78,8 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugin(ContextPlugin)
         .insert_resource(MyResource(1))
+        .register_widget::<MyWidget, _>(my_widget_1_update)
+        .register_widget::<MyWidget2, _>(my_widget_2_update)
         .add_startup_system(startup)
         .add_system(update_resource)
         .run()
@@ -0,0 +1,40 @@
+use bevy::{
+    asset::AssetServerSettings,
+    prelude::{App, AssetServer, Commands, Res, ResMut},
+    DefaultPlugins,
+};
+use kayak_ui::prelude::{widgets::*, *};
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    commands.spawn(UICameraBundle::new());
+
+    let mut widget_context = Context::new();
+    let parent_id = None;
+    let handle = asset_server.load("main_menu.kui");
+    rsx! {
+        <KayakAppBundle>
+            <UiAssetBundle asset={UiAssetProps { handle }} />
+        </KayakAppBundle>
+    }
+    commands.insert_resource(widget_context);
+}
+
+fn main() {
+    App::new()
+        // Reloads `main_menu.kui` whenever it's saved (requires bevy's `filesystem_watcher` feature)
+        .insert_resource(AssetServerSettings {
+            watch_for_changes: true,
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ContextPlugin)
+        .add_plugin(KayakWidgets)
+        .add_startup_system(startup)
+        .run()
+}
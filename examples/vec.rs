@@ -23,16 +23,14 @@ fn my_widget_1_update(
         ];
         rsx! {
             <ElementBundle>
-                {data.iter().for_each(|text| {
-                    constructor! {
-                        <TextWidgetBundle
-                            text={TextProps {
-                                content: text.clone().into(),
-                                ..Default::default()
-                            }}
-                        />
-                    }
-                })}
+                {for text in data.iter() {
+                    <TextWidgetBundle
+                        text={TextProps {
+                            content: text.clone().into(),
+                            ..Default::default()
+                        }}
+                    />
+                }}
             </ElementBundle>
         }
         return true;
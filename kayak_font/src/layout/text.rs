@@ -1,8 +1,11 @@
 use crate::{GlyphRect, Line, RowCol};
+#[cfg(feature = "bevy_renderer")]
+use bevy::reflect::{FromReflect, Reflect};
 use std::cmp::Ordering;
 
 /// The text alignment.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy_renderer", derive(Reflect, FromReflect))]
 pub enum Alignment {
     Start,
     Middle,
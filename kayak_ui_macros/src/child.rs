@@ -1,16 +1,40 @@
 use quote::{quote, ToTokens};
-use syn::parse::{ParseStream, Result};
+use syn::parse::{discouraged::Speculative, ParseStream, Result};
 
-use crate::widget::Widget;
+use crate::{block::Block, children::Children, widget::Widget};
 
 #[derive(Clone, Debug)]
 pub enum Child {
     Widget((Widget, usize)),
     RawBlock((syn::Block, usize)),
+    /// An `if` or `for` containing widgets
+    Block((Block, usize)),
+    /// Widgets grouped with `<>...</>`
+    Fragment((Children, usize)),
 }
 
 impl Child {
     pub fn custom_parse(input: ParseStream, index: usize) -> Result<Self> {
+        if input.peek(syn::Token![<]) && input.peek2(syn::Token![>]) {
+            input.parse::<syn::Token![<]>()?;
+            input.parse::<syn::Token![>]>()?;
+            let children = input.parse::<Children>()?;
+            input.parse::<syn::Token![<]>()?;
+            input.parse::<syn::Token![/]>()?;
+            input.parse::<syn::Token![>]>()?;
+            return Ok(Self::Fragment((children, index)));
+        }
+
+        // Blocks whose bodies aren't widgets, like `{if a { constructor! { ... } }}`, are left as
+        // Rust code
+        if input.peek(syn::token::Brace) {
+            let fork = input.fork();
+            if let Ok(block) = fork.parse::<Block>() {
+                input.advance_to(&fork);
+                return Ok(Self::Block((block, index)));
+            }
+        }
+
         match Widget::custom_parse(input, true, index) {
             Ok(widget) => Ok(Self::Widget((widget, index))),
             Err(_) => {
@@ -34,6 +58,11 @@ impl ToTokens for Child {
                 };
                 ts.to_tokens(tokens);
             }
+            Self::Block((block, _)) => block.to_tokens(tokens),
+            Self::Fragment((children, _)) => {
+                let children = children.as_statements();
+                quote!({ #children }).to_tokens(tokens);
+            }
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{child::Child, widget_builder::build_widget_stream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::parse::{Parse, ParseStream, Result};
 
 #[derive(Clone, Debug)]
@@ -12,13 +12,33 @@ impl Children {
         Children { nodes }
     }
 
+    /// Returns true if any child is Rust code, control flow, or a fragment, rather than a widget
     pub fn is_block(&self) -> bool {
         self.nodes.iter().any(|node| match node {
-            Child::RawBlock(_) => true,
-            _ => false,
+            Child::Widget(_) => false,
+            _ => true,
         })
     }
 
+    /// Creates the statements that build each child and add it to `children`
+    pub fn as_statements(&self) -> proc_macro2::TokenStream {
+        let statements = self.nodes.iter().enumerate().map(|(i, child)| match child {
+            Child::Widget((widget, _)) => {
+                let entity_id = if widget.entity_id.to_string().contains("widget_entity") {
+                    format_ident!("child{}", i).to_token_stream()
+                } else {
+                    widget.entity_id.clone()
+                };
+                build_widget_stream(entity_id, quote! { #child }, i, true)
+            }
+            _ => build_widget_stream(quote! {}, quote! { #child }, i, false),
+        });
+
+        quote! {
+            #(#statements)*
+        }
+    }
+
     // pub fn get_clonable_attributes(&self, index: usize) -> Vec<proc_macro2::TokenStream> {
     //     let mut tokens = Vec::new();
 
@@ -198,7 +218,7 @@ impl Parse for Children {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut nodes = vec![];
         let mut index: usize = 0;
-        while !input.peek(syn::Token![<]) || !input.peek2(syn::Token![/]) {
+        while !input.is_empty() && (!input.peek(syn::Token![<]) || !input.peek2(syn::Token![/])) {
             let child = Child::custom_parse(input, index)?;
             nodes.push(child);
             index += 1;
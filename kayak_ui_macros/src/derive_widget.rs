@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, DeriveInput, Error, Lit, Meta, NestedMeta, Result};
+
+use crate::get_core_crate;
+
+/// Generates the `Widget` impl, bundle, and render filter for a widget's props
+pub(crate) fn derive_widget(input: DeriveInput) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`#[derive(Widget)]` doesn't support generic widgets",
+        ));
+    }
+
+    let kayak_ui = get_core_crate();
+    let vis = &input.vis;
+    let props = &input.ident;
+
+    // `FooProps` becomes `FooBundle` and `FooChanged`
+    let name = props.to_string();
+    let base_name = match name.strip_suffix("Props") {
+        Some(base_name) if !base_name.is_empty() => base_name.to_string(),
+        _ => name.clone(),
+    };
+    let bundle = match parse_bundle_name(&input)? {
+        Some(bundle) => bundle,
+        None => format_ident!("{}Bundle", base_name),
+    };
+    let changed = format_ident!("{}Changed", base_name);
+
+    let bundle_doc = format!("The bundle used to spawn a [`{}`] widget", name);
+    let changed_doc = format!(
+        "A query filter matching [`{}`] widgets that need to be rendered again, because their \
+         props, styles, or children changed or they were just mounted",
+        name
+    );
+
+    Ok(quote! {
+        impl #kayak_ui::prelude::Widget for #props {}
+
+        #[doc = #bundle_doc]
+        #[derive(bevy::prelude::Bundle)]
+        #vis struct #bundle {
+            pub widget: #props,
+            pub styles: #kayak_ui::prelude::KStyle,
+            pub on_event: #kayak_ui::prelude::OnEvent,
+            pub children: #kayak_ui::prelude::KChildren,
+            pub widget_name: #kayak_ui::prelude::WidgetName,
+        }
+
+        impl Default for #bundle {
+            fn default() -> Self {
+                Self {
+                    widget: Default::default(),
+                    styles: Default::default(),
+                    on_event: Default::default(),
+                    children: Default::default(),
+                    widget_name: #kayak_ui::prelude::Widget::get_name(
+                        &<#props as Default>::default(),
+                    ),
+                }
+            }
+        }
+
+        #[doc = #changed_doc]
+        #vis type #changed = bevy::prelude::Or<(
+            bevy::prelude::Changed<#props>,
+            bevy::prelude::Changed<#kayak_ui::prelude::KStyle>,
+            bevy::prelude::Changed<#kayak_ui::prelude::KChildren>,
+            bevy::prelude::With<#kayak_ui::prelude::Mounted>,
+        )>;
+    })
+}
+
+/// Parses the bundle name given with `#[widget(bundle = "FooBundle")]`, if there is one
+fn parse_bundle_name(input: &DeriveInput) -> Result<Option<syn::Ident>> {
+    let mut bundle = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("widget"))
+    {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new(
+                    meta.span(),
+                    "expected `#[widget(bundle = \"...\")]`",
+                ))
+            }
+        };
+
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("bundle") =>
+                {
+                    match &name_value.lit {
+                        Lit::Str(lit) => bundle = Some(lit.parse::<syn::Ident>()?),
+                        lit => return Err(Error::new(lit.span(), "expected a string")),
+                    }
+                }
+                nested => {
+                    return Err(Error::new(
+                        nested.span(),
+                        "unknown widget attribute, expected `bundle = \"...\"`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(bundle)
+}
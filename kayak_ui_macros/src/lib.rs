@@ -1,20 +1,47 @@
 use proc_macro::TokenStream;
 use proc_macro_error::proc_macro_error;
 use quote::quote;
-use syn::parse_macro_input;
+use syn::{parse_macro_input, DeriveInput};
 use widget::{ConstructedWidget, Widget};
 
 pub(crate) mod attribute;
+pub(crate) mod block;
 pub(crate) mod child;
 pub(crate) mod children;
+pub(crate) mod derive_widget;
 pub(crate) mod tags;
 pub(crate) mod widget;
 pub(crate) mod widget_attributes;
 pub(crate) mod widget_builder;
-// mod block;
 
 /// A proc macro that turns RSX syntax into structure constructors and calls the
 /// context to create the widgets.
+///
+/// Children can be built conditionally or in a loop, and grouped with fragments:
+///
+/// ```ignore
+/// rsx! {
+///     <ElementBundle>
+///         {if show_title {
+///             <TextWidgetBundle />
+///         } else {
+///             <>
+///                 <ElementBundle />
+///                 <ElementBundle />
+///             </>
+///         }}
+///         {for item in items.iter() {
+///             <TextWidgetBundle key={item.id} />
+///         }}
+///     </ElementBundle>
+/// }
+/// ```
+///
+/// Widgets are matched with the ones from the previous render, so they keep their entity (and
+/// any state kept with it) between renders. Widgets with a `key` are matched with the sibling
+/// that had the same key, which keeps list items with their state as the list is reordered or
+/// items are removed. The key can be anything that implements `ToString`. Widgets without a
+/// key are matched by their position among the other unkeyed siblings.
 #[proc_macro]
 #[proc_macro_error]
 pub fn rsx(input: TokenStream) -> TokenStream {
@@ -37,41 +64,71 @@ pub fn constructor(input: TokenStream) -> TokenStream {
     TokenStream::from(result)
 }
 
-// / Helper method for getting the core crate
-// /
-// / Depending on the usage of the macro, this will become `crate`, `kayak_core`,
-// / or `kayak_ui::core`.
-// /
-// / # Examples
-// /
-// / ```
-// / fn my_macro() -> proc_macro2::TokenStream {
-// /   let kayak_core = get_core_crate();
-// /   quote! {
-// /     let foo = #kayak_core::Foo;
-// /   }
-// / }
-// / ```
-// fn get_core_crate() -> proc_macro2::TokenStream {
-//     let found_crate = proc_macro_crate::crate_name("kayak_ui");
-//     if let Ok(found_crate) = found_crate {
-//         let result = match found_crate {
-//             proc_macro_crate::FoundCrate::Itself => {
-//                 // let crate_name = find_crate::find_crate(|s| s == "kayak_ui" || s == "crate");
-//                 // dbg!(crate_name);
-//                 // if path.contains("example") {
-//                 //quote! { kayak_ui }
-//                 // } else {
-//                 quote! { crate }
-//                 // }
-//             },
-//             proc_macro_crate::FoundCrate::Name(name) => {
-//                 let ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
-//                 quote!(#ident)
-//             }
-//         };
-//         result
-//     } else {
-//         quote!(kayak_ui)
-//     }
-// }
+/// Derives the boilerplate shared by most widgets from their props
+///
+/// For props named `FooProps` (or `Foo`), this generates:
+/// * An implementation of `Widget`
+/// * A `FooBundle` containing the props (as `widget`), `styles`, `on_event`, `children`, and
+/// `widget_name`, along with a `Default` implementation that sets the widget name
+/// * A `FooChanged` query filter, matching widgets whose props, styles, or children changed or
+/// that were just mounted
+///
+/// The bundle can be given a different name with `#[widget(bundle = "...")]`.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Component, Default, Widget)]
+/// pub struct CounterProps {
+///     pub count: u32,
+/// }
+///
+/// pub fn update_counter(
+///     In((widget_context, entity)): In<(WidgetContext, Entity)>,
+///     query: Query<(&CounterProps, &KChildren), CounterChanged>,
+/// ) -> bool {
+///     if let Ok((props, children)) = query.get(entity) {
+///         children.process(&widget_context, Some(entity));
+///         return true;
+///     }
+///     false
+/// }
+///
+/// // Spawned with `<CounterBundle widget={CounterProps { count: 1 }} />`
+/// app.register_widget::<CounterProps, _>(update_counter);
+/// ```
+#[proc_macro_derive(Widget, attributes(widget))]
+#[proc_macro_error]
+pub fn derive_widget(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive_widget::derive_widget(input) {
+        Ok(result) => TokenStream::from(result),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+/// Helper method for getting the core crate
+///
+/// Depending on how the crate is depended on, this will become `kayak_ui` or the name it was
+/// renamed to. Within `kayak_ui` itself (and its examples), `kayak_ui` refers to the crate
+/// through `extern crate self as kayak_ui`.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn my_macro() -> proc_macro2::TokenStream {
+///   let kayak_ui = get_core_crate();
+///   quote! {
+///     let foo = #kayak_ui::Foo;
+///   }
+/// }
+/// ```
+fn get_core_crate() -> proc_macro2::TokenStream {
+    match proc_macro_crate::crate_name("kayak_ui") {
+        Ok(proc_macro_crate::FoundCrate::Name(name)) => {
+            let ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
+            quote!(#ident)
+        }
+        _ => quote!(kayak_ui),
+    }
+}
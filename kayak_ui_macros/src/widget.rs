@@ -131,16 +131,19 @@ impl Widget {
     ) -> (TokenStream, TokenStream, TokenStream) {
         // let kayak_core = get_core_crate();
 
-        let entity_name_id = attrs.attributes.iter().find_map(|attribute| {
-            let key = attribute.ident();
-            let value = attribute.value_tokens();
-            let key_name = quote! { #key }.to_string();
-            if key_name == "id" {
-                return Some(value);
-            } else {
-                None
-            }
-        });
+        let find_attribute = |name: &str| {
+            attrs.attributes.iter().find_map(|attribute| {
+                let key = attribute.ident();
+                let key_name = quote! { #key }.to_string();
+                if key_name == name {
+                    Some(attribute.value_tokens())
+                } else {
+                    None
+                }
+            })
+        };
+        let entity_name_id = find_attribute("id");
+        let widget_key = find_attribute("key");
 
         let prop_ident = format_ident!("internal_rsx_props");
         let entity_id = if let Some(entity_name_id) = entity_name_id {
@@ -196,8 +199,28 @@ impl Widget {
             return (entity_id, quote! { #children }, quote! {});
         }
 
+        // Keyed widgets are matched with the previous render by key, and the rest by position
+        let (find_entity, set_key) = if let Some(widget_key) = widget_key {
+            (
+                quote! {
+                    let widget_key = (#widget_key).to_string();
+                    let entity = widget_context.get_child_with_key(parent_id, &widget_key);
+                },
+                quote! {
+                    widget_context.set_key(parent_id, #entity_id, widget_key);
+                },
+            )
+        } else {
+            (
+                quote! {
+                    let entity = widget_context.get_child_at(parent_id);
+                },
+                quote! {},
+            )
+        };
+
         let props = quote! {
-            let entity = widget_context.get_child_at(parent_id);
+            #find_entity
             let #entity_id = if let Some(entity) = entity {
                 use bevy::prelude::DespawnRecursiveExt;
                 commands.entity(entity).despawn_recursive();
@@ -205,6 +228,7 @@ impl Widget {
             } else {
                 commands.spawn_empty().id()
             };
+            #set_key
             let mut #prop_ident = #name {
                 #assigned_attrs
                 ..Default::default()
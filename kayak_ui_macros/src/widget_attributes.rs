@@ -88,7 +88,8 @@ impl<'a, 'c> CustomWidgetAttributes<'a, 'c> {
                 let key = attribute.ident();
                 let value = attribute.value_tokens();
                 let key_name = quote! { #key }.to_string();
-                if key_name == "id" {
+                // The entity name and key are used to find the widget, rather than being props
+                if key_name == "id" || key_name == "key" {
                     None
                 } else {
                     Some(quote! {
@@ -117,6 +118,8 @@ impl<'a, 'c> CustomWidgetAttributes<'a, 'c> {
                     // Is child NOT an empty block? (`<Foo>{}</Foo>`)
                     block.stmts.len() > 0
                 }
+                // Is child NOT an empty fragment? (`<Foo><></></Foo>`)
+                Child::Fragment((children, _)) => children.nodes.len() > 0,
                 // Child is a widget
                 _ => true,
             }
@@ -126,3 +129,35 @@ impl<'a, 'c> CustomWidgetAttributes<'a, 'c> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use quote::{format_ident, quote};
+
+    use super::WidgetAttributes;
+    use crate::children::Children;
+
+    #[test]
+    fn key_should_not_be_assigned_to_props() {
+        let attributes = syn::parse2::<WidgetAttributes>(
+            quote! { id={"item"} key={item.id} styles={KStyle::default()} },
+        )
+        .unwrap();
+        assert_eq!(attributes.attributes.len(), 3);
+
+        let children = Children::new(vec![]);
+        let assigned = attributes
+            .for_custom_element(&children)
+            .assign_attributes(&format_ident!("props"))
+            .to_string();
+        assert_eq!(assigned, quote! { styles: KStyle::default(), }.to_string());
+    }
+
+    #[test]
+    fn other_attributes_should_be_parsed() {
+        let attributes =
+            syn::parse2::<WidgetAttributes>(quote! { id={"item"} styles={KStyle::default()} })
+                .unwrap();
+        assert_eq!(attributes.attributes.len(), 2);
+    }
+}
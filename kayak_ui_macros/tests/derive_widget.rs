@@ -0,0 +1,45 @@
+use bevy::{ecs::system::SystemState, prelude::*};
+use kayak_ui::prelude::*;
+
+#[derive(Component, Default, Widget)]
+pub struct CounterProps {
+    pub count: u32,
+}
+
+#[derive(Component, Default, Widget)]
+#[widget(bundle = "BadgeWidgetBundle")]
+pub struct Badge;
+
+#[test]
+fn bundle_should_be_named_after_the_props() {
+    let bundle = CounterBundle::default();
+    assert_eq!(bundle.widget_name, CounterProps::default().get_name());
+    assert_eq!(bundle.widget.count, 0);
+}
+
+#[test]
+fn bundle_name_should_be_configurable() {
+    let bundle = BadgeWidgetBundle::default();
+    assert_eq!(bundle.widget_name, Badge::default().get_name());
+}
+
+#[test]
+fn changed_filter_should_match_changed_and_mounted_widgets() {
+    let mut world = World::new();
+    let changed = world.spawn(CounterBundle::default()).id();
+    let mounted = world.spawn((CounterBundle::default(), Mounted)).id();
+    let mut state = SystemState::<Query<Entity, CounterChanged>>::new(&mut world);
+    let mut matching = |world: &World| {
+        let mut entities = state.get(world).iter().collect::<Vec<_>>();
+        entities.sort();
+        entities
+    };
+
+    // Newly spawned widgets have changed props
+    assert_eq!(matching(&world), vec![changed, mounted]);
+    assert_eq!(matching(&world), vec![mounted]);
+
+    world.get_mut::<CounterProps>(changed).unwrap().count += 1;
+    assert_eq!(matching(&world), vec![changed, mounted]);
+    assert_eq!(matching(&world), vec![mounted]);
+}
@@ -0,0 +1,144 @@
+use bevy::{
+    ecs::system::CommandQueue,
+    prelude::{Commands, Entity, World},
+};
+use kayak_ui::prelude::{widgets::*, *};
+
+/// Runs `build`, which is expected to build a single root widget, and returns the widget names
+/// of that root's children
+fn build_children(build: impl FnOnce(&mut Commands, &mut Context)) -> Vec<WidgetName> {
+    let mut world = World::new();
+    let mut queue = CommandQueue::default();
+    let mut widget_context = Context::new();
+    build(&mut Commands::new(&mut queue, &world), &mut widget_context);
+    queue.apply(&mut world);
+
+    let root = widget_context.tree.read().unwrap().root_node.unwrap();
+    world
+        .get::<KChildren>(root.0)
+        .unwrap()
+        .iter()
+        .map(|child| world.get::<WidgetName>(child).unwrap().clone())
+        .collect()
+}
+
+fn element() -> WidgetName {
+    Element::default().get_name()
+}
+
+fn text() -> WidgetName {
+    TextProps::default().get_name()
+}
+
+#[test]
+fn if_should_build_the_matching_branch() {
+    let background = Background::default().get_name();
+    for (value, expected) in [(0, text()), (1, element()), (2, background)] {
+        let children = build_children(|commands, widget_context| {
+            let parent_id: Option<Entity> = None;
+            rsx! {
+                <ElementBundle>
+                    {if value == 0 {
+                        <TextWidgetBundle />
+                    } else if value == 1 {
+                        <ElementBundle />
+                    } else {
+                        <BackgroundBundle />
+                    }}
+                </ElementBundle>
+            }
+        });
+        assert_eq!(children, vec![expected]);
+    }
+}
+
+#[test]
+fn if_without_else_should_build_nothing_when_false() {
+    let show = false;
+    let children = build_children(|commands, widget_context| {
+        let parent_id: Option<Entity> = None;
+        rsx! {
+            <ElementBundle>
+                {if show {
+                    <TextWidgetBundle />
+                }}
+                <ElementBundle />
+            </ElementBundle>
+        }
+    });
+    assert_eq!(children, vec![element()]);
+}
+
+#[test]
+fn for_should_build_each_item() {
+    let items = vec!["a", "b", "c"];
+    let children = build_children(|commands, widget_context| {
+        let parent_id: Option<Entity> = None;
+        rsx! {
+            <ElementBundle>
+                {for item in items.iter() {
+                    <TextWidgetBundle
+                        text={TextProps {
+                            content: item.to_string(),
+                            ..Default::default()
+                        }}
+                    />
+                }}
+            </ElementBundle>
+        }
+    });
+    assert_eq!(children, vec![text(), text(), text()]);
+}
+
+#[test]
+fn for_should_build_keyed_items() {
+    let items = vec![(1, "a"), (2, "b")];
+    let children = build_children(|commands, widget_context| {
+        let parent_id: Option<Entity> = None;
+        rsx! {
+            <ElementBundle>
+                {for (id, item) in items.iter() {
+                    <TextWidgetBundle
+                        key={id}
+                        text={TextProps {
+                            content: item.to_string(),
+                            ..Default::default()
+                        }}
+                    />
+                }}
+            </ElementBundle>
+        }
+    });
+    assert_eq!(children, vec![text(), text()]);
+}
+
+#[test]
+fn fragments_should_add_their_children_to_the_parent() {
+    let children = build_children(|commands, widget_context| {
+        let parent_id: Option<Entity> = None;
+        rsx! {
+            <ElementBundle>
+                {for _ in 0..2 {
+                    <>
+                        <TextWidgetBundle />
+                        <ElementBundle />
+                    </>
+                }}
+            </ElementBundle>
+        }
+    });
+    assert_eq!(children, vec![text(), element(), text(), element()]);
+}
+
+#[test]
+fn empty_fragment_should_build_nothing() {
+    let children = build_children(|commands, widget_context| {
+        let parent_id: Option<Entity> = None;
+        rsx! {
+            <ElementBundle>
+                <></>
+            </ElementBundle>
+        }
+    });
+    assert!(children.is_empty());
+}
@@ -0,0 +1,703 @@
+use bevy::{
+    prelude::{Color, Commands, Component, Entity, Query, Res, Vec2},
+    reflect::FromReflect,
+    time::Time,
+    utils::HashMap,
+};
+use morphorm::Hierarchy;
+
+use crate::{
+    context::Context,
+    layout::Rect,
+    node::{DirtyNode, WrappedIndex},
+    render_primitive::RenderEffects,
+    styles::{
+        AnimationEffect, Corner, Edge, KStyle, KTransform, Lerp, MountAnimation, StyleProp,
+        TransitionProperty, TransitionSpec, Units,
+    },
+    tree::Tree,
+};
+
+/// A transitionable style value
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum TransitionValue {
+    Float(f32),
+    Color(Color),
+    Units(Units),
+    Corner(Corner<f32>),
+    Edge(Edge<f32>),
+    Transform(KTransform),
+}
+
+impl TransitionValue {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        match (self, other) {
+            (TransitionValue::Float(a), TransitionValue::Float(b)) => {
+                TransitionValue::Float(a.lerp(b, t))
+            }
+            (TransitionValue::Color(a), TransitionValue::Color(b)) => {
+                TransitionValue::Color(a.lerp(b, t))
+            }
+            (TransitionValue::Units(a), TransitionValue::Units(b)) => {
+                TransitionValue::Units(a.lerp(b, t))
+            }
+            (TransitionValue::Corner(a), TransitionValue::Corner(b)) => {
+                TransitionValue::Corner(a.lerp(b, t))
+            }
+            (TransitionValue::Edge(a), TransitionValue::Edge(b)) => {
+                TransitionValue::Edge(a.lerp(b, t))
+            }
+            (TransitionValue::Transform(a), TransitionValue::Transform(b)) => {
+                TransitionValue::Transform(a.lerp(b, t))
+            }
+            _ => *other,
+        }
+    }
+}
+
+fn value_of<T: Default + Clone + FromReflect>(prop: &StyleProp<T>) -> Option<T> {
+    match prop {
+        StyleProp::Value(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+/// Gets the resolved value of a property, if it has one
+fn get_property(styles: &KStyle, property: TransitionProperty) -> Option<TransitionValue> {
+    match property {
+        TransitionProperty::All => None,
+        TransitionProperty::BackgroundColor => {
+            value_of(&styles.background_color).map(TransitionValue::Color)
+        }
+        TransitionProperty::BorderColor => {
+            value_of(&styles.border_color).map(TransitionValue::Color)
+        }
+        TransitionProperty::Color => value_of(&styles.color).map(TransitionValue::Color),
+        TransitionProperty::BorderRadius => {
+            value_of(&styles.border_radius).map(TransitionValue::Corner)
+        }
+        TransitionProperty::Border => value_of(&styles.border).map(TransitionValue::Edge),
+        TransitionProperty::Opacity => value_of(&styles.opacity).map(TransitionValue::Float),
+        TransitionProperty::Transform => {
+            value_of(&styles.transform).map(TransitionValue::Transform)
+        }
+        TransitionProperty::Width => value_of(&styles.width).map(TransitionValue::Units),
+        TransitionProperty::Height => value_of(&styles.height).map(TransitionValue::Units),
+        TransitionProperty::MinWidth => value_of(&styles.min_width).map(TransitionValue::Units),
+        TransitionProperty::MinHeight => value_of(&styles.min_height).map(TransitionValue::Units),
+        TransitionProperty::MaxWidth => value_of(&styles.max_width).map(TransitionValue::Units),
+        TransitionProperty::MaxHeight => value_of(&styles.max_height).map(TransitionValue::Units),
+        TransitionProperty::Left => value_of(&styles.left).map(TransitionValue::Units),
+        TransitionProperty::Right => value_of(&styles.right).map(TransitionValue::Units),
+        TransitionProperty::Top => value_of(&styles.top).map(TransitionValue::Units),
+        TransitionProperty::Bottom => value_of(&styles.bottom).map(TransitionValue::Units),
+    }
+}
+
+/// Overwrites the resolved value of a property
+fn set_property(styles: &mut KStyle, property: TransitionProperty, value: TransitionValue) {
+    match (property, value) {
+        (TransitionProperty::BackgroundColor, TransitionValue::Color(value)) => {
+            styles.background_color = StyleProp::Value(value);
+        }
+        (TransitionProperty::BorderColor, TransitionValue::Color(value)) => {
+            styles.border_color = StyleProp::Value(value);
+        }
+        (TransitionProperty::Color, TransitionValue::Color(value)) => {
+            styles.color = StyleProp::Value(value);
+        }
+        (TransitionProperty::BorderRadius, TransitionValue::Corner(value)) => {
+            styles.border_radius = StyleProp::Value(value);
+        }
+        (TransitionProperty::Border, TransitionValue::Edge(value)) => {
+            styles.border = StyleProp::Value(value);
+        }
+        (TransitionProperty::Opacity, TransitionValue::Float(value)) => {
+            styles.opacity = StyleProp::Value(value);
+        }
+        (TransitionProperty::Transform, TransitionValue::Transform(value)) => {
+            styles.transform = StyleProp::Value(value);
+        }
+        (TransitionProperty::Width, TransitionValue::Units(value)) => {
+            styles.width = StyleProp::Value(value);
+        }
+        (TransitionProperty::Height, TransitionValue::Units(value)) => {
+            styles.height = StyleProp::Value(value);
+        }
+        (TransitionProperty::MinWidth, TransitionValue::Units(value)) => {
+            styles.min_width = StyleProp::Value(value);
+        }
+        (TransitionProperty::MinHeight, TransitionValue::Units(value)) => {
+            styles.min_height = StyleProp::Value(value);
+        }
+        (TransitionProperty::MaxWidth, TransitionValue::Units(value)) => {
+            styles.max_width = StyleProp::Value(value);
+        }
+        (TransitionProperty::MaxHeight, TransitionValue::Units(value)) => {
+            styles.max_height = StyleProp::Value(value);
+        }
+        (TransitionProperty::Left, TransitionValue::Units(value)) => {
+            styles.left = StyleProp::Value(value);
+        }
+        (TransitionProperty::Right, TransitionValue::Units(value)) => {
+            styles.right = StyleProp::Value(value);
+        }
+        (TransitionProperty::Top, TransitionValue::Units(value)) => {
+            styles.top = StyleProp::Value(value);
+        }
+        (TransitionProperty::Bottom, TransitionValue::Units(value)) => {
+            styles.bottom = StyleProp::Value(value);
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Tween {
+    from: TransitionValue,
+    to: TransitionValue,
+    elapsed: f32,
+    spec: TransitionSpec,
+}
+
+impl Tween {
+    fn progress(&self) -> f32 {
+        if self.spec.duration <= f32::EPSILON {
+            1.0
+        } else {
+            self.elapsed / self.spec.duration
+        }
+    }
+
+    fn value(&self) -> TransitionValue {
+        self.from
+            .lerp(&self.to, self.spec.easing.ease(self.progress()))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+}
+
+/// The in-flight style transitions of a widget
+///
+/// Transitions are applied on top of a widget's resolved styles in `calculate_nodes`, so widgets
+/// only ever need to set their target styles and are free to re-render mid-transition.
+#[derive(Component, Default, Debug, Clone)]
+pub(crate) struct StyleTransitions {
+    tweens: HashMap<TransitionProperty, Tween>,
+}
+
+impl StyleTransitions {
+    /// Returns true if any property is currently transitioning
+    pub fn is_animating(&self) -> bool {
+        !self.tweens.is_empty()
+    }
+
+    /// Advances all transitions by `delta` seconds
+    pub fn tick(&mut self, delta: f32) {
+        for tween in self.tweens.values_mut() {
+            tween.elapsed += delta;
+        }
+    }
+
+    /// Starts, retargets, or finishes transitions and writes the current values into `styles`
+    ///
+    /// # Arguments
+    ///
+    /// * `previous`: The resolved styles of the last time this widget was laid out (if any)
+    /// * `styles`: The newly resolved styles, which will be overwritten with in-flight values
+    ///
+    pub fn apply(&mut self, previous: Option<&KStyle>, styles: &mut KStyle) {
+        let transition = styles.transition.resolve_or_default();
+
+        for property in TransitionProperty::PROPERTIES {
+            let target = get_property(styles, property);
+            let (spec, target) = match (transition.get(property), target) {
+                (Some(spec), Some(target)) => (spec, target),
+                _ => {
+                    self.tweens.remove(&property);
+                    continue;
+                }
+            };
+
+            let from = match self.tweens.get(&property) {
+                // Already heading to this value
+                Some(tween) if tween.to == target => None,
+                // Retarget from wherever we currently are
+                Some(tween) => Some(tween.value()),
+                None => previous
+                    .and_then(|previous| get_property(previous, property))
+                    .filter(|previous| *previous != target),
+            };
+
+            if let Some(from) = from {
+                self.tweens.insert(
+                    property,
+                    Tween {
+                        from,
+                        to: target,
+                        elapsed: 0.0,
+                        spec,
+                    },
+                );
+            }
+
+            if let Some(tween) = self.tweens.get(&property) {
+                if tween.is_finished() {
+                    self.tweens.remove(&property);
+                } else {
+                    set_property(styles, property, tween.value());
+                }
+            }
+        }
+    }
+}
+
+/// Advances all style transitions and marks their widgets for re-layout
+pub(crate) fn update_style_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut StyleTransitions)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut transitions) in query.iter_mut() {
+        if transitions.is_animating() {
+            transitions.tick(delta);
+            commands.entity(entity).insert(DirtyNode);
+        }
+    }
+}
+
+/// Whether a mount animation is playing because its widget was added or removed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum MountPhase {
+    Enter,
+    Exit,
+}
+
+/// A mount animation that is currently playing
+#[derive(Debug, Clone)]
+pub(crate) struct ActiveMountAnimation {
+    animation: MountAnimation,
+    phase: MountPhase,
+    elapsed: f32,
+}
+
+impl ActiveMountAnimation {
+    fn progress(&self) -> f32 {
+        if self.animation.duration <= f32::EPSILON {
+            1.0
+        } else {
+            (self.elapsed / self.animation.duration).min(1.0)
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// How "present" the widget is, from `0.0` (fully hidden) to `1.0` (fully shown)
+    fn presence(&self) -> f32 {
+        let t = self.animation.easing.ease(self.progress());
+        match self.phase {
+            MountPhase::Enter => t,
+            MountPhase::Exit => 1.0 - t,
+        }
+    }
+
+    /// The current effects of this animation on a widget with the given layout
+    pub fn effects(&self, layout: &Rect) -> RenderEffects {
+        let presence = self.presence();
+        let mut effects = RenderEffects::default();
+        for effect in self.animation.effects.iter() {
+            let effect = match *effect {
+                AnimationEffect::Fade => RenderEffects::opacity(presence),
+                AnimationEffect::Slide { x, y } => {
+                    RenderEffects::translation(x * (1.0 - presence), y * (1.0 - presence))
+                }
+                AnimationEffect::Scale(factor) => {
+                    let center = Vec2::new(
+                        layout.posx + layout.width / 2.0,
+                        layout.posy + layout.height / 2.0,
+                    );
+                    RenderEffects::scale_around(center, factor.lerp(&1.0, presence))
+                }
+            };
+            effects = effects.then(&effect);
+        }
+        effects
+    }
+}
+
+/// A removed widget that keeps rendering until its exit animation completes
+///
+/// The widget keeps its entity, and its key if it has one, so it can be brought back by adding a
+/// widget with the same key to the same parent.
+#[derive(Debug, Clone)]
+pub(crate) struct ExitingWidget {
+    /// The removed widget
+    pub entity: WrappedIndex,
+    /// The parent the widget was removed from
+    pub parent: WrappedIndex,
+    /// The index the widget had among its parent's children before it was removed
+    pub index: usize,
+    /// A snapshot of the widget's subtree at the time it was removed
+    pub subtree: Tree,
+}
+
+/// The enter and exit animations of all widgets in a context
+#[derive(Debug, Default)]
+pub(crate) struct MountAnimations {
+    active: HashMap<WrappedIndex, ActiveMountAnimation>,
+    exiting: Vec<ExitingWidget>,
+}
+
+impl MountAnimations {
+    /// Returns true if any animation is currently playing
+    pub fn is_animating(&self) -> bool {
+        !self.active.is_empty()
+    }
+
+    /// Starts the enter animation of a newly added widget
+    ///
+    /// This cancels any exit animation the widget had.
+    pub fn enter(&mut self, entity: WrappedIndex, animation: MountAnimation) {
+        self.exiting.retain(|exiting| exiting.entity != entity);
+        self.active.insert(
+            entity,
+            ActiveMountAnimation {
+                animation,
+                phase: MountPhase::Enter,
+                elapsed: 0.0,
+            },
+        );
+    }
+
+    /// Starts the exit animation of a removed widget
+    pub fn exit(&mut self, widget: ExitingWidget, animation: MountAnimation) {
+        self.active.insert(
+            widget.entity,
+            ActiveMountAnimation {
+                animation,
+                phase: MountPhase::Exit,
+                elapsed: 0.0,
+            },
+        );
+        self.exiting
+            .retain(|exiting| exiting.entity != widget.entity);
+        self.exiting.push(widget);
+    }
+
+    /// Stops the exit animation of a removed widget that has been added back
+    ///
+    /// Returns the widget as it was when it was removed, if it was still exiting.
+    pub fn cancel_exit(&mut self, entity: WrappedIndex) -> Option<ExitingWidget> {
+        let index = self
+            .exiting
+            .iter()
+            .position(|exiting| exiting.entity == entity)?;
+        self.active.remove(&entity);
+        Some(self.exiting.remove(index))
+    }
+
+    /// Gets the animation currently playing on the given widget
+    pub fn get(&self, entity: WrappedIndex) -> Option<&ActiveMountAnimation> {
+        self.active.get(&entity)
+    }
+
+    /// Gets the removed (but still exiting) children of the given widget
+    pub fn exiting_children(
+        &self,
+        parent: WrappedIndex,
+    ) -> impl Iterator<Item = &ExitingWidget> + '_ {
+        self.exiting
+            .iter()
+            .filter(move |exiting| exiting.parent == parent)
+    }
+
+    /// Advances all animations by `delta` seconds
+    ///
+    /// Returns the widgets whose exit animation finished.
+    pub fn tick(&mut self, delta: f32) -> Vec<ExitingWidget> {
+        for animation in self.active.values_mut() {
+            animation.elapsed += delta;
+        }
+
+        let finished = self
+            .active
+            .iter()
+            .filter(|(_, animation)| animation.is_finished())
+            .map(|(entity, _)| *entity)
+            .collect::<Vec<_>>();
+
+        let mut exited = Vec::new();
+        for entity in finished {
+            self.active.remove(&entity);
+            if let Some(index) = self
+                .exiting
+                .iter()
+                .position(|exiting| exiting.entity == entity)
+            {
+                exited.push(self.exiting.remove(index));
+            }
+        }
+
+        exited
+    }
+}
+
+/// Advances all mount animations and despawns widgets whose exit animation finished
+///
+/// Render primitives are rebuilt every frame, so in-flight animations don't need to dirty
+/// any nodes.
+pub(crate) fn update_mount_animations(
+    mut commands: Commands,
+    time: Res<Time>,
+    context: Res<Context>,
+) {
+    let exited = if let Ok(mut mount_animations) = context.mount_animations.try_write() {
+        if !mount_animations.is_animating() {
+            return;
+        }
+        mount_animations.tick(time.delta_seconds())
+    } else {
+        return;
+    };
+
+    if exited.is_empty() {
+        return;
+    }
+
+    if let Ok(tree) = context.tree.read() {
+        if let Ok(mut layout_cache) = context.layout_cache.try_write() {
+            for exiting in exited {
+                for node in exiting.subtree.down_iter() {
+                    // The widget may have been re-added in the meantime
+                    if tree.contains(node) {
+                        continue;
+                    }
+                    layout_cache.remove(node);
+                    context.widget_keys.remove(node.0);
+                    if let Some(mut entity_commands) = commands.get_entity(node.0) {
+                        entity_commands.despawn();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        ecs::system::{IntoSystem, System},
+        prelude::{Entity, World},
+        time::Time,
+    };
+    use morphorm::Hierarchy;
+
+    use super::{update_mount_animations, ExitingWidget, MountAnimations, StyleTransitions};
+    use crate::{
+        context::Context,
+        node::WrappedIndex,
+        styles::{Easing, KStyle, MountAnimation, StyleProp, Transition, TransitionProperty},
+        tree::Tree,
+    };
+
+    fn fade_out() -> MountAnimation {
+        MountAnimation::new(1.0, Easing::Linear).fade()
+    }
+
+    /// Removes `child`, and its child, from `parent` with an exit animation
+    fn exiting_widget(world: &mut World) -> (WrappedIndex, WrappedIndex, WrappedIndex) {
+        let parent = WrappedIndex(world.spawn_empty().id());
+        let child = WrappedIndex(world.spawn_empty().id());
+        let grandchild = WrappedIndex(world.spawn_empty().id());
+        let mut subtree = Tree::default();
+        subtree.add(child, None);
+        subtree.add(grandchild, Some(child));
+
+        let mut context = Context::new();
+        context.add_widget(None, parent.0);
+        context
+            .widget_keys
+            .insert(parent.0, child.0, "child".to_string());
+        context.mount_animations.write().unwrap().exit(
+            ExitingWidget {
+                entity: child,
+                parent,
+                index: 0,
+                subtree,
+            },
+            fade_out(),
+        );
+        world.insert_resource(context);
+
+        let mut time = Time::default();
+        let startup = time.startup();
+        time.update_with_instant(startup);
+        world.insert_resource(time);
+
+        (parent, child, grandchild)
+    }
+
+    /// Runs `update_mount_animations` after the given number of seconds
+    fn update_after(world: &mut World, seconds: f32) {
+        {
+            let mut time = world.resource_mut::<Time>();
+            let last_update = time.last_update().unwrap();
+            time.update_with_instant(last_update + Duration::from_secs_f32(seconds));
+        }
+
+        let mut system = IntoSystem::into_system(update_mount_animations);
+        system.initialize(world);
+        system.run((), world);
+        system.apply_buffers(world);
+    }
+
+    fn exiting_children(world: &World, parent: WrappedIndex) -> Vec<WrappedIndex> {
+        world
+            .resource::<Context>()
+            .mount_animations
+            .read()
+            .unwrap()
+            .exiting_children(parent)
+            .map(|exiting| exiting.entity)
+            .collect()
+    }
+
+    #[test]
+    fn exiting_widgets_should_be_despawned_once_their_animation_finishes() {
+        let mut world = World::new();
+        let (parent, child, grandchild) = exiting_widget(&mut world);
+
+        update_after(&mut world, 0.5);
+        assert_eq!(exiting_children(&world, parent), vec![child]);
+        assert!(world.get_entity(child.0).is_some());
+        assert!(world.get_entity(grandchild.0).is_some());
+
+        update_after(&mut world, 0.5);
+        assert!(exiting_children(&world, parent).is_empty());
+        assert!(world.get_entity(child.0).is_none());
+        assert!(world.get_entity(grandchild.0).is_none());
+        assert!(world.get_entity(parent.0).is_some());
+        assert!(world
+            .resource::<Context>()
+            .widget_keys
+            .get(child.0)
+            .is_none());
+    }
+
+    #[test]
+    fn exiting_widgets_should_be_kept_once_added_back() {
+        let mut world = World::new();
+        let (parent, child, _) = exiting_widget(&mut world);
+
+        update_after(&mut world, 0.5);
+        {
+            let context = world.resource::<Context>();
+            context.tree.write().unwrap().add(child, Some(parent));
+            let exiting = context
+                .mount_animations
+                .write()
+                .unwrap()
+                .cancel_exit(child)
+                .unwrap();
+            assert_eq!(exiting.subtree.down_iter().count(), 2);
+        }
+        assert!(exiting_children(&world, parent).is_empty());
+        assert!(world
+            .resource::<Context>()
+            .mount_animations
+            .read()
+            .unwrap()
+            .get(child)
+            .is_none());
+
+        update_after(&mut world, 1.0);
+        assert!(world.get_entity(child.0).is_some());
+    }
+
+    #[test]
+    fn cancelling_should_only_stop_exit_animations() {
+        let mut mount_animations = MountAnimations::default();
+        let entity = WrappedIndex(Entity::from_raw(0));
+        mount_animations.enter(entity, fade_out());
+
+        assert!(mount_animations.cancel_exit(entity).is_none());
+        assert!(mount_animations.get(entity).is_some());
+    }
+
+    /// Styles with the given opacity, which transitions linearly over the given duration
+    fn with_opacity(opacity: f32, duration: f32) -> KStyle {
+        KStyle {
+            opacity: StyleProp::Value(opacity),
+            transition: StyleProp::Value(Transition::new().with(
+                TransitionProperty::Opacity,
+                duration,
+                Easing::Linear,
+            )),
+            ..Default::default()
+        }
+    }
+
+    /// Applies the transitions to the given target styles, returning the resulting opacity
+    fn apply(transitions: &mut StyleTransitions, previous: &KStyle, target: &KStyle) -> f32 {
+        let mut styles = target.clone();
+        transitions.apply(Some(previous), &mut styles);
+        styles.opacity.resolve()
+    }
+
+    #[test]
+    fn transitions_should_retarget_from_their_current_value() {
+        let mut transitions = StyleTransitions::default();
+        let hidden = with_opacity(0.0, 1.0);
+        let shown = with_opacity(1.0, 1.0);
+
+        assert_eq!(apply(&mut transitions, &hidden, &shown), 0.0);
+        transitions.tick(0.5);
+        assert_eq!(apply(&mut transitions, &hidden, &shown), 0.5);
+
+        // Heading back mid-transition starts from where it got to, rather than jumping to the end
+        assert_eq!(apply(&mut transitions, &shown, &hidden), 0.5);
+        transitions.tick(0.5);
+        assert_eq!(apply(&mut transitions, &shown, &hidden), 0.25);
+        assert!(transitions.is_animating());
+    }
+
+    #[test]
+    fn finished_transitions_should_be_removed() {
+        let mut transitions = StyleTransitions::default();
+        let hidden = with_opacity(0.0, 1.0);
+        let shown = with_opacity(1.0, 1.0);
+
+        apply(&mut transitions, &hidden, &shown);
+        assert!(transitions.is_animating());
+
+        transitions.tick(1.0);
+        assert_eq!(apply(&mut transitions, &hidden, &shown), 1.0);
+        assert!(!transitions.is_animating());
+
+        // Once finished, the target value is used as is
+        assert_eq!(apply(&mut transitions, &shown, &shown), 1.0);
+        assert!(!transitions.is_animating());
+    }
+
+    #[test]
+    fn zero_duration_transitions_should_finish_immediately() {
+        let mut transitions = StyleTransitions::default();
+
+        assert_eq!(
+            apply(
+                &mut transitions,
+                &with_opacity(0.0, 0.0),
+                &with_opacity(1.0, 0.0)
+            ),
+            1.0
+        );
+        assert!(!transitions.is_animating());
+    }
+}
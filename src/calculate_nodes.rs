@@ -1,18 +1,32 @@
 use bevy::{
-    prelude::{Assets, Commands, Entity, Query, Res, ResMut, With},
+    math::Affine2,
+    prelude::{Assets, Commands, Entity, Query, Res, ResMut, Vec2, With},
     utils::HashMap,
 };
 use kayak_font::KayakFont;
 
 use crate::{
-    layout::{DataCache, Rect},
-    node::{DirtyNode, Node, NodeBuilder, WrappedIndex},
+    animation::StyleTransitions,
+    layout::{DataCache, LayoutCache, Rect},
+    node::{DerivedSizes, DirtyNode, Node, NodeBuilder, NodeStore, WrappedIndex},
     prelude::{Context, KStyle},
     render::font::FontMapping,
     render_primitive::RenderPrimitive,
-    styles::{StyleProp, Units},
+    styles::{Edge, LayoutType, PositionType, RelativeSizes, StyleProp, Units},
+    tree::Tree,
+    window_size::WindowSize,
 };
 
+/// The z-index added to everything in the overlay layer, placing it above the rest of the tree
+const OVERLAY_Z_INDEX: f32 = 1_000_000.0;
+
+/// The font size used when none is set, matching the one used to render text
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+
+/// The maximum number of extra layout passes used to settle sizes that depend on an earlier
+/// layout, such as those derived from aspect ratios or the lines of wrapping rows
+const MAX_DERIVED_SIZE_PASSES: usize = 4;
+
 pub fn calculate_nodes(
     mut commands: Commands,
     mut context: ResMut<Context>,
@@ -22,6 +36,8 @@ pub fn calculate_nodes(
     all_styles_query: Query<&KStyle>,
     node_query: Query<(Entity, &Node)>,
     nodes_no_entity_query: Query<&'static Node>,
+    mut transitions_query: Query<&mut StyleTransitions>,
+    window_size: Res<WindowSize>,
 ) {
     let mut new_nodes = HashMap::<Entity, (Node, bool)>::default();
     // This is the maximum recursion depth for this method.
@@ -38,6 +54,43 @@ pub fn calculate_nodes(
     //     return;
     // }
     if let Ok(tree) = context.tree.clone().read() {
+        let viewport = Vec2::new(window_size.0, window_size.1);
+        // Viewport units and breakpoints need to be resolved again whenever the window is resized
+        if context.window_size != *window_size {
+            let previous_viewport = Vec2::new(context.window_size.0, context.window_size.1);
+            context.window_size = *window_size;
+            for (entity, node) in node_query.iter() {
+                if node.raw_styles.as_ref().map_or(false, |styles| {
+                    styles.depends_on_viewport(previous_viewport, viewport)
+                }) {
+                    commands.entity(entity).insert(DirtyNode);
+                }
+            }
+        }
+        // Taken from the root's own styles, since its node may not have been resolved yet
+        let root_font_size = tree
+            .root_node
+            .and_then(|root| all_styles_query.get(root.0).ok())
+            .map_or(DEFAULT_FONT_SIZE, |styles| {
+                styles
+                    .with_breakpoints(viewport)
+                    .font_size
+                    .resolve_or(DEFAULT_FONT_SIZE)
+            });
+        // Rem units need to be resolved again whenever the root font size changes
+        if context.root_font_size != root_font_size {
+            context.root_font_size = root_font_size;
+            for (entity, node) in node_query.iter() {
+                if node
+                    .raw_styles
+                    .as_ref()
+                    .map_or(false, |styles| styles.uses_root_font_size())
+                {
+                    commands.entity(entity).insert(DirtyNode);
+                }
+            }
+        }
+
         for dirty_entity in query.iter() {
             let dirty_entity = WrappedIndex(dirty_entity);
             let styles = all_styles_query
@@ -84,18 +137,40 @@ pub fn calculate_nodes(
             };
 
             let raw_styles = styles.clone();
-            let mut styles = raw_styles.clone();
+            // Apply any breakpoints matching the current viewport
+            let mut styles = raw_styles.with_breakpoints(viewport);
             // Fill in all `initial` values for any unset property
             styles.apply(&initial_styles);
             // Fill in all `inherited` values for any `inherit` property
             styles.inherit(&parent_styles);
+            // Convert units relative to the viewport or font size into pixels
+            styles.resolve_relative_units(&RelativeSizes {
+                viewport,
+                font_size: styles.font_size.resolve_or(DEFAULT_FONT_SIZE),
+                root_font_size,
+            });
+
+            // Swap in the in-flight values of any transitioning property
+            let previous_styles = node_query
+                .get(dirty_entity.0)
+                .ok()
+                .map(|(_, node)| &node.resolved_styles);
+            if let Ok(mut transitions) = transitions_query.get_mut(dirty_entity.0) {
+                transitions.apply(previous_styles, &mut styles);
+            } else if matches!(styles.transition, StyleProp::Value(_)) {
+                let mut transitions = StyleTransitions::default();
+                transitions.apply(previous_styles, &mut styles);
+                if transitions.is_animating() {
+                    commands.entity(dirty_entity.0).insert(transitions);
+                }
+            }
 
             let (primitive, needs_layout) = create_primitive(
                 &mut commands,
                 &context,
                 &fonts,
                 &font_mapping,
-                // &node_query,
+                &node_query,
                 dirty_entity,
                 &mut styles,
             );
@@ -156,14 +231,21 @@ pub fn calculate_nodes(
             if let Ok(tree) = context.tree.try_read() {
                 let node_tree = &*tree;
                 if let Ok(mut cache) = context.layout_cache.try_write() {
-                    let mut data_cache = DataCache {
-                        cache: &mut cache,
-                        query: &nodes_no_entity_query,
-                    };
-
                     // dbg!(&node_tree);
 
-                    morphorm::layout(&mut data_cache, node_tree, &nodes_no_entity_query);
+                    layout_with_derived_sizes(node_tree, &mut cache, &nodes_no_entity_query);
+                    apply_wrapping(node_tree, &mut cache, &nodes_no_entity_query);
+                    apply_stacking_and_transforms(node_tree, &mut cache, &nodes_no_entity_query);
+                }
+            }
+        }
+
+        // Text wraps to the space available to it, so re-measure any text whose space has changed
+        for (entity, node) in node_query.iter() {
+            if let RenderPrimitive::Text { properties, .. } = &node.primitive {
+                let bounds = get_text_bounds(&context, &tree, &node_query, WrappedIndex(entity));
+                if bounds.map_or(false, |bounds| bounds != properties.max_size) {
+                    commands.entity(entity).insert(DirtyNode);
                 }
             }
         }
@@ -171,12 +253,416 @@ pub fn calculate_nodes(
     }
 }
 
+/// Lays out the tree, then lays it out again until the sizes derived from aspect ratios and the
+/// lines of wrapping rows settle
+///
+/// Those sizes affect the layout of everything around them, and nested ones need another pass
+/// each, so this gives up after [`MAX_DERIVED_SIZE_PASSES`]. Layout starts from the sizes derived
+/// by the last layout, so only needs another pass when one of them has changed. Returns the number
+/// of extra passes.
+fn layout_with_derived_sizes(tree: &Tree, cache: &mut LayoutCache, nodes: &Query<&Node>) -> usize {
+    let mut store = NodeStore::new(tree, nodes);
+    store.derived = std::mem::take(&mut cache.derived_sizes);
+    let mut data_cache = DataCache {
+        cache,
+        query: nodes,
+    };
+    morphorm::layout(&mut data_cache, tree, &store);
+
+    let mut passes = 0;
+    while passes < MAX_DERIVED_SIZE_PASSES {
+        let (widths, heights) = get_wrapped_sizes(tree, data_cache.cache, nodes);
+        let derived = DerivedSizes {
+            sizes: get_aspect_ratio_sizes(tree, data_cache.cache, nodes),
+            widths,
+            heights,
+        };
+        if derived == store.derived {
+            break;
+        }
+        store.derived = derived;
+        morphorm::layout(&mut data_cache, tree, &store);
+        passes += 1;
+    }
+    data_cache.cache.derived_sizes = store.derived;
+    passes
+}
+
+/// Finds the size of every widget with an aspect ratio, based on the space given to it by layout
+fn get_aspect_ratio_sizes(
+    tree: &Tree,
+    cache: &LayoutCache,
+    nodes: &Query<&Node>,
+) -> HashMap<WrappedIndex, (f32, f32)> {
+    let mut sizes = HashMap::default();
+    for node in nodes.iter() {
+        let styles = &node.resolved_styles;
+        let ratio = match styles.aspect_ratio {
+            StyleProp::Value(ratio) if ratio > 0.0 => ratio,
+            _ => continue,
+        };
+        if !node.is_displayed() {
+            continue;
+        }
+        let layout = match cache.rect.get(&node.id) {
+            Some(layout) => *layout,
+            None => continue,
+        };
+        let parent = tree
+            .get_parent(node.id)
+            .and_then(|parent| cache.rect.get(&parent).copied())
+            .unwrap_or(layout);
+
+        let resolve = |units: &StyleProp<Units>, parent_size: f32, default: f32| {
+            units.resolve_or(Units::Auto).value_or(parent_size, default)
+        };
+        let min_width = resolve(&styles.min_width, parent.width, 0.0);
+        let max_width = resolve(&styles.max_width, parent.width, f32::MAX);
+        let min_height = resolve(&styles.min_height, parent.height, 0.0);
+        let max_height = resolve(&styles.max_height, parent.height, f32::MAX);
+
+        let fit_width = styles.width == StyleProp::Value(Units::Auto);
+        let fit_height = styles.height == StyleProp::Value(Units::Auto);
+        let (mut width, mut height) = (layout.width, layout.height);
+        if fit_height && !fit_width {
+            height = width / ratio;
+        } else if fit_width && !fit_height {
+            width = height * ratio;
+        } else if width > height * ratio {
+            width = height * ratio;
+        } else {
+            height = width / ratio;
+        }
+
+        // The constraints take precedence, though the other dimension keeps the ratio if it can
+        let clamped = height.max(min_height).min(max_height);
+        if clamped != height {
+            height = clamped;
+            width = (height * ratio).max(min_width).min(max_width);
+        }
+        let clamped = width.max(min_width).min(max_width);
+        if clamped != width {
+            width = clamped;
+            height = (width / ratio).max(min_height).min(max_height);
+        }
+
+        sizes.insert(node.id, (width, height));
+    }
+    sizes
+}
+
+/// A child of a wrapping row, measured for breaking the row into lines
+struct WrappedChild {
+    id: WrappedIndex,
+    /// The layout of the child before wrapping
+    rect: Rect,
+    /// The spacing before the child, if it doesn't use the spacing between the row's children
+    left: Option<f32>,
+    /// The spacing after the child
+    right: f32,
+    /// The width used to break lines, which is the minimum width of stretched children
+    width: f32,
+    /// The stretch factor of stretched children
+    stretch: Option<f32>,
+    /// The width stretched children can't grow past
+    max_width: f32,
+}
+
+/// The children of a wrapping row, broken into lines
+struct WrappedRow {
+    /// The height needed to fit every line, if the row is sized to fit its children
+    height: Option<f32>,
+    /// The layout of each child on its line
+    children: Vec<(WrappedIndex, Rect)>,
+    /// The widths given to stretched children, which share the space left on their line
+    stretched: Vec<(WrappedIndex, f32)>,
+}
+
+/// Breaks the children of a wrapping row onto new lines wherever they overflow it
+///
+/// Returns `None` if the widget isn't a wrapping row or hasn't been laid out yet.
+fn wrap_row(
+    tree: &Tree,
+    cache: &LayoutCache,
+    nodes: &Query<&Node>,
+    parent: WrappedIndex,
+) -> Option<WrappedRow> {
+    let styles = &nodes.get(parent.0).ok()?.resolved_styles;
+    if !styles.wrap.resolve() || styles.layout_type.resolve() != LayoutType::Row {
+        return None;
+    }
+    let layout = *cache.rect.get(&parent)?;
+
+    let padding = styles.padding.resolve_or(Edge::all(Units::Auto));
+    let padding_top = styles
+        .padding_top
+        .resolve_or(padding.top)
+        .value_or(layout.height, 0.0);
+    let padding_right = styles
+        .padding_right
+        .resolve_or(padding.right)
+        .value_or(layout.width, 0.0);
+    let padding_bottom = styles
+        .padding_bottom
+        .resolve_or(padding.bottom)
+        .value_or(layout.height, 0.0);
+    let col_between = styles
+        .col_between
+        .resolve_or(Units::Auto)
+        .value_or(layout.width, 0.0);
+    let row_between = styles
+        .row_between
+        .resolve_or(Units::Auto)
+        .value_or(layout.height, 0.0);
+    let max_x = layout.posx + layout.width - padding_right;
+
+    let children = tree
+        .children
+        .get(&parent)
+        .into_iter()
+        .flatten()
+        .filter_map(|child| {
+            let node = nodes.get(child.0).ok()?;
+            let child_styles = &node.resolved_styles;
+            if !node.is_displayed()
+                || child_styles.position_type.resolve() != PositionType::ParentDirected
+            {
+                return None;
+            }
+            let rect = *cache.rect.get(child)?;
+
+            let spacing = |units: &StyleProp<Units>| match units.resolve_or(Units::Auto) {
+                Units::Auto | Units::Stretch(_) => None,
+                units => Some(units.value_or(layout.width, 0.0)),
+            };
+            let resolve = |units: &StyleProp<Units>, default: f32| {
+                units
+                    .resolve_or(Units::Auto)
+                    .value_or(layout.width, default)
+            };
+            // Stretched children only need their minimum width, and grow to fill their line
+            let (width, stretch) = match child_styles.width.resolve_or(Units::Stretch(1.0)) {
+                Units::Stretch(factor) => (resolve(&child_styles.min_width, 0.0), Some(factor)),
+                _ => (rect.width, None),
+            };
+            Some(WrappedChild {
+                id: *child,
+                rect,
+                left: spacing(&child_styles.left),
+                right: spacing(&child_styles.right).unwrap_or(0.0),
+                width,
+                stretch,
+                max_width: resolve(&child_styles.max_width, f32::MAX),
+            })
+        })
+        .collect::<Vec<_>>();
+    let first = children.first()?;
+    let start_x = first.rect.posx - first.left.unwrap_or(0.0);
+    let start_y = first.rect.posy;
+    let before = |child: &WrappedChild, starts_line: bool| match child.left {
+        Some(left) => left,
+        None if starts_line => 0.0,
+        None => col_between,
+    };
+
+    let mut lines: Vec<Vec<&WrappedChild>> = Vec::new();
+    let mut x = start_x;
+    for child in children.iter() {
+        match lines.last_mut() {
+            Some(line) if x + before(child, false) + child.width + child.right <= max_x => {
+                x += before(child, false) + child.width + child.right;
+                line.push(child);
+            }
+            _ => {
+                x = start_x + before(child, true) + child.width + child.right;
+                lines.push(vec![child]);
+            }
+        }
+    }
+
+    let mut row = WrappedRow {
+        height: None,
+        children: Vec::with_capacity(children.len()),
+        stretched: Vec::new(),
+    };
+    let mut row_y = start_y;
+    let mut line_height = 0.0_f32;
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            row_y += line_height + row_between;
+            line_height = 0.0;
+        }
+
+        let used = line
+            .iter()
+            .enumerate()
+            .map(|(index, child)| before(child, index == 0) + child.width + child.right)
+            .sum::<f32>();
+        let free = (max_x - start_x - used).max(0.0);
+        let stretch = line.iter().filter_map(|child| child.stretch).sum::<f32>();
+
+        let mut x = start_x;
+        for (index, child) in line.iter().enumerate() {
+            let width = match child.stretch {
+                Some(factor) if stretch > 0.0 => {
+                    let width = (child.width + free * factor / stretch).min(child.max_width);
+                    row.stretched.push((child.id, width));
+                    width
+                }
+                _ => child.width,
+            };
+            x += before(child, index == 0);
+            row.children.push((
+                child.id,
+                Rect {
+                    posx: x,
+                    posy: row_y + child.rect.posy - start_y,
+                    width,
+                    ..child.rect
+                },
+            ));
+            x += width + child.right;
+            line_height = line_height.max(child.rect.posy - start_y + child.rect.height);
+        }
+    }
+
+    if styles.height == StyleProp::Value(Units::Auto) {
+        row.height = Some(padding_top + row_y - start_y + line_height + padding_bottom);
+    }
+    Some(row)
+}
+
+/// Finds the widths of stretched children of wrapping rows and the heights of the auto-sized
+/// rows, so that layout can make room for every line
+fn get_wrapped_sizes(
+    tree: &Tree,
+    cache: &LayoutCache,
+    nodes: &Query<&Node>,
+) -> (HashMap<WrappedIndex, f32>, HashMap<WrappedIndex, f32>) {
+    let mut widths = HashMap::default();
+    let mut heights = HashMap::default();
+    for node in nodes.iter() {
+        let row = match wrap_row(tree, cache, nodes, node.id) {
+            Some(row) => row,
+            None => continue,
+        };
+        if let Some(height) = row.height {
+            heights.insert(node.id, height);
+        }
+        widths.extend(row.stretched);
+    }
+    (widths, heights)
+}
+
+/// Moves the children of wrapping rows onto the lines they were broken into
+///
+/// Layout has already made room for the lines, but places the children in a single row.
+fn apply_wrapping(tree: &Tree, cache: &mut LayoutCache, nodes: &Query<&Node>) {
+    let root = match tree.root_node {
+        Some(root) => root,
+        None => return,
+    };
+
+    // Parents are visited before their children, so nested wrapping rows see their final position
+    for parent in tree.flatten_node(root) {
+        let row = match wrap_row(tree, cache, nodes, parent) {
+            Some(row) => row,
+            None => continue,
+        };
+
+        for (child, wrapped) in row.children {
+            let rect = match cache.rect.get_mut(&child) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            let delta = Vec2::new(wrapped.posx - rect.posx, wrapped.posy - rect.posy);
+            rect.width = wrapped.width;
+            if delta != Vec2::ZERO {
+                for node in tree.flatten_node(child) {
+                    if let Some(rect) = cache.rect.get_mut(&node) {
+                        rect.posx += delta.x;
+                        rect.posy += delta.y;
+                    }
+                }
+            }
+        }
+
+        if let Some(height) = row.height {
+            if let Some(layout) = cache.rect.get_mut(&parent) {
+                layout.height = height;
+            }
+        }
+    }
+}
+
+/// Moves overlaid widgets next to their anchors, then stores the stacking order and the composed
+/// transform of every widget, so that hit-testing matches how widgets are rendered
+fn apply_stacking_and_transforms(tree: &Tree, cache: &mut LayoutCache, nodes: &Query<&Node>) {
+    let root = match tree.root_node {
+        Some(root) => root,
+        None => return,
+    };
+
+    let mut stack = vec![(root, 0.0, false, Affine2::IDENTITY)];
+    while let Some((current, parent_z_index, parent_in_overlay, parent_transform)) = stack.pop() {
+        let mut z_index = parent_z_index;
+        let mut in_overlay = parent_in_overlay;
+        let mut transform = parent_transform;
+        if let Ok(node) = nodes.get(current.0) {
+            let styles = &node.resolved_styles;
+            if let StyleProp::Value(overlay) = &styles.overlay {
+                in_overlay = true;
+                z_index = OVERLAY_Z_INDEX + styles.z_index.resolve() as f32;
+
+                let anchor = overlay
+                    .anchor
+                    .and_then(|anchor| cache.rect.get(&WrappedIndex(anchor)).copied());
+                let layout = cache.rect.get(&current).copied();
+                if let (Some(anchor), Some(layout)) = (anchor, layout) {
+                    let position = overlay.position(&anchor, layout.width, layout.height);
+                    let delta = position - Vec2::new(layout.posx, layout.posy);
+                    for node in tree.flatten_node(current) {
+                        if let Some(rect) = cache.rect.get_mut(&node) {
+                            rect.posx += delta.x;
+                            rect.posy += delta.y;
+                        }
+                    }
+                }
+            } else if let StyleProp::Value(value) = styles.z_index {
+                let base = if in_overlay { OVERLAY_Z_INDEX } else { 0.0 };
+                z_index = base + value as f32;
+            }
+
+            if let StyleProp::Value(node_transform) = styles.transform {
+                if let Some(layout) = cache.rect.get(&current) {
+                    transform = parent_transform * node_transform.to_affine(layout);
+                }
+            }
+        }
+
+        if let Some(rect) = cache.rect.get_mut(&current) {
+            rect.z_index = z_index;
+        }
+        if transform == Affine2::IDENTITY {
+            cache.transforms.remove(&current);
+        } else {
+            cache.transforms.insert(current, transform);
+        }
+
+        if let Some(children) = tree.children.get(&current) {
+            for child in children {
+                stack.push((*child, z_index, in_overlay, transform));
+            }
+        }
+    }
+}
+
 fn create_primitive(
     commands: &mut Commands,
     context: &Context,
     fonts: &Assets<KayakFont>,
     font_mapping: &FontMapping,
-    // query: &Query<(Entity, &Node)>,
+    query: &Query<(Entity, &Node)>,
     id: WrappedIndex,
     styles: &mut KStyle,
 ) -> (RenderPrimitive, bool) {
@@ -196,23 +682,20 @@ fn create_primitive(
             if let Some(font) = fonts.get(&font_handle) {
                 // self.bind(id, &asset);
                 if let Ok(node_tree) = context.tree.try_read() {
-                    if let Some(parent_id) = node_tree.get_parent(id) {
-                        if let Some(parent_layout) = context.get_layout(&parent_id) {
-                            properties.max_size = (parent_layout.width, parent_layout.height);
-
-                            // --- Calculate Text Layout --- //
-                            *text_layout = font.measure(&content, *properties);
-                            let measurement = text_layout.size();
-
-                            // --- Apply Layout --- //
-                            if matches!(styles.width, StyleProp::Default) {
-                                styles.width = StyleProp::Value(Units::Pixels(measurement.0));
-                            }
-                            if matches!(styles.height, StyleProp::Default) {
-                                styles.height = StyleProp::Value(Units::Pixels(measurement.1));
-                            }
-                        } else {
-                            needs_layout = true;
+                    if let Some(max_size) = get_text_bounds(context, &node_tree, query, id) {
+                        properties.max_size = max_size;
+
+                        // --- Calculate Text Layout --- //
+                        *text_layout = font.measure(&content, *properties);
+                        let measurement = text_layout.size();
+
+                        // --- Apply Layout --- //
+                        // Text fits its content unless given an explicit size
+                        if is_auto(&styles.width) {
+                            styles.width = StyleProp::Value(Units::Pixels(measurement.0));
+                        }
+                        if is_auto(&styles.height) {
+                            styles.height = StyleProp::Value(Units::Pixels(measurement.1));
                         }
                     } else {
                         needs_layout = true;
@@ -234,6 +717,51 @@ fn create_primitive(
     (render_primitive, needs_layout)
 }
 
+/// Returns true if the given size is unset or [`Units::Auto`] (i.e. it should fit its content)
+fn is_auto(size: &StyleProp<Units>) -> bool {
+    matches!(size, StyleProp::Default | StyleProp::Value(Units::Auto))
+}
+
+/// Gets the space available to the text of the given node
+///
+/// This is the size of the nearest ancestor (on each axis) that isn't itself sized to fit its
+/// content, since those would otherwise only ever shrink to fit the text.
+fn get_text_bounds(
+    context: &Context,
+    tree: &Tree,
+    query: &Query<(Entity, &Node)>,
+    id: WrappedIndex,
+) -> Option<(f32, f32)> {
+    let mut width = None;
+    let mut height = None;
+    let mut current = tree.get_parent(id);
+    while let Some(ancestor) = current {
+        let layout = context.get_layout(&ancestor)?;
+        let styles = query
+            .get(ancestor.0)
+            .ok()
+            .map(|(_, node)| &node.resolved_styles);
+        let is_root = tree.get_parent(ancestor).is_none();
+        if width.is_none()
+            && (is_root
+                || !matches!(styles, Some(styles) if styles.width == StyleProp::Value(Units::Auto)))
+        {
+            width = Some(layout.width);
+        }
+        if height.is_none()
+            && (is_root
+                || !matches!(styles, Some(styles) if styles.height == StyleProp::Value(Units::Auto)))
+        {
+            height = Some(layout.height);
+        }
+        if let (Some(width), Some(height)) = (width, height) {
+            return Some((width, height));
+        }
+        current = tree.get_parent(ancestor);
+    }
+    None
+}
+
 // pub fn build_nodes_tree(context: &mut Context, tree: &Tree, node_query: &Query<(Entity, &Node)>) {
 //     if tree.root_node.is_none() {
 //         return;
@@ -319,3 +847,803 @@ fn create_primitive(
 
 //     None
 // }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::AssetPlugin,
+        ecs::system::SystemState,
+        prelude::{AddAsset, App, Entity, IntoSystem, Query, System, Vec2, World},
+    };
+    use kayak_font::KayakFont;
+
+    use super::{
+        apply_stacking_and_transforms, apply_wrapping, calculate_nodes, get_aspect_ratio_sizes,
+        get_text_bounds, layout_with_derived_sizes, MAX_DERIVED_SIZE_PASSES, OVERLAY_Z_INDEX,
+    };
+    use crate::{
+        layout::{DataCache, LayoutCache, Rect},
+        node::{DirtyNode, Node, NodeBuilder, NodeStore, WrappedIndex},
+        prelude::{Context, KStyle},
+        render::font::FontMapping,
+        styles::{Edge, LayoutType, Overlay, Placement, StyleProp, Units},
+        tree::Tree,
+        window_size::WindowSize,
+    };
+
+    /// A widget tree whose nodes are spawned into a world, along with their layouts
+    #[derive(Default)]
+    struct TestNodes {
+        world: World,
+        tree: Tree,
+        cache: LayoutCache,
+    }
+
+    impl TestNodes {
+        fn add(
+            &mut self,
+            parent: Option<WrappedIndex>,
+            styles: KStyle,
+            layout: Rect,
+        ) -> WrappedIndex {
+            let id = WrappedIndex(self.world.spawn_empty().id());
+            // Resolve the styles like they would be before layout
+            let mut styles = styles;
+            styles.apply(&KStyle::initial());
+            let node = NodeBuilder::new(id, styles).build();
+            self.world.entity_mut(id.0).insert(node);
+            self.tree.add(id, parent);
+            self.cache.add(id);
+            self.cache.rect.insert(id, layout);
+            id
+        }
+
+        /// Runs the given layout step over the nodes
+        fn apply<R>(
+            &mut self,
+            step: impl FnOnce(&Tree, &mut LayoutCache, &Query<&Node>) -> R,
+        ) -> R {
+            let mut state = SystemState::<Query<&'static Node>>::new(&mut self.world);
+            let query = state.get(&self.world);
+            step(&self.tree, &mut self.cache, &query)
+        }
+
+        fn layout(&self, id: WrappedIndex) -> Rect {
+            self.cache.rect[&id]
+        }
+    }
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    fn text_bounds(nodes: &mut TestNodes, id: WrappedIndex) -> Option<(f32, f32)> {
+        let context = Context::new();
+        *context.layout_cache.write().unwrap() = std::mem::take(&mut nodes.cache);
+        let mut state = SystemState::<Query<(Entity, &'static Node)>>::new(&mut nodes.world);
+        let query = state.get(&nodes.world);
+        get_text_bounds(&context, &nodes.tree, &query, id)
+    }
+
+    #[test]
+    fn text_bounds_should_skip_auto_sized_ancestors() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let panel = nodes.add(
+            Some(root),
+            KStyle {
+                height: StyleProp::Value(Units::Auto),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 200.0, 20.0),
+        );
+        let button = nodes.add(
+            Some(panel),
+            KStyle {
+                width: StyleProp::Value(Units::Auto),
+                height: StyleProp::Value(Units::Auto),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 50.0, 20.0),
+        );
+        let text = nodes.add(Some(button), KStyle::default(), rect(0.0, 0.0, 50.0, 20.0));
+
+        // The width comes from the panel and the height from the root
+        assert_eq!(text_bounds(&mut nodes, text), Some((200.0, 300.0)));
+    }
+
+    #[test]
+    fn text_bounds_should_use_auto_sized_root() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(
+            None,
+            KStyle {
+                width: StyleProp::Value(Units::Auto),
+                height: StyleProp::Value(Units::Auto),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 400.0, 300.0),
+        );
+        let text = nodes.add(Some(root), KStyle::default(), rect(0.0, 0.0, 50.0, 20.0));
+
+        assert_eq!(text_bounds(&mut nodes, text), Some((400.0, 300.0)));
+    }
+
+    #[test]
+    fn text_bounds_should_wait_for_layout() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let text = nodes.add(Some(root), KStyle::default(), rect(0.0, 0.0, 50.0, 20.0));
+        nodes.cache.rect.remove(&root);
+
+        assert_eq!(text_bounds(&mut nodes, text), None);
+        assert_eq!(text_bounds(&mut nodes, root), None);
+    }
+
+    #[test]
+    fn auto_sized_container_should_fit_its_children() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(
+            None,
+            KStyle {
+                layout_type: StyleProp::Value(LayoutType::Column),
+                width: StyleProp::Value(Units::Pixels(400.0)),
+                height: StyleProp::Value(Units::Pixels(300.0)),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 400.0, 300.0),
+        );
+        // Like a themed button: auto-sized, with padding around its content
+        let container = nodes.add(
+            Some(root),
+            KStyle {
+                layout_type: StyleProp::Value(LayoutType::Column),
+                width: StyleProp::Value(Units::Pixels(100.0)),
+                height: StyleProp::Value(Units::Auto),
+                padding: StyleProp::Value(Edge::all(Units::Pixels(5.0))),
+                ..Default::default()
+            },
+            Rect::default(),
+        );
+        for height in [20.0, 30.0] {
+            nodes.add(
+                Some(container),
+                KStyle {
+                    width: StyleProp::Value(Units::Pixels(40.0)),
+                    height: StyleProp::Value(Units::Pixels(height)),
+                    ..Default::default()
+                },
+                Rect::default(),
+            );
+        }
+
+        nodes.apply(|tree, cache, query| {
+            let mut data_cache = DataCache { cache, query };
+            let store = NodeStore::new(tree, query);
+            morphorm::layout(&mut data_cache, tree, &store);
+        });
+
+        let layout = nodes.layout(container);
+        assert_eq!((layout.width, layout.height), (100.0, 60.0));
+    }
+
+    fn aspect_ratio(ratio: f32, width: Units, height: Units) -> KStyle {
+        KStyle {
+            aspect_ratio: StyleProp::Value(ratio),
+            width: StyleProp::Value(width),
+            height: StyleProp::Value(height),
+            ..Default::default()
+        }
+    }
+
+    fn aspect_ratio_size(nodes: &mut TestNodes, id: WrappedIndex) -> (f32, f32) {
+        let sizes = nodes.apply(|tree, cache, query| get_aspect_ratio_sizes(tree, cache, query));
+        sizes[&id]
+    }
+
+    #[test]
+    fn aspect_ratio_should_size_the_fitted_dimension() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let stretched = nodes.add(
+            Some(root),
+            aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto),
+            rect(0.0, 0.0, 400.0, 0.0),
+        );
+        let percentage = nodes.add(
+            Some(root),
+            aspect_ratio(0.5, Units::Auto, Units::Percentage(50.0)),
+            rect(0.0, 0.0, 0.0, 150.0),
+        );
+        // With both sizes given, the widget fits within them
+        let contained = nodes.add(
+            Some(root),
+            aspect_ratio(2.0, Units::Percentage(50.0), Units::Stretch(1.0)),
+            rect(0.0, 0.0, 200.0, 300.0),
+        );
+
+        assert_eq!(aspect_ratio_size(&mut nodes, stretched), (400.0, 200.0));
+        assert_eq!(aspect_ratio_size(&mut nodes, percentage), (75.0, 150.0));
+        assert_eq!(aspect_ratio_size(&mut nodes, contained), (200.0, 100.0));
+    }
+
+    #[test]
+    fn aspect_ratio_should_respect_min_and_max_sizes() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let max_height = nodes.add(
+            Some(root),
+            KStyle {
+                max_height: StyleProp::Value(Units::Pixels(100.0)),
+                ..aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto)
+            },
+            rect(0.0, 0.0, 400.0, 0.0),
+        );
+        // Percentages are relative to the parent
+        let max_width = nodes.add(
+            Some(root),
+            KStyle {
+                max_width: StyleProp::Value(Units::Percentage(25.0)),
+                ..aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto)
+            },
+            rect(0.0, 0.0, 400.0, 0.0),
+        );
+        // The constraints win over the ratio when they can't both be met
+        let conflicting = nodes.add(
+            Some(root),
+            KStyle {
+                min_height: StyleProp::Value(Units::Pixels(50.0)),
+                max_width: StyleProp::Value(Units::Pixels(40.0)),
+                ..aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto)
+            },
+            rect(0.0, 0.0, 400.0, 0.0),
+        );
+
+        assert_eq!(aspect_ratio_size(&mut nodes, max_height), (200.0, 100.0));
+        assert_eq!(aspect_ratio_size(&mut nodes, max_width), (100.0, 50.0));
+        assert_eq!(aspect_ratio_size(&mut nodes, conflicting), (40.0, 50.0));
+    }
+
+    #[test]
+    fn nested_aspect_ratios_should_stop_after_max_passes() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(
+            None,
+            KStyle {
+                width: StyleProp::Value(Units::Pixels(400.0)),
+                height: StyleProp::Value(Units::Pixels(300.0)),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 400.0, 300.0),
+        );
+        // Each level is sized from the ratio of its parent, so needs a pass of its own
+        let mut levels = vec![];
+        let mut parent = root;
+        for level in 0..MAX_DERIVED_SIZE_PASSES * 2 {
+            let styles = if level % 2 == 0 {
+                aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto)
+            } else {
+                aspect_ratio(0.5, Units::Auto, Units::Stretch(1.0))
+            };
+            parent = nodes.add(Some(parent), styles, Rect::default());
+            levels.push(parent);
+        }
+
+        let passes = nodes.apply(layout_with_derived_sizes);
+        assert_eq!(passes, MAX_DERIVED_SIZE_PASSES);
+
+        // The outermost levels have settled
+        let size = |id| {
+            let layout = nodes.layout(id);
+            (layout.width, layout.height)
+        };
+        assert_eq!(size(levels[0]), (400.0, 200.0));
+        assert_eq!(size(levels[1]), (100.0, 200.0));
+    }
+
+    #[test]
+    fn aspect_ratios_should_settle_in_one_pass() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(
+            None,
+            KStyle {
+                width: StyleProp::Value(Units::Pixels(400.0)),
+                height: StyleProp::Value(Units::Pixels(300.0)),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 400.0, 300.0),
+        );
+        let child = nodes.add(
+            Some(root),
+            aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto),
+            Rect::default(),
+        );
+
+        assert_eq!(nodes.apply(layout_with_derived_sizes), 1);
+        let layout = nodes.layout(child);
+        assert_eq!((layout.width, layout.height), (400.0, 200.0));
+    }
+
+    #[test]
+    fn unchanged_aspect_ratios_should_not_need_another_pass() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(
+            None,
+            KStyle {
+                width: StyleProp::Value(Units::Pixels(400.0)),
+                height: StyleProp::Value(Units::Pixels(300.0)),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 400.0, 300.0),
+        );
+        let child = nodes.add(
+            Some(root),
+            aspect_ratio(2.0, Units::Stretch(1.0), Units::Auto),
+            Rect::default(),
+        );
+
+        assert_eq!(nodes.apply(layout_with_derived_sizes), 1);
+        // The next layout starts from the sizes derived by this one
+        assert_eq!(nodes.apply(layout_with_derived_sizes), 0);
+        let layout = nodes.layout(child);
+        assert_eq!((layout.width, layout.height), (400.0, 200.0));
+
+        // Sizes derived for removed nodes are forgotten
+        nodes.cache.remove(child);
+        assert!(nodes.cache.derived_sizes.sizes.is_empty());
+    }
+
+    fn wrapping_row(styles: KStyle) -> KStyle {
+        KStyle {
+            layout_type: StyleProp::Value(LayoutType::Row),
+            wrap: StyleProp::Value(true),
+            ..styles
+        }
+    }
+
+    fn sized(width: f32, height: f32) -> KStyle {
+        KStyle {
+            width: StyleProp::Value(Units::Pixels(width)),
+            height: StyleProp::Value(Units::Pixels(height)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wrapping_should_move_overflowing_children_to_new_rows() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let row = nodes.add(
+            Some(root),
+            wrapping_row(KStyle {
+                height: StyleProp::Value(Units::Auto),
+                padding: StyleProp::Value(Edge::all(Units::Pixels(10.0))),
+                col_between: StyleProp::Value(Units::Pixels(10.0)),
+                row_between: StyleProp::Value(Units::Pixels(5.0)),
+                ..Default::default()
+            }),
+            rect(0.0, 0.0, 200.0, 40.0),
+        );
+        // Laid out in a single row, like morphorm does
+        let children = [10.0, 80.0, 150.0, 220.0]
+            .map(|posx| nodes.add(Some(row), sized(60.0, 20.0), rect(posx, 10.0, 60.0, 20.0)));
+        let grandchild = nodes.add(
+            Some(children[2]),
+            KStyle::default(),
+            rect(155.0, 15.0, 10.0, 10.0),
+        );
+
+        nodes.apply(apply_wrapping);
+
+        let position = |id| {
+            let layout = nodes.layout(id);
+            (layout.posx, layout.posy)
+        };
+        assert_eq!(position(children[0]), (10.0, 10.0));
+        assert_eq!(position(children[1]), (80.0, 10.0));
+        assert_eq!(position(children[2]), (10.0, 35.0));
+        assert_eq!(position(children[3]), (80.0, 35.0));
+        // Descendants move along with their wrapped ancestor
+        assert_eq!(position(grandchild), (15.0, 40.0));
+        // Auto-sized rows grow to fit the wrapped children
+        assert_eq!(nodes.layout(row).height, 65.0);
+    }
+
+    #[test]
+    fn wrapping_should_give_wide_children_their_own_row() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let row = nodes.add(
+            Some(root),
+            wrapping_row(KStyle::default()),
+            rect(0.0, 0.0, 100.0, 100.0),
+        );
+        let narrow = nodes.add(Some(row), sized(30.0, 10.0), rect(0.0, 0.0, 30.0, 10.0));
+        let wide = nodes.add(Some(row), sized(150.0, 10.0), rect(30.0, 0.0, 150.0, 10.0));
+        let last = nodes.add(Some(row), sized(30.0, 10.0), rect(180.0, 0.0, 30.0, 10.0));
+
+        nodes.apply(apply_wrapping);
+
+        let position = |id| {
+            let layout = nodes.layout(id);
+            (layout.posx, layout.posy)
+        };
+        assert_eq!(position(narrow), (0.0, 0.0));
+        assert_eq!(position(wide), (0.0, 10.0));
+        assert_eq!(position(last), (0.0, 20.0));
+        // Rows with a fixed height keep it
+        assert_eq!(nodes.layout(row).height, 100.0);
+    }
+
+    #[test]
+    fn rows_should_only_wrap_when_enabled() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let row = nodes.add(
+            Some(root),
+            KStyle {
+                layout_type: StyleProp::Value(LayoutType::Row),
+                ..Default::default()
+            },
+            rect(0.0, 0.0, 100.0, 100.0),
+        );
+        nodes.add(Some(row), KStyle::default(), rect(0.0, 0.0, 60.0, 10.0));
+        let overflowing = nodes.add(Some(row), KStyle::default(), rect(60.0, 0.0, 60.0, 10.0));
+
+        nodes.apply(apply_wrapping);
+
+        let layout = nodes.layout(overflowing);
+        assert_eq!((layout.posx, layout.posy), (60.0, 0.0));
+    }
+
+    /// Adds a column with a wrapping row that is 100 pixels wide and fits its height to its lines
+    fn add_wrapping_row(nodes: &mut TestNodes) -> (WrappedIndex, WrappedIndex) {
+        let root = nodes.add(
+            None,
+            KStyle {
+                layout_type: StyleProp::Value(LayoutType::Column),
+                ..sized(400.0, 300.0)
+            },
+            Rect::default(),
+        );
+        let row = nodes.add(
+            Some(root),
+            wrapping_row(KStyle {
+                width: StyleProp::Value(Units::Pixels(100.0)),
+                height: StyleProp::Value(Units::Auto),
+                ..Default::default()
+            }),
+            Rect::default(),
+        );
+        (root, row)
+    }
+
+    /// Lays out the nodes like [`calculate_nodes`] does, returning the position of each of the
+    /// given nodes relative to the given row
+    fn wrapped_positions<const N: usize>(
+        nodes: &mut TestNodes,
+        row: WrappedIndex,
+        ids: [WrappedIndex; N],
+    ) -> [(f32, f32); N] {
+        nodes.apply(layout_with_derived_sizes);
+        nodes.apply(apply_wrapping);
+        let row = nodes.layout(row);
+        ids.map(|id| {
+            let layout = nodes.layout(id);
+            (layout.posx - row.posx, layout.posy - row.posy)
+        })
+    }
+
+    #[test]
+    fn wrapped_rows_should_make_room_for_their_lines() {
+        let mut nodes = TestNodes::default();
+        let (root, row) = add_wrapping_row(&mut nodes);
+        let children = [(); 3].map(|_| nodes.add(Some(row), sized(60.0, 20.0), Rect::default()));
+        let sibling = nodes.add(Some(root), sized(100.0, 30.0), Rect::default());
+
+        let positions = wrapped_positions(&mut nodes, row, children);
+
+        assert_eq!(positions, [(0.0, 0.0), (0.0, 20.0), (0.0, 40.0)]);
+        assert_eq!(nodes.layout(row).height, 60.0);
+        // Later siblings are placed after every line, rather than just the first
+        assert_eq!(nodes.layout(sibling).posy, nodes.layout(row).posy + 60.0);
+    }
+
+    #[test]
+    fn wrapping_should_include_the_spacing_of_children() {
+        let mut nodes = TestNodes::default();
+        let (_, row) = add_wrapping_row(&mut nodes);
+        let spaced = KStyle {
+            left: StyleProp::Value(Units::Pixels(10.0)),
+            ..sized(40.0, 20.0)
+        };
+        let children = [(); 3].map(|_| nodes.add(Some(row), spaced.clone(), Rect::default()));
+
+        let positions = wrapped_positions(&mut nodes, row, children);
+
+        assert_eq!(positions, [(10.0, 0.0), (60.0, 0.0), (10.0, 20.0)]);
+        assert_eq!(nodes.layout(row).height, 40.0);
+    }
+
+    #[test]
+    fn stretched_children_should_wrap_at_their_min_width() {
+        let mut nodes = TestNodes::default();
+        let (_, row) = add_wrapping_row(&mut nodes);
+        let fixed = nodes.add(Some(row), sized(60.0, 20.0), Rect::default());
+        let stretched = nodes.add(
+            Some(row),
+            KStyle {
+                width: StyleProp::Value(Units::Stretch(1.0)),
+                min_width: StyleProp::Value(Units::Pixels(50.0)),
+                height: StyleProp::Value(Units::Pixels(20.0)),
+                ..Default::default()
+            },
+            Rect::default(),
+        );
+
+        let positions = wrapped_positions(&mut nodes, row, [fixed, stretched]);
+
+        assert_eq!(positions, [(0.0, 0.0), (0.0, 20.0)]);
+        // Stretched children fill the space left on their line
+        assert_eq!(nodes.layout(fixed).width, 60.0);
+        assert_eq!(nodes.layout(stretched).width, 100.0);
+        assert_eq!(nodes.layout(row).height, 40.0);
+    }
+
+    /// Adds a 300x200 grid, with rows that are 50 and 150 pixels high and columns that are 100
+    /// and 200 pixels wide unless no tracks are wanted
+    fn add_grid(nodes: &mut TestNodes, tracks: bool) -> WrappedIndex {
+        let (rows, cols) = match tracks {
+            true => (vec![50.0, 150.0], vec![100.0, 200.0]),
+            false => (vec![], vec![]),
+        };
+        let pixels = |sizes: Vec<f32>| sizes.into_iter().map(Units::Pixels).collect();
+        nodes.add(
+            None,
+            KStyle {
+                layout_type: StyleProp::Value(LayoutType::Grid),
+                width: StyleProp::Value(Units::Pixels(300.0)),
+                height: StyleProp::Value(Units::Pixels(200.0)),
+                grid_rows: StyleProp::Value(pixels(rows)),
+                grid_cols: StyleProp::Value(pixels(cols)),
+                ..Default::default()
+            },
+            Rect::default(),
+        )
+    }
+
+    fn grid_cell(row: usize, col: usize, row_span: usize, col_span: usize) -> KStyle {
+        KStyle {
+            row_index: StyleProp::Value(row),
+            col_index: StyleProp::Value(col),
+            row_span: StyleProp::Value(row_span),
+            col_span: StyleProp::Value(col_span),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the layout of a grid's child relative to the grid
+    fn grid_layout(
+        nodes: &TestNodes,
+        grid: WrappedIndex,
+        id: WrappedIndex,
+    ) -> (f32, f32, f32, f32) {
+        let (grid, layout) = (nodes.layout(grid), nodes.layout(id));
+        (
+            layout.posx - grid.posx,
+            layout.posy - grid.posy,
+            layout.width,
+            layout.height,
+        )
+    }
+
+    #[test]
+    fn grid_children_should_be_placed_in_their_cells() {
+        let mut nodes = TestNodes::default();
+        let grid = add_grid(&mut nodes, true);
+        let first = nodes.add(Some(grid), grid_cell(0, 0, 1, 1), Rect::default());
+        let last = nodes.add(Some(grid), grid_cell(1, 1, 1, 1), Rect::default());
+
+        nodes.apply(layout_with_derived_sizes);
+
+        assert_eq!(grid_layout(&nodes, grid, first), (0.0, 0.0, 100.0, 50.0));
+        assert_eq!(grid_layout(&nodes, grid, last), (100.0, 50.0, 200.0, 150.0));
+    }
+
+    #[test]
+    fn grid_children_should_span_cells() {
+        let mut nodes = TestNodes::default();
+        let grid = add_grid(&mut nodes, true);
+        let row = nodes.add(Some(grid), grid_cell(0, 0, 1, 2), Rect::default());
+        let col = nodes.add(Some(grid), grid_cell(0, 1, 2, 1), Rect::default());
+
+        nodes.apply(layout_with_derived_sizes);
+
+        assert_eq!(grid_layout(&nodes, grid, row), (0.0, 0.0, 300.0, 50.0));
+        assert_eq!(grid_layout(&nodes, grid, col), (100.0, 0.0, 200.0, 200.0));
+    }
+
+    #[test]
+    fn grid_placements_should_be_clamped_to_the_tracks() {
+        let mut nodes = TestNodes::default();
+        let grid = add_grid(&mut nodes, true);
+        let outside = nodes.add(Some(grid), grid_cell(5, 5, 1, 1), Rect::default());
+        let overflowing = nodes.add(Some(grid), grid_cell(1, 0, 3, 3), Rect::default());
+
+        nodes.apply(layout_with_derived_sizes);
+
+        assert_eq!(
+            grid_layout(&nodes, grid, outside),
+            (100.0, 50.0, 200.0, 150.0)
+        );
+        assert_eq!(
+            grid_layout(&nodes, grid, overflowing),
+            (0.0, 50.0, 300.0, 150.0)
+        );
+    }
+
+    #[test]
+    fn grid_without_tracks_should_have_a_single_cell() {
+        let mut nodes = TestNodes::default();
+        let grid = add_grid(&mut nodes, false);
+        let child = nodes.add(Some(grid), grid_cell(2, 3, 2, 2), Rect::default());
+
+        nodes.apply(layout_with_derived_sizes);
+
+        assert_eq!(grid_layout(&nodes, grid, child), (0.0, 0.0, 300.0, 200.0));
+    }
+
+    fn z_index(z_index: i32) -> KStyle {
+        KStyle {
+            z_index: StyleProp::Value(z_index),
+            ..Default::default()
+        }
+    }
+
+    fn overlay(overlay: Overlay) -> KStyle {
+        KStyle {
+            overlay: StyleProp::Value(overlay),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stacking_should_inherit_z_index_unless_set() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let raised = nodes.add(Some(root), z_index(2), Rect::default());
+        let raised_child = nodes.add(Some(raised), KStyle::default(), Rect::default());
+        let plain = nodes.add(Some(root), KStyle::default(), Rect::default());
+        let lowered_child = nodes.add(Some(plain), z_index(-1), Rect::default());
+
+        nodes.apply(apply_stacking_and_transforms);
+
+        let z = |id| nodes.layout(id).z_index;
+        assert_eq!(z(root), 0.0);
+        assert_eq!(z(raised), 2.0);
+        assert_eq!(z(raised_child), 2.0);
+        assert_eq!(z(plain), 0.0);
+        assert_eq!(z(lowered_child), -1.0);
+    }
+
+    #[test]
+    fn overlay_should_stack_above_the_rest_of_the_tree() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let high = nodes.add(Some(root), z_index(1000), Rect::default());
+        // An overlay escapes the clips and stacking of its ancestors
+        let clip = nodes.add(Some(root), z_index(-5), rect(0.0, 0.0, 50.0, 50.0));
+        let overlaid = nodes.add(Some(clip), overlay(Overlay::default()), Rect::default());
+        let overlaid_child = nodes.add(Some(overlaid), z_index(3), Rect::default());
+        let overlaid_grandchild =
+            nodes.add(Some(overlaid_child), KStyle::default(), Rect::default());
+
+        nodes.apply(apply_stacking_and_transforms);
+
+        let z = |id| nodes.layout(id).z_index;
+        assert_eq!(z(high), 1000.0);
+        assert_eq!(z(overlaid), OVERLAY_Z_INDEX);
+        assert_eq!(z(overlaid_child), OVERLAY_Z_INDEX + 3.0);
+        assert_eq!(z(overlaid_grandchild), OVERLAY_Z_INDEX + 3.0);
+        assert!(z(overlaid) > z(high));
+    }
+
+    #[test]
+    fn overlay_should_be_placed_against_its_anchor() {
+        let mut nodes = TestNodes::default();
+        let root = nodes.add(None, KStyle::default(), rect(0.0, 0.0, 400.0, 300.0));
+        let anchor = nodes.add(Some(root), KStyle::default(), rect(50.0, 60.0, 100.0, 20.0));
+        let below = nodes.add(
+            Some(root),
+            overlay(Overlay {
+                offset: Vec2::new(0.0, 4.0),
+                ..Overlay::anchored(anchor.0, Placement::Below)
+            }),
+            rect(0.0, 0.0, 80.0, 40.0),
+        );
+        let below_child = nodes.add(Some(below), KStyle::default(), rect(5.0, 5.0, 10.0, 10.0));
+        let above = nodes.add(
+            Some(root),
+            overlay(Overlay::anchored(anchor.0, Placement::Above)),
+            rect(200.0, 200.0, 80.0, 40.0),
+        );
+        let right = nodes.add(
+            Some(root),
+            overlay(Overlay::anchored(anchor.0, Placement::Right)),
+            rect(0.0, 0.0, 80.0, 40.0),
+        );
+
+        nodes.apply(apply_stacking_and_transforms);
+
+        let position = |id| {
+            let layout = nodes.layout(id);
+            (layout.posx, layout.posy)
+        };
+        assert_eq!(position(below), (50.0, 84.0));
+        // Descendants move along with the overlay
+        assert_eq!(position(below_child), (55.0, 89.0));
+        assert_eq!(position(above), (50.0, 20.0));
+        assert_eq!(position(right), (150.0, 60.0));
+        // The anchor itself stays in place
+        assert_eq!(position(anchor), (50.0, 60.0));
+    }
+
+    #[test]
+    fn rem_units_should_follow_root_font_size() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<KayakFont>()
+            .insert_resource(FontMapping::default())
+            .insert_resource(WindowSize(800.0, 600.0));
+        let world = &mut app.world;
+
+        let root_styles = |font_size| KStyle {
+            font_size: StyleProp::Value(font_size),
+            width: StyleProp::Value(Units::Pixels(800.0)),
+            height: StyleProp::Value(Units::Pixels(600.0)),
+            ..Default::default()
+        };
+        let root = world.spawn((root_styles(20.0), DirtyNode)).id();
+        let child_styles = KStyle {
+            width: StyleProp::Value(Units::Rem(2.0)),
+            ..Default::default()
+        };
+        let child = world.spawn((child_styles, DirtyNode)).id();
+        let mut context = Context::new();
+        context.add_widget(None, root);
+        context.add_widget(Some(root), child);
+        world.insert_resource(context);
+
+        let mut system = IntoSystem::into_system(calculate_nodes);
+        system.initialize(world);
+        let mut run = |world: &mut World| {
+            system.run((), world);
+            system.apply_buffers(world);
+        };
+        let child_width = |world: &World| {
+            world
+                .get::<Node>(child)
+                .unwrap()
+                .resolved_styles
+                .width
+                .clone()
+        };
+
+        run(world);
+        assert_eq!(child_width(world), StyleProp::Value(Units::Pixels(40.0)));
+
+        // Only the root's own styles change, but the child depends on them
+        world
+            .entity_mut(root)
+            .insert((root_styles(30.0), DirtyNode));
+        run(world);
+        run(world);
+        assert_eq!(child_width(world), StyleProp::Value(Units::Pixels(60.0)));
+    }
+}
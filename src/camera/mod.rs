@@ -1,8 +1,11 @@
 use bevy::{
-    prelude::{CoreStage, Plugin},
+    prelude::{CoreStage, IntoSystemDescriptor, Plugin, Query, Res, With},
     render::{camera::CameraProjectionPlugin, extract_component::ExtractComponentPlugin},
+    window::Windows,
 };
 
+use crate::UiScale;
+
 mod camera;
 mod ortho;
 
@@ -17,7 +20,31 @@ impl Plugin for KayakUICameraPlugin {
             CoreStage::PostUpdate,
             bevy::render::camera::camera_system::<UIOrthographicProjection>,
         )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            update_ui_camera_scale
+                .before(bevy::render::camera::camera_system::<UIOrthographicProjection>),
+        )
         .add_plugin(CameraProjectionPlugin::<UIOrthographicProjection>::default())
         .add_plugin(ExtractComponentPlugin::<CameraUiKayak>::default());
     }
 }
+
+/// Scales the projection of UI cameras so that they cover the window in UI pixels
+fn update_ui_camera_scale(
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    mut query: Query<&mut UIOrthographicProjection, With<CameraUiKayak>>,
+) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let scale = ui_scale.logical_to_ui(window);
+    for mut projection in query.iter_mut() {
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+    }
+}
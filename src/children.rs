@@ -3,7 +3,8 @@ use bevy::prelude::*;
 use crate::prelude::WidgetContext;
 
 /// Defers widgets being added to the widget tree.
-#[derive(Component, Debug, Default, Clone)]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
 pub struct KChildren {
     inner: Vec<Entity>,
 }
@@ -22,6 +23,11 @@ impl KChildren {
         self.inner.get(index).and_then(|e| Some(*e))
     }
 
+    /// Returns an iterator over the children, in order
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.inner.iter().copied()
+    }
+
     pub fn remove(&mut self, index: usize) -> Option<Entity> {
         if index < self.inner.len() {
             Some(self.inner.remove(index))
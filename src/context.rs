@@ -2,13 +2,16 @@ use std::sync::{Arc, RwLock};
 
 use bevy::{
     ecs::{event::ManualEventReader, system::CommandQueue},
+    math::Affine2,
     prelude::*,
-    utils::HashMap,
+    utils::{HashMap, HashSet},
 };
 use morphorm::Hierarchy;
 
 use crate::{
+    animation::{ExitingWidget, MountAnimations, StyleTransitions},
     calculate_nodes::calculate_nodes,
+    children::KChildren,
     context_entities::ContextEntities,
     event_dispatcher::EventDispatcher,
     focus_tree::FocusTree,
@@ -16,9 +19,12 @@ use crate::{
     layout_dispatcher::LayoutEventDispatcher,
     node::{DirtyNode, WrappedIndex},
     prelude::WidgetContext,
-    render_primitive::RenderPrimitive,
+    render_primitive::{RenderEffects, RenderPrimitive},
+    styles::{Corner, KStyle, StyleProp},
     tree::{Change, Tree},
-    Focusable, WindowSize,
+    widget::RegisteredWidgets,
+    widget_keys::WidgetKeys,
+    Focusable, UiScale, WindowSize,
 };
 
 /// A tag component representing when a widget has been mounted(added to the tree).
@@ -32,10 +38,22 @@ pub struct Context {
     pub tree: Arc<RwLock<Tree>>,
     pub(crate) layout_cache: Arc<RwLock<LayoutCache>>,
     pub(crate) focus_tree: Arc<RwLock<FocusTree>>,
+    /// The system of each widget type, keyed by its [`WidgetName`]
+    ///
+    /// Widgets are dispatched by name rather than by type, since the name is all an entity,
+    /// snapshot, or UI asset records about the widget it is.
     systems: HashMap<String, Box<dyn System<In = (WidgetContext, Entity), Out = bool>>>,
     pub(crate) current_z: f32,
     pub(crate) context_entities: ContextEntities,
+    pub(crate) widget_keys: WidgetKeys,
     pub(crate) current_cursor: CursorIcon,
+    pub(crate) mount_animations: Arc<RwLock<MountAnimations>>,
+    /// The window size that viewport units were last resolved against
+    pub(crate) window_size: WindowSize,
+    /// The root font size that rem units were last resolved against
+    pub(crate) root_font_size: f32,
+    /// Widget types that have been reported as having no system, so they're only reported once
+    unregistered_widgets: HashSet<String>,
 }
 
 impl Context {
@@ -47,7 +65,12 @@ impl Context {
             systems: HashMap::default(),
             current_z: 0.0,
             context_entities: ContextEntities::new(),
+            widget_keys: WidgetKeys::new(),
             current_cursor: CursorIcon::Default,
+            mount_animations: Default::default(),
+            window_size: WindowSize::default(),
+            root_font_size: 0.0,
+            unregistered_widgets: HashSet::default(),
         }
     }
 
@@ -59,6 +82,21 @@ impl Context {
         }
     }
 
+    /// Maps a point in window space to the untransformed layout space of the given widget
+    pub(crate) fn to_layout_space(&self, id: &WrappedIndex, point: (f32, f32)) -> (f32, f32) {
+        if let Ok(cache) = self.layout_cache.try_read() {
+            if let Some(transform) = cache.transforms.get(id) {
+                let point = transform
+                    .inverse()
+                    .transform_point2(Vec2::new(point.0, point.1));
+                return (point.x, point.y);
+            }
+        }
+        point
+    }
+
+    /// Adds the system that updates widgets with the given [`WidgetName`], replacing any
+    /// previously added for it
     pub fn add_widget_system<Params>(
         &mut self,
         type_name: impl Into<String>,
@@ -102,6 +140,26 @@ impl Context {
         None
     }
 
+    /// Gets the child of the given entity with the given key, for widgets with a `key` attribute
+    /// in `rsx!`
+    pub fn get_child_with_key(&self, entity: Option<Entity>, key: &str) -> Option<Entity> {
+        let entity = entity?;
+        if let Ok(tree) = self.tree.try_read() {
+            return tree
+                .child_iter(WrappedIndex(entity))
+                .map(|index| index.0)
+                .find(|child| self.widget_keys.matches(*child, entity, key));
+        }
+        None
+    }
+
+    /// Sets the key of a widget within the given parent
+    pub fn set_key(&self, parent: Option<Entity>, entity: Entity, key: String) {
+        if let Some(parent) = parent {
+            self.widget_keys.insert(parent, entity, key);
+        }
+    }
+
     pub fn build_render_primitives(
         &self,
         nodes: &Query<&crate::node::Node>,
@@ -117,32 +175,183 @@ impl Context {
             return vec![];
         }
 
+        let mount_animations = self.mount_animations.try_read();
+        if mount_animations.is_err() {
+            return vec![];
+        }
+
+        let mount_animations = mount_animations.unwrap();
+        let root_node = node_tree.root_node.unwrap();
+
         // self.node_tree.dump();
 
-        recurse_node_tree_to_build_primitives(
+        let mut layers = StackingLayers::default();
+        let mut main_primitives = Some(recurse_node_tree_to_build_primitives(
             &*node_tree,
             &self.layout_cache,
             nodes,
-            node_tree.root_node.unwrap(),
+            &mount_animations,
+            root_node,
             0.0,
             RenderPrimitive::Empty,
-        )
+            RenderEffects::default(),
+            &mut layers,
+        ));
+
+        // Anything rendered in a layer starts out clipped to the whole window (or its own clip)
+        let root_layout = self.get_layout(&root_node).unwrap_or_default();
+        let root_clip = RenderPrimitive::Clip {
+            layout: root_layout,
+            mask_layout: root_layout,
+            border_radius: Corner::default(),
+        };
+
+        let mut render_primitives = Vec::new();
+        loop {
+            let next_layer = layers.next_index();
+            // The rest of the tree sits between the negative and positive z-indices
+            let main_is_next = next_layer
+                .map(|index| layers.layers[index].is_above_main())
+                .unwrap_or(true);
+            if main_is_next {
+                if let Some(main_primitives) = main_primitives.take() {
+                    if !render_primitives.is_empty() {
+                        render_primitives.push(root_clip.clone());
+                        render_primitives.push(RenderPrimitive::Transform {
+                            transform: Affine2::IDENTITY,
+                        });
+                    }
+                    render_primitives.extend(main_primitives);
+                    continue;
+                }
+            }
+
+            let layer = match next_layer {
+                Some(index) => layers.layers.remove(index),
+                None => break,
+            };
+            let clip = if matches!(layer.clip, RenderPrimitive::Clip { .. }) {
+                layer.clip.clone()
+            } else {
+                root_clip.clone()
+            };
+            render_primitives.push(clip.clone());
+            render_primitives.push(RenderPrimitive::Transform {
+                transform: layer.effects.transform,
+            });
+            let z_index = render_primitives.len() as f32;
+            layers.in_overlay = layer.overlay;
+            render_primitives.extend(recurse_node_tree_to_build_primitives(
+                layer.tree,
+                &self.layout_cache,
+                nodes,
+                &mount_animations,
+                layer.node,
+                z_index,
+                clip,
+                layer.effects,
+                &mut layers,
+            ));
+        }
+
+        render_primitives
+    }
+}
+
+/// A subtree that is rendered separately from (and above or below) the rest of the tree
+struct StackingLayer<'a> {
+    tree: &'a Tree,
+    node: WrappedIndex,
+    z_index: i32,
+    overlay: bool,
+    /// The order in which this layer was found, used to keep tree order between equal z-indices
+    order: usize,
+    /// The clip the subtree would have been rendered under
+    clip: RenderPrimitive,
+    effects: RenderEffects,
+}
+
+impl<'a> StackingLayer<'a> {
+    fn is_above_main(&self) -> bool {
+        self.overlay || self.z_index >= 0
     }
 }
 
-fn recurse_node_tree_to_build_primitives(
-    node_tree: &Tree,
+#[derive(Default)]
+struct StackingLayers<'a> {
+    layers: Vec<StackingLayer<'a>>,
+    next_order: usize,
+    /// Whether the layer currently being rendered is in the overlay layer
+    in_overlay: bool,
+}
+
+impl<'a> StackingLayers<'a> {
+    /// Defers rendering of the given node if it starts a new layer
+    ///
+    /// Returns true if the node was deferred.
+    fn defer(
+        &mut self,
+        tree: &'a Tree,
+        node: WrappedIndex,
+        styles: &KStyle,
+        clip: &RenderPrimitive,
+        effects: RenderEffects,
+    ) -> bool {
+        let overlay = matches!(styles.overlay, StyleProp::Value(_));
+        if !overlay && !matches!(styles.z_index, StyleProp::Value(_)) {
+            return false;
+        }
+
+        self.layers.push(StackingLayer {
+            tree,
+            node,
+            z_index: styles.z_index.resolve(),
+            overlay: overlay || self.in_overlay,
+            order: self.next_order,
+            clip: if overlay {
+                RenderPrimitive::Empty
+            } else {
+                clip.clone()
+            },
+            effects,
+        });
+        self.next_order += 1;
+        true
+    }
+
+    /// Returns the index of the lowest layer left to render
+    fn next_index(&self) -> Option<usize> {
+        self.layers
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, layer)| (layer.overlay, layer.z_index, layer.order))
+            .map(|(index, _)| index)
+    }
+}
+
+fn recurse_node_tree_to_build_primitives<'a>(
+    node_tree: &'a Tree,
     layout_cache: &Arc<RwLock<LayoutCache>>,
     nodes: &Query<&crate::node::Node>,
+    mount_animations: &'a MountAnimations,
     current_node: WrappedIndex,
     mut main_z_index: f32,
     mut prev_clip: RenderPrimitive,
+    mut effects: RenderEffects,
+    layers: &mut StackingLayers<'a>,
 ) -> Vec<RenderPrimitive> {
     let mut render_primitives = Vec::new();
     if let Ok(node) = nodes.get(current_node.0) {
         if let Ok(cache) = layout_cache.try_read() {
             if let Some(layout) = cache.rect.get(&current_node) {
-                let mut render_primitive = node.primitive.clone();
+                // Hidden widgets still clip their (possibly visible) descendants
+                let mut render_primitive = if node.is_visible()
+                    || matches!(node.primitive, RenderPrimitive::Clip { .. })
+                {
+                    node.primitive.clone()
+                } else {
+                    RenderPrimitive::Empty
+                };
                 let mut layout = *layout;
                 let new_z_index = if matches!(render_primitive, RenderPrimitive::Clip { .. }) {
                     main_z_index - 0.1
@@ -151,6 +360,55 @@ fn recurse_node_tree_to_build_primitives(
                 };
                 layout.z_index = new_z_index;
                 render_primitive.set_layout(layout);
+                if let Some(animation) = mount_animations.get(current_node) {
+                    effects = effects.then(&animation.effects(&layout));
+                }
+                if let StyleProp::Value(opacity) = node.resolved_styles.opacity {
+                    effects = effects.then(&RenderEffects::opacity(opacity.clamp(0.0, 1.0)));
+                }
+                render_primitive.apply_effects(&effects);
+
+                let parent_transform = effects.transform;
+                if let StyleProp::Value(transform) = node.resolved_styles.transform {
+                    let mut transformed_layout = layout;
+                    effects.apply_to_rect(&mut transformed_layout);
+                    effects.transform = parent_transform * transform.to_affine(&transformed_layout);
+                }
+                if effects.transform != Affine2::IDENTITY {
+                    if let RenderPrimitive::Clip { layout, .. } = &mut render_primitive {
+                        // Clips can't be rotated, so clip to the bounds of the transformed rect instead
+                        *layout = layout.transformed_bounds(&effects.transform);
+                    }
+                }
+
+                // Nested clips only show what their ancestors show, and keep masking content to
+                // the closest rounded clip
+                if let (
+                    RenderPrimitive::Clip {
+                        layout: clip_layout,
+                        mask_layout,
+                        border_radius,
+                    },
+                    RenderPrimitive::Clip {
+                        layout: parent_layout,
+                        mask_layout: parent_mask_layout,
+                        border_radius: parent_border_radius,
+                    },
+                ) = (&mut render_primitive, &prev_clip)
+                {
+                    *clip_layout = clip_layout.intersection(parent_layout);
+                    if *border_radius == Corner::default() {
+                        *mask_layout = *parent_mask_layout;
+                        *border_radius = *parent_border_radius;
+                    }
+                }
+
+                let has_transform = effects.transform != parent_transform;
+                if has_transform {
+                    render_primitives.push(RenderPrimitive::Transform {
+                        transform: effects.transform,
+                    });
+                }
                 render_primitives.push(render_primitive.clone());
 
                 let new_prev_clip = if matches!(render_primitive, RenderPrimitive::Clip { .. }) {
@@ -160,16 +418,51 @@ fn recurse_node_tree_to_build_primitives(
                 };
 
                 prev_clip = new_prev_clip.clone();
-                if node_tree.children.contains_key(&current_node) {
-                    for child in node_tree.children.get(&current_node).unwrap() {
+
+                // Removed children keep rendering in their old spot until their exit animation ends
+                let mut children = node_tree
+                    .children
+                    .get(&current_node)
+                    .map(|children| {
+                        children
+                            .iter()
+                            .map(|child| (node_tree, *child))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                for exiting in mount_animations.exiting_children(current_node) {
+                    let index = exiting.index.min(children.len());
+                    children.insert(index, (&exiting.subtree, exiting.entity));
+                }
+
+                if !children.is_empty() {
+                    for (child_tree, child) in children {
+                        if let Ok(child_node) = nodes.get(child.0) {
+                            if !child_node.is_displayed() {
+                                continue;
+                            }
+                            if layers.defer(
+                                child_tree,
+                                child,
+                                &child_node.resolved_styles,
+                                &new_prev_clip,
+                                effects,
+                            ) {
+                                continue;
+                            }
+                        }
+
                         main_z_index += 1.0;
                         render_primitives.extend(recurse_node_tree_to_build_primitives(
-                            node_tree,
+                            child_tree,
                             layout_cache,
                             nodes,
-                            *child,
+                            mount_animations,
+                            child,
                             main_z_index,
                             new_prev_clip.clone(),
+                            effects,
+                            layers,
                         ));
 
                         main_z_index = layout.z_index;
@@ -177,7 +470,7 @@ fn recurse_node_tree_to_build_primitives(
                         if matches!(prev_clip, RenderPrimitive::Clip { .. }) {
                             // main_z_index = new_z_index;
                             match &mut prev_clip {
-                                RenderPrimitive::Clip { layout } => {
+                                RenderPrimitive::Clip { layout, .. } => {
                                     layout.z_index = main_z_index + 0.1;
                                 }
                                 _ => {}
@@ -186,6 +479,12 @@ fn recurse_node_tree_to_build_primitives(
                         }
                     }
                 }
+
+                if has_transform {
+                    render_primitives.push(RenderPrimitive::Transform {
+                        transform: parent_transform,
+                    });
+                }
             } else {
                 println!("No layout for: {:?}", current_node.0.id());
             }
@@ -199,6 +498,7 @@ fn recurse_node_tree_to_build_primitives(
 
 fn update_widgets_sys(world: &mut World) {
     let mut context = world.remove_resource::<Context>().unwrap();
+    add_registered_widgets(world, &mut context);
     let tree_iterator = if let Ok(tree) = context.tree.read() {
         tree.down_iter().collect::<Vec<_>>()
     } else {
@@ -228,8 +528,11 @@ fn update_widgets_sys(world: &mut World) {
         &mut context.systems,
         tree_iterator,
         &context.context_entities,
+        &context.widget_keys,
         &context.focus_tree,
+        &context.mount_animations,
         &mut new_ticks,
+        &mut context.unregistered_widgets,
     );
 
     if let Some(old_focus) = old_focus {
@@ -266,8 +569,11 @@ fn update_widgets(
     systems: &mut HashMap<String, Box<dyn System<In = (WidgetContext, Entity), Out = bool>>>,
     widgets: Vec<WrappedIndex>,
     context_entities: &ContextEntities,
+    widget_keys: &WidgetKeys,
     focus_tree: &Arc<RwLock<FocusTree>>,
+    mount_animations: &Arc<RwLock<MountAnimations>>,
     new_ticks: &mut HashMap<String, u32>,
+    unregistered_widgets: &mut HashSet<String>,
 ) {
     for entity in widgets.iter() {
         if let Some(entity_ref) = world.get_entity(entity.0) {
@@ -276,6 +582,7 @@ fn update_widgets(
                     tree.clone(),
                     context_entities.clone(),
                     layout_cache.clone(),
+                    widget_keys.clone(),
                 );
                 widget_context.copy_from_point(&tree, *entity);
                 let children_before = widget_context.get_children(entity.0);
@@ -287,7 +594,10 @@ fn update_widgets(
                     widget_type.0.clone(),
                     widget_context,
                     children_before,
+                    widget_keys,
+                    mount_animations,
                     new_ticks,
+                    unregistered_widgets,
                 );
 
                 if should_update_children {
@@ -320,8 +630,11 @@ fn update_widgets(
                     systems,
                     children,
                     context_entities,
+                    widget_keys,
                     focus_tree,
+                    mount_animations,
                     new_ticks,
+                    unregistered_widgets,
                 );
                 // }
             }
@@ -339,6 +652,40 @@ fn update_widgets(
     }
 }
 
+/// Removes the focusable widgets that are hidden by the latest layout from the focus tree
+///
+/// The focus tree is rebuilt each time the widgets are updated, so widgets that are shown again
+/// become focusable again.
+fn remove_hidden_focusables(world: &World, context: &Context) {
+    if let Ok(tree) = context.tree.try_read() {
+        if let Ok(mut focus_tree) = context.focus_tree.try_write() {
+            focus_tree.remove_hidden(|index| is_hidden(world, &tree, index));
+        }
+    }
+}
+
+/// Returns true if the given widget is hidden, either by its own styles or by an ancestor
+/// with [`Display::None`](crate::styles::Display::None)
+fn is_hidden(world: &World, tree: &Tree, entity: WrappedIndex) -> bool {
+    if let Some(node) = world.get::<crate::node::Node>(entity.0) {
+        if !node.is_visible() {
+            return true;
+        }
+    }
+
+    let mut current = tree.get_parent(entity);
+    while let Some(parent) = current {
+        if let Some(node) = world.get::<crate::node::Node>(parent.0) {
+            if !node.is_displayed() {
+                return true;
+            }
+        }
+        current = tree.get_parent(parent);
+    }
+
+    false
+}
+
 fn update_widget(
     systems: &mut HashMap<String, Box<dyn System<In = (WidgetContext, Entity), Out = bool>>>,
     tree: &Arc<RwLock<Tree>>,
@@ -347,13 +694,29 @@ fn update_widget(
     widget_type: String,
     widget_context: WidgetContext,
     previous_children: Vec<Entity>,
+    widget_keys: &WidgetKeys,
+    mount_animations: &Arc<RwLock<MountAnimations>>,
     new_ticks: &mut HashMap<String, u32>,
+    unregistered_widgets: &mut HashSet<String>,
 ) -> (Tree, bool) {
+    let widget_system = match systems.get_mut(&widget_type) {
+        Some(widget_system) => widget_system,
+        None => {
+            if unregistered_widgets.insert(widget_type.clone()) {
+                error!(
+                    "Widget {:?} is a `{}`, but no system has been registered for that widget. \
+                     Register one with `app.register_widget::<{}, _>(system)`.",
+                    entity.0, widget_type, widget_type
+                );
+            }
+            return (widget_context.take(), false);
+        }
+    };
+
     let should_update_children;
     {
         // Remove children from previous render.
         widget_context.remove_children(previous_children);
-        let widget_system = systems.get_mut(&widget_type).unwrap();
         let old_tick = widget_system.get_last_change_tick();
         should_update_children = widget_system.run((widget_context.clone(), entity.0), world);
         let new_tick = widget_system.get_last_change_tick();
@@ -378,7 +741,7 @@ fn update_widget(
         panic!("Failed to acquire read lock.");
     };
     if should_update_children {
-        for (_, changed_entity, _, changes) in diff.changes.iter() {
+        for (index, changed_entity, parent, changes) in diff.changes.iter() {
             if changes.iter().any(|change| *change != Change::Deleted) {
                 commands.entity(changed_entity.0).insert(DirtyNode);
             }
@@ -386,9 +749,76 @@ fn update_widget(
             if changes.iter().any(|change| *change == Change::Deleted) {
                 // commands.entity(changed_entity.0).despawn();
                 commands.entity(changed_entity.0).remove::<DirtyNode>();
+                commands
+                    .entity(changed_entity.0)
+                    .remove::<StyleTransitions>();
+
+                let exit_animation = world.get::<KStyle>(changed_entity.0).and_then(|styles| {
+                    match &styles.exit_animation {
+                        StyleProp::Value(animation) => Some(animation.clone()),
+                        _ => None,
+                    }
+                });
+                if let Ok(tree) = tree.read() {
+                    if let Some(exit_animation) = exit_animation {
+                        // Keep the removed subtree, and its keys, around until its exit
+                        // animation completes. The index of a deleted child in the diff isn't
+                        // its index in the previous render, so that's looked up instead.
+                        let index = tree
+                            .children
+                            .get(parent)
+                            .and_then(|children| {
+                                children.iter().position(|child| child == changed_entity)
+                            })
+                            .unwrap_or(*index);
+                        if let Ok(mut mount_animations) = mount_animations.try_write() {
+                            mount_animations.exit(
+                                ExitingWidget {
+                                    entity: *changed_entity,
+                                    parent: *parent,
+                                    index,
+                                    subtree: tree.subtree(*changed_entity),
+                                },
+                                exit_animation,
+                            );
+                        }
+                    } else {
+                        for node in tree.subtree(*changed_entity).down_iter() {
+                            widget_keys.remove(node.0);
+                        }
+                    }
+                }
             }
             if changes.iter().any(|change| *change == Change::Inserted) {
                 commands.entity(changed_entity.0).insert(Mounted);
+
+                // A widget added back with the same key before its exit animation completed
+                // renders its children again, so the ones it was removed with are no longer needed
+                if let Ok(mut mount_animations) = mount_animations.try_write() {
+                    if let Some(exiting) = mount_animations.cancel_exit(*changed_entity) {
+                        for node in exiting.subtree.down_iter() {
+                            if node == *changed_entity {
+                                continue;
+                            }
+                            widget_keys.remove(node.0);
+                            if let Some(mut entity_commands) = commands.get_entity(node.0) {
+                                entity_commands.despawn();
+                            }
+                        }
+                    }
+                }
+
+                let enter_animation = world.get::<KStyle>(changed_entity.0).and_then(|styles| {
+                    match &styles.enter_animation {
+                        StyleProp::Value(animation) => Some(animation.clone()),
+                        _ => None,
+                    }
+                });
+                if let Some(enter_animation) = enter_animation {
+                    if let Ok(mut mount_animations) = mount_animations.try_write() {
+                        mount_animations.enter(*changed_entity, enter_animation);
+                    }
+                }
             }
         }
     }
@@ -397,6 +827,29 @@ fn update_widget(
     (widget_context, should_update_children)
 }
 
+/// Moves the systems registered with [`RegisterWidget`](crate::prelude::RegisterWidget) into
+/// the context
+fn add_registered_widgets(world: &mut World, context: &mut Context) {
+    let pending = world
+        .get_resource_or_insert_with(RegisteredWidgets::default)
+        .take_pending();
+
+    for (widget_type, mut system) in pending {
+        if context.systems.contains_key(&widget_type) {
+            error!(
+                "The widget `{}` has already been given a system with `Context::add_widget_system`, \
+                 so the one registered with `register_widget` is ignored.",
+                widget_type
+            );
+            continue;
+        }
+        system.initialize(world);
+        context.systems.insert(widget_type, system);
+    }
+
+    world.resource_mut::<RegisteredWidgets>().systems = context.systems.keys().cloned().collect();
+}
+
 fn init_systems(world: &mut World) {
     let mut context = world.remove_resource::<Context>().unwrap();
     for system in context.systems.values_mut() {
@@ -414,6 +867,7 @@ pub struct CustomEventReader<T: bevy::ecs::event::Event>(pub ManualEventReader<T
 impl Plugin for ContextPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(WindowSize::default())
+            .init_resource::<UiScale>()
             .insert_resource(EventDispatcher::new())
             .insert_resource(CustomEventReader(ManualEventReader::<
                 bevy::window::CursorMoved,
@@ -433,11 +887,24 @@ impl Plugin for ContextPlugin {
             .add_plugin(crate::camera::KayakUICameraPlugin)
             .add_plugin(crate::render::BevyKayakUIRenderPlugin)
             .register_type::<Node>()
+            .register_type::<WidgetName>()
+            .register_type::<KChildren>()
+            .register_type::<Vec<Entity>>()
             .add_startup_system_to_stage(StartupStage::PostStartup, init_systems.at_end())
             .add_system_to_stage(CoreStage::Update, crate::input::process_events)
             .add_system_to_stage(CoreStage::PostUpdate, update_widgets_sys.at_start())
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                crate::animation::update_style_transitions,
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                crate::animation::update_mount_animations,
+            )
             .add_system_to_stage(CoreStage::PostUpdate, calculate_ui.at_end())
             .add_system(crate::window_size::update_window_size);
+
+        crate::styles::register_types(app);
     }
 }
 
@@ -454,6 +921,10 @@ fn calculate_ui(world: &mut World) {
         });
     }
 
+    world.resource_scope::<Context, _>(|world, context| {
+        remove_hidden_focusables(world, &context);
+    });
+
     world.resource_scope::<Context, _>(|world, mut context| {
         world.resource_scope::<EventDispatcher, _>(|world, event_dispatcher| {
             if event_dispatcher.hovered.is_none() {
@@ -484,7 +955,8 @@ fn calculate_ui(world: &mut World) {
     // dbg!("Finished dispatching layout events!");
 }
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Default, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
 pub struct WidgetName(pub String);
 
 impl From<String> for WidgetName {
@@ -498,3 +970,467 @@ impl Into<String> for WidgetName {
         self.0.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use bevy::{
+        ecs::system::{System, SystemState},
+        prelude::{App, Commands, Entity, In, Query, Res, Resource, World},
+        utils::{HashMap, HashSet},
+    };
+    use kayak_ui_macros::rsx;
+    use morphorm::Hierarchy;
+
+    use super::{
+        add_registered_widgets, init_systems, update_widget, update_widgets_sys, Context,
+        WidgetName,
+    };
+    use crate::{
+        context_entities::ContextEntities,
+        layout::Rect,
+        node::{Node, NodeBuilder, WrappedIndex},
+        prelude::WidgetContext,
+        render_primitive::RenderPrimitive,
+        styles::{Easing, KStyle, MountAnimation, Overlay, RenderCommand, StyleProp},
+        tree::Tree,
+        widget::{RegisterWidget, RegisteredWidgets, Widget},
+        widget_keys::WidgetKeys,
+        widgets::{ElementBundle, KButton},
+    };
+
+    fn widget_update(In((_, _)): In<(WidgetContext, Entity)>) -> bool {
+        true
+    }
+
+    #[test]
+    fn widgets_without_a_system_should_be_skipped_and_reported_once() {
+        let mut world = World::new();
+        let entity = WrappedIndex(world.spawn_empty().id());
+        let tree = Arc::new(RwLock::new(Tree::default()));
+        let mut unregistered_widgets = HashSet::default();
+
+        for _ in 0..2 {
+            let widget_context = WidgetContext::new(
+                tree.clone(),
+                ContextEntities::new(),
+                Default::default(),
+                WidgetKeys::new(),
+            );
+            let (_, should_update_children) = update_widget(
+                &mut HashMap::default(),
+                &tree,
+                &mut world,
+                entity,
+                "Unregistered".to_string(),
+                widget_context,
+                vec![],
+                &WidgetKeys::new(),
+                &Default::default(),
+                &mut HashMap::default(),
+                &mut unregistered_widgets,
+            );
+            assert!(!should_update_children);
+        }
+
+        assert_eq!(unregistered_widgets.len(), 1);
+        assert!(unregistered_widgets.contains("Unregistered"));
+    }
+
+    /// Adds a laid out widget that renders the primitive given by its styles
+    fn add_node(
+        world: &mut World,
+        context: &mut Context,
+        parent: Option<Entity>,
+        styles: KStyle,
+        (posx, posy, width, height): (f32, f32, f32, f32),
+    ) -> Entity {
+        let entity = world.spawn_empty().id();
+        let node = NodeBuilder::new(WrappedIndex(entity), styles.clone())
+            .with_primitive(RenderPrimitive::from(&styles))
+            .build();
+        world.entity_mut(entity).insert(node);
+        context.add_widget(parent, entity);
+        context.layout_cache.write().unwrap().rect.insert(
+            WrappedIndex(entity),
+            Rect {
+                posx,
+                posy,
+                width,
+                height,
+                z_index: 0.0,
+            },
+        );
+        entity
+    }
+
+    #[test]
+    fn overlay_should_escape_ancestor_clips() {
+        let mut world = World::new();
+        let mut context = Context::new();
+        let quad = |overlay| KStyle {
+            render_command: StyleProp::Value(RenderCommand::Quad),
+            overlay,
+            ..Default::default()
+        };
+        let root = add_node(
+            &mut world,
+            &mut context,
+            None,
+            KStyle::default(),
+            (0.0, 0.0, 400.0, 300.0),
+        );
+        let clip = add_node(
+            &mut world,
+            &mut context,
+            Some(root),
+            KStyle {
+                render_command: StyleProp::Value(RenderCommand::Clip),
+                ..Default::default()
+            },
+            (0.0, 0.0, 100.0, 100.0),
+        );
+        add_node(
+            &mut world,
+            &mut context,
+            Some(clip),
+            quad(StyleProp::Value(Overlay::default())),
+            (150.0, 150.0, 50.0, 50.0),
+        );
+        add_node(
+            &mut world,
+            &mut context,
+            Some(clip),
+            quad(StyleProp::Unset),
+            (10.0, 10.0, 20.0, 20.0),
+        );
+
+        let mut state = SystemState::<Query<&Node>>::new(&mut world);
+        let primitives = context.build_render_primitives(&state.get(&world));
+
+        // The width of the clip that each quad is rendered in, by the quad's position
+        let clip_widths = primitives
+            .iter()
+            .scan(None, |clip_width, primitive| {
+                if let RenderPrimitive::Clip { layout, .. } = primitive {
+                    *clip_width = Some(layout.width);
+                }
+                Some((primitive, *clip_width))
+            })
+            .filter_map(|(primitive, clip_width)| match primitive {
+                RenderPrimitive::Quad { layout, .. } => Some((layout.posx, clip_width)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // The overlay is rendered last, clipped to the window rather than its ancestor
+        assert_eq!(clip_widths, vec![(10.0, Some(100.0)), (150.0, Some(400.0))]);
+    }
+
+    fn clip_style() -> KStyle {
+        KStyle {
+            render_command: StyleProp::Value(RenderCommand::Clip),
+            ..Default::default()
+        }
+    }
+
+    fn quad_style() -> KStyle {
+        KStyle {
+            render_command: StyleProp::Value(RenderCommand::Quad),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the layout of each quad, along with the layout of the clip it's rendered in
+    fn clipped_quads(primitives: &[RenderPrimitive]) -> Vec<(Rect, Option<Rect>)> {
+        primitives
+            .iter()
+            .scan(None, |clip, primitive| {
+                if let RenderPrimitive::Clip { layout, .. } = primitive {
+                    *clip = Some(*layout);
+                }
+                Some((primitive, *clip))
+            })
+            .filter_map(|(primitive, clip)| match primitive {
+                RenderPrimitive::Quad { layout, .. } => Some((*layout, clip)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn bounds(rect: Rect) -> (f32, f32, f32, f32) {
+        (rect.posx, rect.posy, rect.width, rect.height)
+    }
+
+    #[test]
+    fn nested_clips_should_intersect() {
+        let mut world = World::new();
+        let mut context = Context::new();
+        let root = add_node(
+            &mut world,
+            &mut context,
+            None,
+            KStyle::default(),
+            (0.0, 0.0, 400.0, 300.0),
+        );
+        let outer = add_node(
+            &mut world,
+            &mut context,
+            Some(root),
+            clip_style(),
+            (0.0, 0.0, 100.0, 100.0),
+        );
+        let inner = add_node(
+            &mut world,
+            &mut context,
+            Some(outer),
+            clip_style(),
+            (50.0, 50.0, 100.0, 100.0),
+        );
+        add_node(
+            &mut world,
+            &mut context,
+            Some(inner),
+            quad_style(),
+            (60.0, 60.0, 10.0, 10.0),
+        );
+
+        let mut state = SystemState::<Query<&Node>>::new(&mut world);
+        let primitives = context.build_render_primitives(&state.get(&world));
+
+        let quads = clipped_quads(&primitives);
+        assert_eq!(quads.len(), 1);
+        assert_eq!(
+            quads[0].1.map(bounds),
+            Some((50.0, 50.0, 50.0, 50.0)),
+            "the inner clip should only show what the outer clip shows"
+        );
+    }
+
+    #[test]
+    fn disjoint_clips_should_show_nothing() {
+        let mut world = World::new();
+        let mut context = Context::new();
+        let root = add_node(
+            &mut world,
+            &mut context,
+            None,
+            KStyle::default(),
+            (0.0, 0.0, 400.0, 300.0),
+        );
+        let outer = add_node(
+            &mut world,
+            &mut context,
+            Some(root),
+            clip_style(),
+            (0.0, 0.0, 100.0, 100.0),
+        );
+        let inner = add_node(
+            &mut world,
+            &mut context,
+            Some(outer),
+            clip_style(),
+            (200.0, 200.0, 50.0, 50.0),
+        );
+        add_node(
+            &mut world,
+            &mut context,
+            Some(inner),
+            quad_style(),
+            (210.0, 210.0, 10.0, 10.0),
+        );
+
+        let mut state = SystemState::<Query<&Node>>::new(&mut world);
+        let primitives = context.build_render_primitives(&state.get(&world));
+
+        let quads = clipped_quads(&primitives);
+        assert_eq!(quads.len(), 1);
+        let clip = quads[0].1.unwrap();
+        assert_eq!((clip.width, clip.height), (0.0, 0.0));
+    }
+
+    #[test]
+    fn children_outside_their_parents_clip_should_be_masked() {
+        let mut world = World::new();
+        let mut context = Context::new();
+        let root = add_node(
+            &mut world,
+            &mut context,
+            None,
+            KStyle::default(),
+            (0.0, 0.0, 400.0, 300.0),
+        );
+        let clip = add_node(
+            &mut world,
+            &mut context,
+            Some(root),
+            clip_style(),
+            (0.0, 0.0, 100.0, 100.0),
+        );
+        let child = add_node(
+            &mut world,
+            &mut context,
+            Some(clip),
+            quad_style(),
+            (150.0, 50.0, 50.0, 20.0),
+        );
+        // Even a grandchild inside a non-clipping child is still clipped by the ancestor
+        add_node(
+            &mut world,
+            &mut context,
+            Some(child),
+            quad_style(),
+            (160.0, 55.0, 10.0, 10.0),
+        );
+
+        let mut state = SystemState::<Query<&Node>>::new(&mut world);
+        let primitives = context.build_render_primitives(&state.get(&world));
+
+        let quads = clipped_quads(&primitives);
+        assert_eq!(quads.len(), 2);
+        for (quad, clip) in quads {
+            let clip = clip.expect("the quad should be rendered within a clip");
+            assert_eq!(bounds(clip), (0.0, 0.0, 100.0, 100.0));
+            assert_eq!(clip.intersection(&quad).width, 0.0);
+        }
+    }
+
+    fn added_update(In((_, _)): In<(WidgetContext, Entity)>) -> bool {
+        true
+    }
+
+    #[test]
+    fn registered_widgets_should_not_replace_added_systems() {
+        let mut app = App::new();
+        app.register_widget::<KButton, _>(widget_update);
+
+        let mut context = Context::new();
+        let name = KButton::default().get_name();
+        context.add_widget_system(name.clone(), added_update);
+        add_registered_widgets(&mut app.world, &mut context);
+
+        assert!(context.systems[&name.0].name().ends_with("added_update"));
+        assert!(app
+            .world
+            .resource::<RegisteredWidgets>()
+            .systems
+            .contains(&name.0));
+    }
+
+    #[derive(Resource)]
+    struct KeyedList {
+        items: Vec<u32>,
+        styles: KStyle,
+    }
+
+    fn keyed_list_update(
+        In((widget_context, entity)): In<(WidgetContext, Entity)>,
+        mut commands: Commands,
+        list: Res<KeyedList>,
+    ) -> bool {
+        let parent_id = Some(entity);
+        for item in list.items.iter() {
+            rsx! {
+                <ElementBundle key={item} styles={list.styles.clone()} />
+            }
+        }
+        true
+    }
+
+    /// Creates a context whose root widget renders a keyed child with the given styles for each
+    /// of its items
+    fn keyed_list(styles: KStyle) -> World {
+        let mut world = World::new();
+        let mut context = Context::new();
+        context.add_widget_system("KeyedList", keyed_list_update);
+        let root = world.spawn(WidgetName("KeyedList".to_string())).id();
+        context.add_widget(None, root);
+        world.insert_resource(context);
+        world.insert_resource(KeyedList {
+            items: Vec::new(),
+            styles,
+        });
+        init_systems(&mut world);
+        world
+    }
+
+    /// Renders the keyed list with the given items, returning its children and their keys
+    fn render_keyed_list(world: &mut World, items: Vec<u32>) -> Vec<(Entity, String)> {
+        world.resource_mut::<KeyedList>().items = items;
+        update_widgets_sys(world);
+
+        let context = world.resource::<Context>();
+        let tree = context.tree.read().unwrap();
+        let children = tree
+            .child_iter(tree.root_node.unwrap())
+            .map(|child| (child.0, context.widget_keys.get(child.0).unwrap().1))
+            .collect();
+        children
+    }
+
+    #[test]
+    fn keyed_children_should_keep_their_entity_when_reordered_or_removed() {
+        let mut world = keyed_list(KStyle::default());
+
+        let rendered = render_keyed_list(&mut world, vec![1, 2, 3]);
+        let keys = rendered
+            .iter()
+            .map(|(_, key)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["1", "2", "3"]);
+
+        let reordered = render_keyed_list(&mut world, vec![3, 1, 2]);
+        assert_eq!(
+            reordered,
+            vec![
+                rendered[2].clone(),
+                rendered[0].clone(),
+                rendered[1].clone()
+            ]
+        );
+
+        let removed = render_keyed_list(&mut world, vec![3, 2]);
+        assert_eq!(removed, vec![rendered[2].clone(), rendered[1].clone()]);
+        assert!(world
+            .resource::<Context>()
+            .widget_keys
+            .get(rendered[0].0)
+            .is_none());
+    }
+
+    #[test]
+    fn removed_keyed_children_should_exit_in_place_until_added_back() {
+        let mut world = keyed_list(KStyle {
+            exit_animation: StyleProp::Value(MountAnimation::new(1.0, Easing::Linear).fade()),
+            ..Default::default()
+        });
+        let exiting = |world: &World| {
+            let context = world.resource::<Context>();
+            let root = context.tree.read().unwrap().root_node.unwrap();
+            let mount_animations = context.mount_animations.read().unwrap();
+            let exiting = mount_animations
+                .exiting_children(root)
+                .map(|exiting| (exiting.entity.0, exiting.index))
+                .collect::<Vec<_>>();
+            exiting
+        };
+
+        let rendered = render_keyed_list(&mut world, vec![1, 2, 3]);
+
+        // The removed item exits from where it was, rather than the last one
+        let removed = render_keyed_list(&mut world, vec![1, 3]);
+        assert_eq!(removed, vec![rendered[0].clone(), rendered[2].clone()]);
+        assert_eq!(exiting(&world), vec![(rendered[1].0, 1)]);
+
+        let added_back = render_keyed_list(&mut world, vec![1, 2, 3]);
+        assert_eq!(added_back, rendered);
+        assert!(exiting(&world).is_empty());
+        assert!(world
+            .resource::<Context>()
+            .mount_animations
+            .read()
+            .unwrap()
+            .get(WrappedIndex(rendered[1].0))
+            .is_none());
+    }
+}
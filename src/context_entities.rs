@@ -37,4 +37,27 @@ impl ContextEntities {
         let inner = self.ce.get(&parent_id).unwrap();
         inner.get(&T::default().type_id()).and_then(|e| Some(*e))
     }
+
+    /// Adds a context entity using the type id of its context type
+    pub(crate) fn add_context_entity_by_id(
+        &self,
+        parent_id: Entity,
+        type_id: TypeId,
+        context_entity: Entity,
+    ) {
+        self.ce
+            .entry(parent_id)
+            .or_insert_with(DashMap::new)
+            .insert(type_id, context_entity);
+    }
+
+    /// Returns the type id and entity of each context entity added to the given parent
+    pub(crate) fn get_context_entities(&self, parent_id: Entity) -> Vec<(TypeId, Entity)> {
+        self.ce.get(&parent_id).map_or(Vec::new(), |inner| {
+            inner
+                .iter()
+                .map(|entry| (*entry.key(), *entry.value()))
+                .collect()
+        })
+    }
 }
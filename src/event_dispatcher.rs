@@ -11,7 +11,7 @@ use crate::{
     input_event::{InputEvent, InputEventCategory},
     keyboard_event::{KeyboardEvent, KeyboardModifiers},
     layout::Rect,
-    node::WrappedIndex,
+    node::{Node, WrappedIndex},
     on_event::OnEvent,
     prelude::WidgetContext,
     styles::{KStyle, RenderCommand},
@@ -232,6 +232,7 @@ impl EventDispatcher {
                             context.tree.clone(),
                             context.context_entities.clone(),
                             context.layout_cache.clone(),
+                            context.widget_keys.clone(),
                         );
                         node_event.run_on_change(world, widget_context);
                     }
@@ -371,6 +372,16 @@ impl EventDispatcher {
                     let (current, depth) = stack.pop().unwrap();
                     let mut enter_children = true;
 
+                    // Hidden widgets don't receive events, and neither do widgets removed from layout
+                    // (along with their descendants)
+                    let (is_displayed, is_visible) = world
+                        .get::<Node>(current.0)
+                        .map(|node| (node.is_displayed(), node.is_visible()))
+                        .unwrap_or((true, true));
+                    if !is_displayed {
+                        continue;
+                    }
+
                     for input_event in input_events {
                         // --- Process Event --- //
                         if matches!(input_event.category(), InputEventCategory::Mouse) {
@@ -379,15 +390,17 @@ impl EventDispatcher {
 
                             match pointer_events {
                                 PointerEvents::All | PointerEvents::SelfOnly => {
-                                    let events = self.process_pointer_events(
-                                        input_event,
-                                        (current, depth),
-                                        &mut states,
-                                        world,
-                                        context,
-                                        false,
-                                    );
-                                    event_stream.extend(events);
+                                    if is_visible {
+                                        let events = self.process_pointer_events(
+                                            input_event,
+                                            (current, depth),
+                                            &mut states,
+                                            world,
+                                            context,
+                                            false,
+                                        );
+                                        event_stream.extend(events);
+                                    }
 
                                     if matches!(pointer_events, PointerEvents::SelfOnly) {
                                         enter_children = false;
@@ -499,13 +512,15 @@ impl EventDispatcher {
     ) -> Vec<Event> {
         let mut event_stream = Vec::<Event>::new();
         let (node, depth) = tree_node;
+        // The cursor position in the (untransformed) layout space of this widget
+        let mouse_position = context.to_layout_space(&node, self.current_mouse_position);
 
         match input_event {
             InputEvent::MouseMoved(point) => {
                 if let Some(layout) = context.get_layout(&node) {
                     let cursor_event = self.get_cursor_event(*point);
-                    let was_contained = layout.contains(&self.current_mouse_position);
-                    let is_contained = layout.contains(point);
+                    let was_contained = layout.contains(&mouse_position);
+                    let is_contained = layout.contains(&context.to_layout_space(&node, *point));
                     if !ignore_layout && was_contained != is_contained {
                         if was_contained {
                             event_stream
@@ -544,7 +559,7 @@ impl EventDispatcher {
             }
             InputEvent::MouseLeftPress => {
                 if let Some(layout) = context.get_layout(&node) {
-                    if ignore_layout || layout.contains(&self.current_mouse_position) {
+                    if ignore_layout || layout.contains(&mouse_position) {
                         let cursor_event = self.get_cursor_event(self.current_mouse_position);
                         event_stream.push(Event::new(node.0, EventType::MouseDown(cursor_event)));
 
@@ -565,7 +580,7 @@ impl EventDispatcher {
             }
             InputEvent::MouseLeftRelease => {
                 if let Some(layout) = context.get_layout(&node) {
-                    if ignore_layout || layout.contains(&self.current_mouse_position) {
+                    if ignore_layout || layout.contains(&mouse_position) {
                         let cursor_event = self.get_cursor_event(self.current_mouse_position);
                         event_stream.push(Event::new(node.0, EventType::MouseUp(cursor_event)));
                         // self.last_clicked.set(node);
@@ -588,7 +603,7 @@ impl EventDispatcher {
             InputEvent::Scroll { dx, dy, is_line } => {
                 if let Some(layout) = context.get_layout(&node) {
                     // Check for scroll eligibility
-                    if ignore_layout || layout.contains(&self.current_mouse_position) {
+                    if ignore_layout || layout.contains(&mouse_position) {
                         Self::update_state(
                             states,
                             (node, depth),
@@ -60,6 +60,22 @@ impl FocusTree {
         }
     }
 
+    /// Remove every index, apart from the root node, for which `is_hidden` returns true
+    ///
+    /// If the current focus is removed, focus returns to the root node.
+    pub(crate) fn remove_hidden(&mut self, is_hidden: impl Fn(WrappedIndex) -> bool) {
+        let root_node = self.tree.root_node;
+        let hidden = self
+            .tree
+            .flatten()
+            .into_iter()
+            .filter(|index| Some(*index) != root_node && is_hidden(*index))
+            .collect::<Vec<_>>();
+        for index in hidden {
+            self.remove(index);
+        }
+    }
+
     /// Checks if the given index is present in the tree
     pub fn contains(&self, index: WrappedIndex) -> bool {
         self.tree.contains(index)
@@ -306,4 +322,35 @@ mod tests {
 
         // etc.
     }
+
+    #[test]
+    fn hidden_indices_should_be_skipped_and_blurred() {
+        let mut focus_tree = FocusTree::default();
+        let mut tree = Tree::default();
+
+        let a = WrappedIndex(Entity::from_raw(0));
+        tree.add(a, None);
+        let a_a = WrappedIndex(Entity::from_raw(1));
+        tree.add(a_a, Some(a));
+        let a_a_a = WrappedIndex(Entity::from_raw(2));
+        tree.add(a_a_a, Some(a_a));
+        let a_b = WrappedIndex(Entity::from_raw(3));
+        tree.add(a_b, Some(a));
+
+        focus_tree.add(a, &tree);
+        focus_tree.add(a_a, &tree);
+        focus_tree.add(a_a_a, &tree);
+        focus_tree.add(a_b, &tree);
+        focus_tree.focus(a_a_a);
+
+        // Hide `a_a` along with its child
+        focus_tree.remove_hidden(|index| index == a_a || index == a_a_a);
+
+        assert!(!focus_tree.contains(a_a));
+        assert!(!focus_tree.contains(a_a_a));
+        assert_eq!(Some(a), focus_tree.current());
+        assert_eq!(Some(a_b), focus_tree.next());
+        assert_eq!(Some(a), focus_tree.next());
+        assert_eq!(Some(a_b), focus_tree.prev());
+    }
 }
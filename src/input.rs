@@ -11,12 +11,26 @@ use crate::{
     context::{Context, CustomEventReader},
     event_dispatcher::EventDispatcher,
     input_event::InputEvent,
+    UiScale,
 };
 
+/// Converts a cursor position reported by bevy, in logical pixels from the bottom left of the
+/// window, into UI pixels from the top left
+fn cursor_to_ui(position: Vec2, window_height: f32, logical_to_ui: f32) -> (f32, f32) {
+    (
+        position.x * logical_to_ui,
+        (window_height - position.y) * logical_to_ui,
+    )
+}
+
 pub(crate) fn process_events(world: &mut World) {
-    let window_size = if let Some(windows) = world.get_resource::<Windows>() {
+    let ui_scale = world.get_resource::<UiScale>().copied().unwrap_or_default();
+    let (window_size, logical_to_ui) = if let Some(windows) = world.get_resource::<Windows>() {
         if let Some(window) = windows.get_primary() {
-            Vec2::new(window.width(), window.height())
+            (
+                Vec2::new(window.width(), window.height()),
+                ui_scale.logical_to_ui(window),
+            )
         } else {
             // log::warn!("Couldn't find primiary window!");
             return;
@@ -62,9 +76,10 @@ pub(crate) fn process_events(world: &mut World) {
                 .last()
             {
                 // Currently, we can only handle a single MouseMoved event at a time so everything but the last needs to be skipped
-                input_events.push(InputEvent::MouseMoved((
-                    event.position.x as f32,
-                    window_size.y - event.position.y as f32,
+                input_events.push(InputEvent::MouseMoved(cursor_to_ui(
+                    event.position,
+                    window_size.y,
+                    logical_to_ui,
                 )));
             }
 
@@ -82,10 +97,13 @@ pub(crate) fn process_events(world: &mut World) {
             }
 
             for MouseWheel { x, y, unit } in custom_event_mouse_wheel.0.iter(&mouse_wheel_events) {
+                let is_line = matches!(unit, MouseScrollUnit::Line);
+                // Pixel deltas are in logical pixels, like the cursor position
+                let scale = if is_line { 1.0 } else { logical_to_ui };
                 input_events.push(InputEvent::Scroll {
-                    dx: *x,
-                    dy: *y,
-                    is_line: matches!(unit, MouseScrollUnit::Line),
+                    dx: *x * scale,
+                    dy: *y * scale,
+                    is_line,
                 })
             }
 
@@ -125,3 +143,47 @@ where
 
     r
 }
+
+#[cfg(test)]
+mod tests {
+    use super::cursor_to_ui;
+    use crate::{UiScale, UiScaleMode};
+    use bevy::{
+        prelude::Vec2,
+        window::{Window, WindowDescriptor, WindowId},
+    };
+
+    #[test]
+    fn cursor_should_be_mapped_through_the_ui_scale() {
+        // 1920x1080 logical pixels at a scale factor of 2
+        let window = Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            3840,
+            2160,
+            2.0,
+            None,
+            None,
+        );
+        let ui_scale = UiScale {
+            mode: UiScaleMode::Fixed(4.0),
+            ..Default::default()
+        };
+        let logical_to_ui = ui_scale.logical_to_ui(&window);
+        let window_height = window.height();
+
+        // The UI is 960x540 UI pixels, with the origin in the top left
+        assert_eq!(
+            cursor_to_ui(Vec2::new(0.0, window_height), window_height, logical_to_ui),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            cursor_to_ui(Vec2::new(1920.0, 0.0), window_height, logical_to_ui),
+            (960.0, 540.0)
+        );
+        assert_eq!(
+            cursor_to_ui(Vec2::new(480.0, 810.0), window_height, logical_to_ui),
+            (240.0, 135.0)
+        );
+    }
+}
@@ -1,11 +1,14 @@
 use std::collections::hash_map::Iter;
 use std::collections::HashMap;
 
-use bevy::prelude::{Entity, Query};
+use bevy::{
+    math::{Affine2, Vec2},
+    prelude::{Entity, Query},
+};
 use morphorm::Cache;
 pub use morphorm::GeometryChanged;
 
-use crate::node::WrappedIndex;
+use crate::node::{DerivedSizes, WrappedIndex};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Rect {
@@ -21,6 +24,47 @@ impl Rect {
         (point.0 >= self.posx && point.0 <= self.posx + self.width)
             && (point.1 >= self.posy && point.1 <= self.posy + self.height)
     }
+
+    /// Returns the smallest rect containing this rect after it's been transformed
+    pub fn transformed_bounds(&self, transform: &Affine2) -> Rect {
+        let corners = [
+            Vec2::new(self.posx, self.posy),
+            Vec2::new(self.posx + self.width, self.posy),
+            Vec2::new(self.posx, self.posy + self.height),
+            Vec2::new(self.posx + self.width, self.posy + self.height),
+        ]
+        .map(|corner| transform.transform_point2(corner));
+        let min = corners
+            .iter()
+            .fold(corners[0], |min, corner| min.min(*corner));
+        let max = corners
+            .iter()
+            .fold(corners[0], |max, corner| max.max(*corner));
+        Rect {
+            posx: min.x,
+            posy: min.y,
+            width: max.x - min.x,
+            height: max.y - min.y,
+            z_index: self.z_index,
+        }
+    }
+
+    /// Returns the area covered by both rects
+    ///
+    /// If the rects don't overlap, the returned rect has a width and/or height of zero.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let posx = self.posx.max(other.posx);
+        let posy = self.posy.max(other.posy);
+        let right = (self.posx + self.width).min(other.posx + other.width);
+        let bottom = (self.posy + self.height).min(other.posy + other.height);
+        Rect {
+            posx,
+            posy,
+            width: (right - posx).max(0.0),
+            height: (bottom - posy).max(0.0),
+            z_index: self.z_index,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
@@ -41,6 +85,8 @@ pub struct Size {
 pub(crate) struct LayoutCache {
     // Computed Outputs
     pub rect: HashMap<WrappedIndex, Rect>,
+    /// The composed transform of every widget that has one (or has an ancestor with one)
+    pub transforms: HashMap<WrappedIndex, Affine2>,
 
     // Intermediate Values
     space: HashMap<WrappedIndex, Space>,
@@ -70,6 +116,9 @@ pub(crate) struct LayoutCache {
     geometry_changed: HashMap<WrappedIndex, GeometryChanged>,
 
     visible: HashMap<WrappedIndex, bool>,
+
+    /// The sizes derived by the last layout, which the next layout starts from
+    pub derived_sizes: DerivedSizes,
 }
 
 impl LayoutCache {
@@ -103,6 +152,35 @@ impl LayoutCache {
         self.visible.insert(node_index, true);
     }
 
+    /// Removes all cached values for the given node
+    pub fn remove(&mut self, node_index: WrappedIndex) {
+        self.rect.remove(&node_index);
+        self.transforms.remove(&node_index);
+        self.space.remove(&node_index);
+        self.size.remove(&node_index);
+
+        self.child_width_max.remove(&node_index);
+        self.child_height_max.remove(&node_index);
+        self.child_width_sum.remove(&node_index);
+        self.child_height_sum.remove(&node_index);
+
+        self.grid_row_max.remove(&node_index);
+        self.grid_col_max.remove(&node_index);
+
+        self.horizontal_free_space.remove(&node_index);
+        self.horizontal_stretch_sum.remove(&node_index);
+
+        self.vertical_free_space.remove(&node_index);
+        self.vertical_stretch_sum.remove(&node_index);
+
+        self.stack_first_child.remove(&node_index);
+        self.stack_last_child.remove(&node_index);
+
+        self.geometry_changed.remove(&node_index);
+        self.visible.remove(&node_index);
+        self.derived_sizes.remove(&node_index);
+    }
+
     /// Attempts to initialize the node if it hasn't already been initialized.
     fn try_init(&mut self, node: WrappedIndex) {
         self.rect.entry(node).or_default();
@@ -473,3 +551,56 @@ impl LayoutEvent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Rect;
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    #[test]
+    fn intersection_should_be_the_overlapping_area() {
+        let a = rect(0.0, 0.0, 100.0, 100.0);
+        let b = rect(50.0, 25.0, 100.0, 100.0);
+        assert_eq!(a.intersection(&b), rect(50.0, 25.0, 50.0, 75.0));
+        assert_eq!(b.intersection(&a), rect(50.0, 25.0, 50.0, 75.0));
+    }
+
+    #[test]
+    fn intersection_should_be_the_inner_rect_when_nested() {
+        let outer = rect(0.0, 0.0, 100.0, 100.0);
+        let inner = rect(10.0, 20.0, 30.0, 40.0);
+        assert_eq!(outer.intersection(&inner), inner);
+        assert_eq!(inner.intersection(&outer), inner);
+    }
+
+    #[test]
+    fn intersection_should_be_empty_when_disjoint() {
+        let a = rect(0.0, 0.0, 100.0, 100.0);
+        let b = rect(200.0, 50.0, 50.0, 50.0);
+        let intersection = a.intersection(&b);
+        assert_eq!(intersection.width, 0.0);
+        assert_eq!(intersection.height, 50.0);
+
+        let c = rect(200.0, 200.0, 50.0, 50.0);
+        let intersection = a.intersection(&c);
+        assert_eq!((intersection.width, intersection.height), (0.0, 0.0));
+    }
+
+    #[test]
+    fn intersection_should_keep_its_z_index() {
+        let mut a = rect(0.0, 0.0, 100.0, 100.0);
+        a.z_index = 3.0;
+        let mut b = rect(50.0, 50.0, 100.0, 100.0);
+        b.z_index = 7.0;
+        assert_eq!(a.intersection(&b).z_index, 3.0);
+    }
+}
@@ -1,5 +1,9 @@
 #![allow(dead_code)]
 
+// Lets the paths generated by `kayak_ui_macros` resolve within this crate and its examples
+extern crate self as kayak_ui;
+
+mod animation;
 mod calculate_nodes;
 mod camera;
 mod children;
@@ -20,13 +24,19 @@ mod on_event;
 mod on_layout;
 pub(crate) mod render;
 mod render_primitive;
+mod snapshot;
 mod styles;
+mod theme;
 mod tree;
+mod ui_asset;
+mod ui_scale;
 mod widget;
 mod widget_context;
+mod widget_keys;
 mod widgets;
 mod window_size;
 
+pub use ui_scale::{UiScale, UiScaleMode};
 pub use window_size::WindowSize;
 
 pub use camera::*;
@@ -39,7 +49,12 @@ pub mod prelude {
     pub use crate::children::KChildren;
     pub use crate::context::*;
     pub use crate::render::font::FontMapping;
+    pub use crate::snapshot::{ContextEntitySnapshot, SnapshotError, UiSnapshot, WidgetSnapshot};
     pub use crate::tree::*;
+    pub use crate::ui_asset::{
+        UiAsset, UiAssetError, UiAttribute, UiAttributes, UiElement, UiWidgetRegistry,
+    };
+    pub use crate::ui_scale::{UiScale, UiScaleMode};
     pub mod widgets {
         pub use crate::widgets::*;
     }
@@ -54,10 +69,11 @@ pub mod prelude {
     pub use crate::on_event::OnEvent;
     pub use crate::on_layout::OnLayout;
     pub use crate::styles::*;
+    pub use crate::theme::{KayakTheme, ThemeFontSizes, ThemePalette, ThemeRadii, ThemeSpacing};
     pub use crate::widget::*;
     pub use crate::widget_context::*;
     pub use kayak_font::Alignment;
-    pub use kayak_ui_macros::{constructor, rsx};
+    pub use kayak_ui_macros::{constructor, rsx, Widget};
 }
 
 pub use focus_tree::Focusable;
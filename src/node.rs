@@ -1,8 +1,13 @@
-use bevy::prelude::{Component, Entity, Query};
+use bevy::{
+    ecs::query::QueryEntityError,
+    prelude::{Component, Entity, Query},
+    utils::HashMap,
+};
 
 use crate::{
     render_primitive::RenderPrimitive,
-    styles::{KStyle, StyleProp},
+    styles::{Display, KStyle, LayoutType, StyleProp, Units, Visibility},
+    tree::Tree,
 };
 
 #[derive(Component, Debug, Clone, Copy)]
@@ -38,6 +43,22 @@ impl Default for Node {
     }
 }
 
+impl Node {
+    /// Returns false if this node is removed from layout by [`Display::None`]
+    ///
+    /// Note that this doesn't check the node's ancestors.
+    pub fn is_displayed(&self) -> bool {
+        self.resolved_styles.display.resolve() != Display::None
+    }
+
+    /// Returns true if this node should be rendered and receive events
+    ///
+    /// Note that this doesn't check the node's ancestors for [`Display::None`].
+    pub fn is_visible(&self) -> bool {
+        self.is_displayed() && self.resolved_styles.visibility.resolve() == Visibility::Visible
+    }
+}
+
 /// A struct used for building a [`Node`]
 pub struct NodeBuilder {
     node: Node,
@@ -106,14 +127,75 @@ impl NodeBuilder {
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct WrappedIndex(pub Entity);
 
+/// Sizes that can only be known after an initial layout, and replace the size styles of a node
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DerivedSizes {
+    /// Sizes (in pixels) that replace the width and height styles of a node, such as those derived
+    /// from an aspect ratio
+    pub sizes: HashMap<WrappedIndex, (f32, f32)>,
+    /// Widths (in pixels) that replace the width style of a node, such as those given to stretched
+    /// children on the lines of a wrapping row
+    pub widths: HashMap<WrappedIndex, f32>,
+    /// Heights (in pixels) that replace the height style of a node, such as those of auto-sized
+    /// wrapping rows fitting all of their lines
+    pub heights: HashMap<WrappedIndex, f32>,
+}
+
+impl DerivedSizes {
+    /// Removes any sizes derived for the given node
+    pub fn remove(&mut self, node: &WrappedIndex) {
+        self.sizes.remove(node);
+        self.widths.remove(node);
+        self.heights.remove(node);
+    }
+}
+
+/// The nodes being laid out by morphorm
+pub struct NodeStore<'borrow, 'world, 'state> {
+    pub query: &'borrow Query<'world, 'state, &'static Node>,
+    /// The sizes derived from the previous layout
+    pub derived: DerivedSizes,
+    /// The number of rows and columns of the grid that each child of a grid is placed in
+    ///
+    /// Placements are clamped to these, since morphorm doesn't check them against the grid.
+    pub grid_tracks: HashMap<WrappedIndex, (usize, usize)>,
+}
+
+impl<'b, 'w, 's> NodeStore<'b, 'w, 's> {
+    pub fn new(tree: &Tree, query: &'b Query<'w, 's, &'static Node>) -> Self {
+        let mut grid_tracks = HashMap::default();
+        for node in query.iter() {
+            let styles = &node.resolved_styles;
+            if styles.layout_type.resolve_or(LayoutType::default()) != LayoutType::Grid {
+                continue;
+            }
+            let tracks = |prop: &StyleProp<Vec<Units>>| prop.resolve_or(Vec::new()).len().max(1);
+            let tracks = (tracks(&styles.grid_rows), tracks(&styles.grid_cols));
+            for child in tree.children.get(&node.id).into_iter().flatten() {
+                grid_tracks.insert(*child, tracks);
+            }
+        }
+
+        Self {
+            query,
+            derived: DerivedSizes::default(),
+            grid_tracks,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Result<&Node, QueryEntityError> {
+        self.query.get(entity)
+    }
+}
+
 impl<'a> morphorm::Node<'a> for WrappedIndex {
-    type Data = Query<'a, 'a, &'static Node>;
+    type Data = NodeStore<'a, 'a, 'a>;
 
     fn layout_type(&self, store: &'_ Self::Data) -> Option<morphorm::LayoutType> {
         if let Ok(node) = store.get(self.0) {
             return match node.resolved_styles.layout_type {
                 StyleProp::Default => Some(morphorm::LayoutType::default()),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::LayoutType::default()),
             };
         }
@@ -122,9 +204,13 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
 
     fn position_type(&self, store: &'_ Self::Data) -> Option<morphorm::PositionType> {
         if let Ok(node) = store.get(self.0) {
+            if !node.is_displayed() {
+                // Hidden nodes shouldn't take up space among their siblings
+                return Some(morphorm::PositionType::SelfDirected);
+            }
             return match node.resolved_styles.position_type {
                 StyleProp::Default => Some(morphorm::PositionType::default()),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::PositionType::default()),
             };
         }
@@ -133,9 +219,18 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
 
     fn width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Ok(node) = store.get(self.0) {
+            if !node.is_displayed() {
+                return Some(morphorm::Units::Pixels(0.0));
+            }
+            if let Some((width, _)) = store.derived.sizes.get(self) {
+                return Some(morphorm::Units::Pixels(*width));
+            }
+            if let Some(width) = store.derived.widths.get(self) {
+                return Some(morphorm::Units::Pixels(*width));
+            }
             return match node.resolved_styles.width {
                 StyleProp::Default => Some(morphorm::Units::Stretch(1.0)),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Stretch(1.0)),
             };
         }
@@ -144,9 +239,18 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
 
     fn height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Ok(node) = store.get(self.0) {
+            if !node.is_displayed() {
+                return Some(morphorm::Units::Pixels(0.0));
+            }
+            if let Some((_, height)) = store.derived.sizes.get(self) {
+                return Some(morphorm::Units::Pixels(*height));
+            }
+            if let Some(height) = store.derived.heights.get(self) {
+                return Some(morphorm::Units::Pixels(*height));
+            }
             return match node.resolved_styles.height {
                 StyleProp::Default => Some(morphorm::Units::Stretch(1.0)),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Stretch(1.0)),
             };
         }
@@ -155,9 +259,12 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
 
     fn min_width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Ok(node) = store.get(self.0) {
+            if !node.is_displayed() {
+                return Some(morphorm::Units::Pixels(0.0));
+            }
             return match node.resolved_styles.min_width {
                 StyleProp::Default => Some(morphorm::Units::Pixels(0.0)),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -166,9 +273,12 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
 
     fn min_height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Ok(node) = store.get(self.0) {
+            if !node.is_displayed() {
+                return Some(morphorm::Units::Pixels(0.0));
+            }
             return match node.resolved_styles.min_height {
                 StyleProp::Default => Some(morphorm::Units::Pixels(0.0)),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -179,7 +289,7 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
         if let Ok(node) = store.get(self.0) {
             return match node.resolved_styles.max_width {
                 StyleProp::Default => Some(morphorm::Units::Auto),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -190,7 +300,7 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
         if let Ok(node) = store.get(self.0) {
             return match node.resolved_styles.max_height {
                 StyleProp::Default => Some(morphorm::Units::Auto),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -202,10 +312,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.left {
                 StyleProp::Default => match node.resolved_styles.offset {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.left),
+                    StyleProp::Value(prop) => Some(prop.left.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -217,10 +327,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.right {
                 StyleProp::Default => match node.resolved_styles.offset {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.right),
+                    StyleProp::Value(prop) => Some(prop.right.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -232,10 +342,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.top {
                 StyleProp::Default => match node.resolved_styles.offset {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.top),
+                    StyleProp::Value(prop) => Some(prop.top.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -247,10 +357,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.bottom {
                 StyleProp::Default => match node.resolved_styles.offset {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.bottom),
+                    StyleProp::Value(prop) => Some(prop.bottom.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -294,10 +404,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.padding_left {
                 StyleProp::Default => match node.resolved_styles.padding {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.left),
+                    StyleProp::Value(prop) => Some(prop.left.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -309,10 +419,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.padding_right {
                 StyleProp::Default => match node.resolved_styles.padding {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.right),
+                    StyleProp::Value(prop) => Some(prop.right.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -324,10 +434,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.padding_top {
                 StyleProp::Default => match node.resolved_styles.padding {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.top),
+                    StyleProp::Value(prop) => Some(prop.top.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -339,10 +449,10 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
             return match node.resolved_styles.padding_bottom {
                 StyleProp::Default => match node.resolved_styles.padding {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop.bottom),
+                    StyleProp::Value(prop) => Some(prop.bottom.into()),
                     _ => Some(morphorm::Units::Auto),
                 },
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -353,7 +463,7 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
         if let Ok(node) = store.get(self.0) {
             return match node.resolved_styles.row_between {
                 StyleProp::Default => Some(morphorm::Units::Auto),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
@@ -364,34 +474,96 @@ impl<'a> morphorm::Node<'a> for WrappedIndex {
         if let Ok(node) = store.get(self.0) {
             return match node.resolved_styles.col_between {
                 StyleProp::Default => Some(morphorm::Units::Auto),
-                StyleProp::Value(prop) => Some(prop),
+                StyleProp::Value(prop) => Some(prop.into()),
                 _ => Some(morphorm::Units::Auto),
             };
         }
         Some(morphorm::Units::Auto)
     }
 
-    fn grid_rows(&self, _store: &'_ Self::Data) -> Option<Vec<morphorm::Units>> {
-        Some(vec![])
+    fn grid_rows(&self, store: &'_ Self::Data) -> Option<Vec<morphorm::Units>> {
+        if let Ok(node) = store.get(self.0) {
+            return match &node.resolved_styles.grid_rows {
+                StyleProp::Value(prop) if !prop.is_empty() => {
+                    Some(prop.iter().map(|units| (*units).into()).collect())
+                }
+                // A grid without rows is given a single one that fills it
+                _ => Some(vec![morphorm::Units::Stretch(1.0)]),
+            };
+        }
+        Some(vec![morphorm::Units::Stretch(1.0)])
     }
 
-    fn grid_cols(&self, _store: &'_ Self::Data) -> Option<Vec<morphorm::Units>> {
-        Some(vec![])
+    fn grid_cols(&self, store: &'_ Self::Data) -> Option<Vec<morphorm::Units>> {
+        if let Ok(node) = store.get(self.0) {
+            return match &node.resolved_styles.grid_cols {
+                StyleProp::Value(prop) if !prop.is_empty() => {
+                    Some(prop.iter().map(|units| (*units).into()).collect())
+                }
+                // A grid without columns is given a single one that fills it
+                _ => Some(vec![morphorm::Units::Stretch(1.0)]),
+            };
+        }
+        Some(vec![morphorm::Units::Stretch(1.0)])
     }
 
-    fn row_index(&self, _store: &'_ Self::Data) -> Option<usize> {
+    fn row_index(&self, store: &'_ Self::Data) -> Option<usize> {
+        if let Ok(node) = store.get(self.0) {
+            let index = match node.resolved_styles.row_index {
+                StyleProp::Value(prop) => prop,
+                _ => 0,
+            };
+            return match store.grid_tracks.get(self) {
+                Some((rows, _)) => Some(index.min(rows - 1)),
+                None => Some(index),
+            };
+        }
         Some(0)
     }
 
-    fn col_index(&self, _store: &'_ Self::Data) -> Option<usize> {
+    fn col_index(&self, store: &'_ Self::Data) -> Option<usize> {
+        if let Ok(node) = store.get(self.0) {
+            let index = match node.resolved_styles.col_index {
+                StyleProp::Value(prop) => prop,
+                _ => 0,
+            };
+            return match store.grid_tracks.get(self) {
+                Some((_, cols)) => Some(index.min(cols - 1)),
+                None => Some(index),
+            };
+        }
         Some(0)
     }
 
-    fn row_span(&self, _store: &'_ Self::Data) -> Option<usize> {
+    fn row_span(&self, store: &'_ Self::Data) -> Option<usize> {
+        if let Ok(node) = store.get(self.0) {
+            let span = match node.resolved_styles.row_span {
+                StyleProp::Value(prop) => prop.max(1),
+                _ => 1,
+            };
+            // Spans stop at the last row, wherever they start
+            let index = self.row_index(store).unwrap_or_default();
+            return match store.grid_tracks.get(self) {
+                Some((rows, _)) => Some(span.min(rows - index)),
+                None => Some(span),
+            };
+        }
         Some(1)
     }
 
-    fn col_span(&self, _store: &'_ Self::Data) -> Option<usize> {
+    fn col_span(&self, store: &'_ Self::Data) -> Option<usize> {
+        if let Ok(node) = store.get(self.0) {
+            let span = match node.resolved_styles.col_span {
+                StyleProp::Value(prop) => prop.max(1),
+                _ => 1,
+            };
+            // Spans stop at the last column, wherever they start
+            let index = self.col_index(store).unwrap_or_default();
+            return match store.grid_tracks.get(self) {
+                Some((_, cols)) => Some(span.min(cols - index)),
+                None => Some(span),
+            };
+        }
         Some(1)
     }
 
@@ -1,6 +1,9 @@
-use crate::{context::Context, node::Node, render_primitive::RenderPrimitive, styles::Corner};
+use crate::{
+    context::Context, node::Node, render_primitive::RenderPrimitive, styles::Corner, UiScale,
+};
 use bevy::{
     // math::Vec2,
+    math::Affine2,
     prelude::{Assets, Color, Commands, Image, Plugin, Query, Rect, Res, Vec2},
     render::{Extract, RenderApp, RenderStage},
     window::Windows,
@@ -10,7 +13,7 @@ use kayak_font::KayakFont;
 use super::{
     font::{self, FontMapping},
     image, nine_patch, texture_atlas,
-    unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadParams, UIQuadType},
 };
 
 // mod nine_patch;
@@ -33,13 +36,16 @@ pub fn extract(
     node_query: Extract<Query<&Node>>,
     images: Extract<Res<Assets<Image>>>,
     windows: Extract<Res<Windows>>,
+    ui_scale: Extract<Res<UiScale>>,
 ) {
     // dbg!("STARTED");
     let render_primitives = context.build_render_primitives(&node_query);
     // dbg!("FINISHED");
 
-    let dpi = if let Some(window) = windows.get_primary() {
-        window.scale_factor() as f32
+    // Everything is extracted in UI pixels, which the UI camera maps onto the window. Clips are
+    // the exception, since they become scissor rects in physical pixels.
+    let scale_factor = if let Some(window) = windows.get_primary() {
+        ui_scale.scale_factor(window)
     } else {
         1.0
     };
@@ -47,14 +53,30 @@ pub fn extract(
     // dbg!(&render_primitives);
 
     let mut extracted_quads = Vec::new();
+    // The rounded rect that content is currently masked to, if any
+    let mut clip_mask: Option<(Rect, Corner<f32>)> = None;
+    let mut clip_is_empty = false;
+    // The transform applied to the vertices of every quad, in logical pixels
+    let mut transform = Affine2::IDENTITY;
     for render_primitive in render_primitives {
+        // Nothing inside a fully clipped area can be seen
+        if clip_is_empty
+            && !matches!(
+                render_primitive,
+                RenderPrimitive::Clip { .. } | RenderPrimitive::Transform { .. }
+            )
+        {
+            continue;
+        }
+
+        let start = extracted_quads.len();
         match render_primitive {
             RenderPrimitive::Text { .. } => {
-                let text_quads = font::extract_texts(&render_primitive, &fonts, &font_mapping, dpi);
+                let text_quads = font::extract_texts(&render_primitive, &fonts, &font_mapping, 1.0);
                 extracted_quads.extend(text_quads);
             }
             RenderPrimitive::Image { .. } => {
-                let image_quads = image::extract_images(&render_primitive, dpi);
+                let image_quads = image::extract_images(&render_primitive, 1.0);
                 extracted_quads.extend(image_quads);
             }
             RenderPrimitive::Quad { .. } => {
@@ -63,21 +85,40 @@ pub fn extract(
             }
             RenderPrimitive::NinePatch { .. } => {
                 let nine_patch_quads =
-                    nine_patch::extract_nine_patch(&render_primitive, &images, dpi);
+                    nine_patch::extract_nine_patch(&render_primitive, &images, 1.0);
                 extracted_quads.extend(nine_patch_quads);
             }
             RenderPrimitive::TextureAtlas { .. } => {
                 let texture_atlas_quads =
-                    texture_atlas::extract_texture_atlas(&render_primitive, &images, dpi);
+                    texture_atlas::extract_texture_atlas(&render_primitive, &images, 1.0);
                 extracted_quads.extend(texture_atlas_quads);
             }
-            RenderPrimitive::Clip { layout } => {
+            RenderPrimitive::Clip {
+                layout,
+                mask_layout,
+                border_radius,
+            } => {
+                clip_is_empty = layout.width <= 0.0 || layout.height <= 0.0;
+                clip_mask = if border_radius == Corner::default() {
+                    None
+                } else {
+                    Some((
+                        Rect {
+                            min: Vec2::new(mask_layout.posx, mask_layout.posy),
+                            max: Vec2::new(
+                                mask_layout.posx + mask_layout.width,
+                                mask_layout.posy + mask_layout.height,
+                            ),
+                        },
+                        border_radius,
+                    ))
+                };
                 extracted_quads.push(ExtractQuadBundle {
                     extracted_quad: ExtractedQuad {
                         rect: Rect {
-                            min: Vec2::new(layout.posx, layout.posy) * dpi,
+                            min: Vec2::new(layout.posx, layout.posy) * scale_factor,
                             max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height)
-                                * dpi,
+                                * scale_factor,
                         },
                         color: Color::default(),
                         vertex_index: 0,
@@ -90,11 +131,35 @@ pub fn extract(
                         image: None,
                         uv_min: None,
                         uv_max: None,
+                        params: QuadParams::default(),
+                        transform: Affine2::IDENTITY,
                     },
                 });
             }
+            RenderPrimitive::Transform {
+                transform: new_transform,
+            } => {
+                transform = new_transform;
+            }
             _ => {}
         }
+
+        if transform != Affine2::IDENTITY {
+            for quad in extracted_quads[start..].iter_mut() {
+                if quad.extracted_quad.quad_type != UIQuadType::Clip {
+                    quad.extracted_quad.transform = transform;
+                }
+            }
+        }
+
+        if let Some((clip_bounds, clip_radius)) = clip_mask {
+            for quad in extracted_quads[start..].iter_mut() {
+                if quad.extracted_quad.quad_type != UIQuadType::Clip {
+                    quad.extracted_quad.params.clip_bounds = clip_bounds;
+                    quad.extracted_quad.params.clip_radius = clip_radius;
+                }
+            }
+        }
     }
 
     // dbg!(&extracted_quads);
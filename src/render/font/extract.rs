@@ -1,11 +1,11 @@
 use bevy::{
-    math::Vec2,
+    math::{Affine2, Vec2},
     prelude::{Assets, Rect, Res},
 };
 use kayak_font::KayakFont;
 
 use crate::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadParams, UIQuadType},
     render_primitive::RenderPrimitive,
     styles::Corner,
 };
@@ -64,6 +64,8 @@ pub fn extract_texts(
                 image: None,
                 uv_max: None,
                 uv_min: None,
+                params: QuadParams::default(),
+                transform: Affine2::IDENTITY,
             },
         });
     }
@@ -1,27 +1,46 @@
 use crate::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::{
+        quad::extract_box_shadows,
+        unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadParams, UIQuadType},
+    },
     render_primitive::RenderPrimitive,
     styles::Corner,
 };
-use bevy::{math::Vec2, prelude::Rect, render::color::Color};
+use bevy::{
+    math::{Affine2, Vec2},
+    prelude::Rect,
+    render::color::Color,
+};
 
 pub fn extract_images(render_command: &RenderPrimitive, dpi: f32) -> Vec<ExtractQuadBundle> {
-    let (border_radius, layout, handle) = match render_command {
+    let (border_radius, layout, handle, opacity, box_shadow) = match render_command {
         RenderPrimitive::Image {
             border_radius,
             layout,
             handle,
-        } => (*border_radius, layout, handle),
+            opacity,
+            box_shadow,
+        } => (*border_radius, layout, handle, *opacity, box_shadow),
         _ => panic!(""),
     };
 
-    vec![ExtractQuadBundle {
+    let bounds = Rect {
+        min: Vec2::new(layout.posx, layout.posy) * dpi,
+        max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height) * dpi,
+    };
+
+    let mut quads = extract_box_shadows(
+        bounds,
+        border_radius,
+        box_shadow,
+        false,
+        layout.z_index,
+        dpi,
+    );
+    quads.push(ExtractQuadBundle {
         extracted_quad: ExtractedQuad {
-            rect: Rect {
-                min: Vec2::new(layout.posx, layout.posy) * dpi,
-                max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height) * dpi,
-            },
-            color: Color::WHITE,
+            rect: bounds,
+            color: Color::rgba(1.0, 1.0, 1.0, opacity),
             vertex_index: 0,
             char_id: 0,
             z_index: layout.z_index,
@@ -37,6 +56,26 @@ pub fn extract_images(render_command: &RenderPrimitive, dpi: f32) -> Vec<Extract
             image: Some(handle.clone_weak()),
             uv_max: None,
             uv_min: None,
+            params: if border_radius == Corner::default() {
+                QuadParams::default()
+            } else {
+                QuadParams {
+                    bounds,
+                    radius: border_radius * dpi,
+                    ..Default::default()
+                }
+            },
+            transform: Affine2::IDENTITY,
         },
-    }]
+    });
+    quads.extend(extract_box_shadows(
+        bounds,
+        border_radius,
+        box_shadow,
+        true,
+        layout.z_index,
+        dpi,
+    ));
+
+    quads
 }
@@ -1,10 +1,10 @@
 use crate::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadParams, UIQuadType},
     render_primitive::RenderPrimitive,
     styles::Corner,
 };
 use bevy::{
-    math::Vec2,
+    math::{Affine2, Vec2},
     prelude::{Assets, Rect, Res},
     render::{color::Color, texture::Image},
 };
@@ -16,12 +16,13 @@ pub fn extract_nine_patch(
 ) -> Vec<ExtractQuadBundle> {
     let mut extracted_quads = Vec::new();
 
-    let (layout, handle, border) = match render_primitive {
+    let (layout, handle, border, opacity) = match render_primitive {
         RenderPrimitive::NinePatch {
             layout,
             handle,
             border,
-        } => (layout, handle, border),
+            opacity,
+        } => (layout, handle, border, *opacity),
         _ => panic!(""),
     };
 
@@ -46,7 +47,7 @@ pub fn extract_nine_patch(
             min: Vec2::ZERO,
             max: Vec2::ZERO,
         },
-        color: Color::WHITE,
+        color: Color::rgba(1.0, 1.0, 1.0, opacity),
         vertex_index: 0,
         char_id: 0,
         z_index: layout.z_index,
@@ -57,6 +58,8 @@ pub fn extract_nine_patch(
         image: Some(handle.clone_weak()),
         uv_max: None,
         uv_min: None,
+        params: QuadParams::default(),
+        transform: Affine2::IDENTITY,
     };
 
     // TOP
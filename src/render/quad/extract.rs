@@ -1,86 +1,465 @@
 use crate::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadParams, UIQuadType},
     render_primitive::RenderPrimitive,
-    styles::Corner,
+    styles::{BoxShadow, Corner, Edge},
+};
+use bevy::{
+    math::{Affine2, Vec2},
+    prelude::{Color, Rect},
 };
-use bevy::{math::Vec2, prelude::Rect};
 
 pub fn extract_quads(render_primitive: &RenderPrimitive, dpi: f32) -> Vec<ExtractQuadBundle> {
-    let (background_color, border_color, layout, border_radius, mut border) = match render_primitive
-    {
+    let (
+        background_color,
+        background_gradient,
+        border_color,
+        layout,
+        border_radius,
+        mut border,
+        border_style,
+        box_shadow,
+    ) = match render_primitive {
         RenderPrimitive::Quad {
             background_color,
+            background_gradient,
             border_color,
             layout,
             border_radius,
             border,
+            border_style,
+            box_shadow,
         } => (
             *background_color,
+            background_gradient,
             *border_color,
             *layout,
             *border_radius,
             *border,
+            *border_style,
+            box_shadow,
         ),
         _ => panic!(""),
     };
 
     border *= dpi;
 
-    vec![
-        // Border
-        ExtractQuadBundle {
+    let bounds = Rect {
+        min: Vec2::new(layout.posx, layout.posy),
+        max: Vec2::new(
+            layout.posx + (layout.width * dpi),
+            layout.posy + (layout.height * dpi),
+        ),
+    };
+
+    let mut quads = extract_box_shadows(
+        bounds,
+        border_radius,
+        box_shadow,
+        false,
+        layout.z_index,
+        dpi,
+    );
+
+    // Gradients are drawn by their own quad type, underneath the border
+    let background_color = if let Some(gradient) = background_gradient {
+        let background_rect = Rect {
+            min: bounds.min + Vec2::new(border.left, border.top),
+            max: bounds.max - Vec2::new(border.right, border.bottom),
+        };
+        quads.push(ExtractQuadBundle {
             extracted_quad: ExtractedQuad {
-                rect: Rect {
-                    min: Vec2::new(layout.posx, layout.posy),
-                    max: Vec2::new(
-                        layout.posx + (layout.width * dpi),
-                        layout.posy + (layout.height * dpi),
-                    ),
-                },
-                color: border_color,
+                rect: background_rect,
+                color: Color::WHITE,
                 vertex_index: 0,
                 char_id: 0,
                 z_index: layout.z_index,
                 font_handle: None,
-                quad_type: UIQuadType::Quad,
+                quad_type: UIQuadType::Gradient,
                 type_index: 0,
-                border_radius: Corner {
-                    top_left: border_radius.top_left,
-                    top_right: border_radius.top_right,
-                    bottom_left: border_radius.bottom_left,
-                    bottom_right: border_radius.bottom_right,
-                },
+                border_radius: Corner::default(),
                 image: None,
                 uv_max: None,
                 uv_min: None,
+                params: QuadParams {
+                    bounds: background_rect,
+                    radius: inner_radius(border_radius, border),
+                    gradient: Some(gradient.clone()),
+                    ..Default::default()
+                },
+                transform: Affine2::IDENTITY,
+            },
+        });
+        Color::rgba(1.0, 1.0, 1.0, 0.0)
+    } else {
+        background_color
+    };
+
+    // The background and every side of the border are drawn by a single quad
+    let params = if border == Edge::default() && border_radius == Corner::default() {
+        // Plain quads can share their shader parameters
+        QuadParams::default()
+    } else {
+        QuadParams {
+            bounds,
+            radius: border_radius,
+            border,
+            border_color,
+            border_style,
+            ..Default::default()
+        }
+    };
+    quads.push(ExtractQuadBundle {
+        extracted_quad: ExtractedQuad {
+            rect: bounds,
+            color: background_color,
+            vertex_index: 0,
+            char_id: 0,
+            z_index: layout.z_index,
+            font_handle: None,
+            quad_type: UIQuadType::Quad,
+            type_index: 0,
+            border_radius: Corner {
+                top_left: border_radius.top_left,
+                top_right: border_radius.top_right,
+                bottom_left: border_radius.bottom_left,
+                bottom_right: border_radius.bottom_right,
             },
+            image: None,
+            uv_max: None,
+            uv_min: None,
+            params,
+            transform: Affine2::IDENTITY,
         },
-        ExtractQuadBundle {
-            extracted_quad: ExtractedQuad {
-                rect: Rect {
-                    min: Vec2::new(layout.posx + border.left, layout.posy + border.top),
-                    max: Vec2::new(
-                        (layout.posx + (layout.width * dpi)) - border.right,
-                        (layout.posy + (layout.height * dpi)) - border.bottom,
-                    ),
-                },
-                color: background_color,
-                vertex_index: 0,
-                char_id: 0,
-                z_index: layout.z_index,
-                font_handle: None,
-                quad_type: UIQuadType::Quad,
-                type_index: 0,
-                border_radius: Corner {
-                    top_left: border_radius.top_left,
-                    top_right: border_radius.top_right,
-                    bottom_left: border_radius.bottom_left,
-                    bottom_right: border_radius.bottom_right,
+    });
+
+    quads.extend(extract_box_shadows(
+        bounds,
+        border_radius,
+        box_shadow,
+        true,
+        layout.z_index,
+        dpi,
+    ));
+
+    quads
+}
+
+/// The radii of the inner edge of a border
+///
+/// This matches the inner radii computed in the quad shader.
+fn inner_radius(border_radius: Corner<f32>, border: Edge<f32>) -> Corner<f32> {
+    Corner {
+        top_left: (border_radius.top_left - border.top.max(border.left)).max(0.0),
+        top_right: (border_radius.top_right - border.top.max(border.right)).max(0.0),
+        bottom_left: (border_radius.bottom_left - border.bottom.max(border.left)).max(0.0),
+        bottom_right: (border_radius.bottom_right - border.bottom.max(border.right)).max(0.0),
+    }
+}
+
+/// Extracts the outer (or inset) shadows of a widget
+///
+/// Outer shadows should be drawn before the widget and inset shadows after it.
+pub fn extract_box_shadows(
+    bounds: Rect,
+    border_radius: Corner<f32>,
+    box_shadow: &[BoxShadow],
+    inset: bool,
+    z_index: f32,
+    dpi: f32,
+) -> Vec<ExtractQuadBundle> {
+    box_shadow
+        .iter()
+        .filter(|shadow| shadow.inset == inset && shadow.color.a() > 0.0)
+        .filter_map(|shadow| {
+            let shadow = BoxShadow {
+                offset: shadow.offset * dpi,
+                blur_radius: shadow.blur_radius.max(0.0) * dpi,
+                spread: shadow.spread * dpi,
+                ..*shadow
+            };
+
+            let rect = if inset {
+                bounds
+            } else {
+                // The shadow covers the (offset and spread) bounds, plus its blurred edges
+                let grow = Vec2::splat(shadow.spread + shadow.blur_radius);
+                let rect = Rect {
+                    min: bounds.min + shadow.offset - grow,
+                    max: bounds.max + shadow.offset + grow,
+                };
+                if rect.width() <= 0.0 || rect.height() <= 0.0 {
+                    return None;
+                }
+                rect
+            };
+
+            Some(ExtractQuadBundle {
+                extracted_quad: ExtractedQuad {
+                    rect,
+                    color: shadow.color,
+                    vertex_index: 0,
+                    char_id: 0,
+                    z_index,
+                    font_handle: None,
+                    quad_type: UIQuadType::BoxShadow,
+                    type_index: 0,
+                    border_radius: Corner::default(),
+                    image: None,
+                    uv_max: None,
+                    uv_min: None,
+                    params: QuadParams {
+                        bounds,
+                        radius: border_radius * dpi,
+                        shadow,
+                        ..Default::default()
+                    },
+                    transform: Affine2::IDENTITY,
                 },
-                image: None,
-                uv_max: None,
-                uv_min: None,
-            },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::Vec2,
+        prelude::{Color, Rect},
+    };
+
+    use super::extract_quads;
+    use crate::{
+        layout,
+        render::unified::pipeline::{ExtractQuadBundle, QuadParams, UIQuadType},
+        render_primitive::RenderPrimitive,
+        styles::{
+            Background, BorderStyle, BoxShadow, ColorStop, Corner, Edge, Gradient, KStyle,
+            RenderCommand, StyleProp,
         },
-    ]
+    };
+
+    /// A quad with the given styles, laid out at (10, 20) with a size of 100x50
+    fn quad(styles: KStyle) -> RenderPrimitive {
+        let mut primitive = RenderPrimitive::from(&KStyle {
+            render_command: StyleProp::Value(RenderCommand::Quad),
+            ..styles
+        });
+        primitive.set_layout(layout::Rect {
+            posx: 10.0,
+            posy: 20.0,
+            width: 100.0,
+            height: 50.0,
+            z_index: 0.0,
+        });
+        primitive
+    }
+
+    fn with_shadows(box_shadow: Vec<BoxShadow>) -> KStyle {
+        KStyle {
+            box_shadow: StyleProp::Value(box_shadow),
+            ..Default::default()
+        }
+    }
+
+    fn quad_types(quads: &[ExtractQuadBundle]) -> Vec<UIQuadType> {
+        quads
+            .iter()
+            .map(|quad| quad.extracted_quad.quad_type)
+            .collect()
+    }
+
+    #[test]
+    fn outer_shadows_should_be_drawn_before_the_quad_and_inset_shadows_after() {
+        let quads = extract_quads(
+            &quad(with_shadows(vec![
+                BoxShadow::new(0.0, 4.0, 0.0, 0.0, Color::BLACK).inset(),
+                BoxShadow::new(0.0, 4.0, 0.0, 0.0, Color::BLACK),
+            ])),
+            1.0,
+        );
+        assert_eq!(
+            quad_types(&quads),
+            vec![
+                UIQuadType::BoxShadow,
+                UIQuadType::Quad,
+                UIQuadType::BoxShadow
+            ]
+        );
+        assert!(!quads[0].extracted_quad.params.shadow.inset);
+        assert!(quads[2].extracted_quad.params.shadow.inset);
+    }
+
+    #[test]
+    fn outer_shadows_should_cover_their_offset_spread_and_blur() {
+        let quads = extract_quads(
+            &quad(with_shadows(vec![BoxShadow::new(
+                5.0,
+                10.0,
+                4.0,
+                2.0,
+                Color::BLACK,
+            )])),
+            1.0,
+        );
+        let shadow = &quads[0].extracted_quad;
+        assert_eq!(shadow.quad_type, UIQuadType::BoxShadow);
+        // The widget's bounds, offset by (5, 10) and grown by the spread and blur on every side
+        assert_eq!(
+            shadow.rect,
+            Rect {
+                min: Vec2::new(9.0, 24.0),
+                max: Vec2::new(121.0, 86.0),
+            }
+        );
+        // The shader draws the shadow relative to the widget itself
+        assert_eq!(
+            shadow.params.bounds,
+            Rect {
+                min: Vec2::new(10.0, 20.0),
+                max: Vec2::new(110.0, 70.0),
+            }
+        );
+    }
+
+    #[test]
+    fn inset_shadows_should_cover_the_quad() {
+        let quads = extract_quads(
+            &quad(with_shadows(vec![BoxShadow::new(
+                5.0,
+                10.0,
+                4.0,
+                2.0,
+                Color::BLACK,
+            )
+            .inset()])),
+            1.0,
+        );
+        assert_eq!(quads[1].extracted_quad.rect, quads[0].extracted_quad.rect);
+    }
+
+    #[test]
+    fn shadows_should_be_scaled_by_the_dpi() {
+        let quads = extract_quads(
+            &quad(with_shadows(vec![BoxShadow::new(
+                1.0,
+                2.0,
+                3.0,
+                4.0,
+                Color::BLACK,
+            )])),
+            2.0,
+        );
+        let shadow = &quads[0].extracted_quad.params.shadow;
+        assert_eq!(shadow.offset, Vec2::new(2.0, 4.0));
+        assert_eq!(shadow.blur_radius, 6.0);
+        assert_eq!(shadow.spread, 8.0);
+    }
+
+    #[test]
+    fn invisible_shadows_should_be_skipped() {
+        let quads = extract_quads(
+            &quad(with_shadows(vec![
+                // Fully transparent
+                BoxShadow::new(0.0, 4.0, 4.0, 0.0, Color::NONE),
+                // Shrunk past the widget's height by its spread
+                BoxShadow::new(0.0, 0.0, 0.0, -30.0, Color::BLACK),
+            ])),
+            1.0,
+        );
+        assert_eq!(quad_types(&quads), vec![UIQuadType::Quad]);
+    }
+
+    #[test]
+    fn gradients_should_be_drawn_inside_the_border() {
+        let gradient = Gradient::linear(
+            90.0,
+            vec![
+                ColorStop::new(Color::RED, 0.0),
+                ColorStop::new(Color::BLUE, 1.0),
+            ],
+        );
+        let quads = extract_quads(
+            &quad(KStyle {
+                background: StyleProp::Value(Background::Gradient(gradient.clone())),
+                // Replaced by the gradient
+                background_color: StyleProp::Value(Color::GREEN),
+                border: StyleProp::Value(Edge::all(2.0)),
+                border_radius: StyleProp::Value(Corner::all(10.0)),
+                ..Default::default()
+            }),
+            1.0,
+        );
+        assert_eq!(
+            quad_types(&quads),
+            vec![UIQuadType::Gradient, UIQuadType::Quad]
+        );
+
+        let background = &quads[0].extracted_quad;
+        assert_eq!(
+            background.rect,
+            Rect {
+                min: Vec2::new(12.0, 22.0),
+                max: Vec2::new(108.0, 68.0),
+            }
+        );
+        assert_eq!(background.params.gradient, Some(gradient));
+        // The corners follow the inner edge of the border
+        assert_eq!(background.params.radius, Corner::all(8.0));
+
+        // The quad on top only draws the border
+        assert_eq!(quads[1].extracted_quad.color.a(), 0.0);
+        assert_eq!(quads[1].extracted_quad.params.gradient, None);
+    }
+
+    fn bordered() -> KStyle {
+        KStyle {
+            border: StyleProp::Value(Edge::new(1.0, 2.0, 3.0, 4.0)),
+            border_color: StyleProp::Value(Color::RED),
+            border_color_left: StyleProp::Value(Color::BLUE),
+            border_radius: StyleProp::Value(Corner::new(5.0, 6.0, 7.0, 8.0)),
+            border_style: StyleProp::Value(BorderStyle::Dashed),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn borders_should_keep_their_corners_and_sides() {
+        let quads = extract_quads(&quad(bordered()), 1.0);
+        assert_eq!(quad_types(&quads), vec![UIQuadType::Quad]);
+
+        let params = &quads[0].extracted_quad.params;
+        assert_eq!(params.radius, Corner::new(5.0, 6.0, 7.0, 8.0));
+        assert_eq!(params.border, Edge::new(1.0, 2.0, 3.0, 4.0));
+        // The specific sides take precedence over `border_color`
+        assert_eq!(
+            params.border_color,
+            Edge::new(Color::RED, Color::RED, Color::RED, Color::BLUE)
+        );
+        assert_eq!(params.border_style, BorderStyle::Dashed);
+    }
+
+    #[test]
+    fn borders_should_be_scaled_by_the_dpi() {
+        let quads = extract_quads(&quad(bordered()), 2.0);
+        assert_eq!(
+            quads[0].extracted_quad.params.border,
+            Edge::new(2.0, 4.0, 6.0, 8.0)
+        );
+    }
+
+    #[test]
+    fn plain_quads_should_share_their_params() {
+        let quads = extract_quads(&quad(KStyle::default()), 1.0);
+        assert_eq!(quads[0].extracted_quad.params, QuadParams::default());
+
+        // Even a dashed border style needs no params without a border to draw
+        let quads = extract_quads(
+            &quad(KStyle {
+                border_style: StyleProp::Value(BorderStyle::Dashed),
+                ..Default::default()
+            }),
+            1.0,
+        );
+        assert_eq!(quads[0].extracted_quad.params, QuadParams::default());
+    }
 }
@@ -1,10 +1,10 @@
 use crate::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadParams, UIQuadType},
     render_primitive::RenderPrimitive,
     styles::Corner,
 };
 use bevy::{
-    math::Vec2,
+    math::{Affine2, Vec2},
     prelude::{Assets, Rect, Res},
     render::{color::Color, texture::Image},
 };
@@ -16,13 +16,14 @@ pub fn extract_texture_atlas(
 ) -> Vec<ExtractQuadBundle> {
     let mut extracted_quads = Vec::new();
 
-    let (size, position, layout, handle) = match render_primitive {
+    let (size, position, layout, handle, opacity) = match render_primitive {
         RenderPrimitive::TextureAtlas {
             size,
             position,
             layout,
             handle,
-        } => (size, position, layout, handle),
+            opacity,
+        } => (size, position, layout, handle, *opacity),
         _ => panic!(""),
     };
 
@@ -56,7 +57,7 @@ pub fn extract_texture_atlas(
                 (position.x + size.x) / image_size.x,
                 1.0 - (position.y / image_size.y),
             )),
-            color: Color::WHITE,
+            color: Color::rgba(1.0, 1.0, 1.0, opacity),
             vertex_index: 0,
             char_id: 0,
             z_index: layout.z_index,
@@ -65,6 +66,8 @@ pub fn extract_texture_atlas(
             type_index: 0,
             border_radius: Corner::default(),
             image: Some(handle.clone_weak()),
+            params: QuadParams::default(),
+            transform: Affine2::IDENTITY,
         },
     };
     extracted_quads.push(quad);
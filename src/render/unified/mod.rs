@@ -12,7 +12,7 @@ use crate::{
         ui_pass::TransparentUI,
         unified::pipeline::{DrawUI, QuadMeta, UnifiedPipeline},
     },
-    WindowSize,
+    UiScale, WindowSize,
 };
 
 use self::pipeline::ImageBindGroups;
@@ -53,6 +53,7 @@ impl Plugin for UnifiedRenderPlugin {
     }
 }
 
+/// The number of physical pixels covered by a UI pixel
 #[derive(Resource)]
 pub struct Dpi(f32);
 
@@ -60,9 +61,10 @@ pub fn extract_baseline(
     mut commands: Commands,
     windows: Extract<Res<Windows>>,
     window_size: Extract<Res<WindowSize>>,
+    ui_scale: Extract<Res<UiScale>>,
 ) {
     let dpi = if let Some(window) = windows.get_primary() {
-        window.scale_factor() as f32
+        ui_scale.scale_factor(window)
     } else {
         1.0
     };
@@ -6,7 +6,7 @@ use bevy::{
         lifetimeless::{Read, SQuery, SRes},
         SystemState,
     },
-    math::{Mat4, Quat, Vec2, Vec3, Vec4},
+    math::{Affine2, Mat4, Quat, Vec2, Vec3, Vec4},
     prelude::{Bundle, Component, Entity, FromWorld, Handle, Query, Res, ResMut, World},
     render::{
         color::Color,
@@ -37,7 +37,7 @@ use kayak_font::{
 };
 
 use super::{Dpi, UNIFIED_SHADER_HANDLE};
-use crate::prelude::Corner;
+use crate::prelude::{BorderStyle, BoxShadow, Corner, Edge, Gradient, GradientKind};
 use crate::render::ui_pass::TransparentUI;
 use crate::WindowSize;
 
@@ -96,9 +96,7 @@ impl FromWorld for UnifiedPipeline {
                 ty: BindingType::Buffer {
                     ty: BufferBindingType::Uniform,
                     has_dynamic_offset: true,
-                    // TODO: change this to ViewUniform::std140_size_static once crevice fixes this!
-                    // Context: https://github.com/LPGhatguy/crevice/issues/29
-                    min_binding_size: BufferSize::new(16),
+                    min_binding_size: Some(QuadType::min_size()),
                 },
                 count: None,
             }],
@@ -308,6 +306,31 @@ pub enum UIQuadType {
     Text,
     Image,
     Clip,
+    BoxShadow,
+    Gradient,
+}
+
+/// Additional shader parameters for quads that need more than a color and a texture
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct QuadParams {
+    /// The bounds of the widget this quad belongs to, in the same space as the quad's rect
+    pub bounds: Rect,
+    /// The corner radii of the widget this quad belongs to
+    pub radius: Corner<f32>,
+    /// The border widths of a [`UIQuadType::Quad`]
+    pub border: Edge<f32>,
+    /// The border colors of a [`UIQuadType::Quad`]
+    pub border_color: Edge<Color>,
+    /// The border line style of a [`UIQuadType::Quad`]
+    pub border_style: BorderStyle,
+    /// The shadow drawn by a [`UIQuadType::BoxShadow`] quad
+    pub shadow: BoxShadow,
+    /// The gradient drawn by a [`UIQuadType::Gradient`] quad
+    pub gradient: Option<Gradient>,
+    /// The rounded rect this quad is masked to, in the same space as the quad's rect
+    pub clip_bounds: Rect,
+    /// The corner radii of the rounded clip mask, no mask is applied if these are all zero
+    pub clip_radius: Corner<f32>,
 }
 
 #[derive(Debug, Component, Clone)]
@@ -324,6 +347,9 @@ pub struct ExtractedQuad {
     pub image: Option<Handle<Image>>,
     pub uv_min: Option<Vec2>,
     pub uv_max: Option<Vec2>,
+    pub params: QuadParams,
+    /// The transform applied to the vertices of this quad
+    pub transform: Affine2,
 }
 
 #[repr(C)]
@@ -339,9 +365,140 @@ struct QuadVertex {
 #[derive(Copy, Clone, ShaderType)]
 struct QuadType {
     pub t: i32,
+    pub inset: i32,
+    pub stop_count: i32,
+    /// `0` for linear gradients, `1` for radial gradients
+    pub gradient_kind: i32,
+    /// `0` for solid borders, `1` for dashed borders, `2` for dotted borders
+    pub border_style: i32,
     pub _padding_1: i32,
     pub _padding_2: i32,
     pub _padding_3: i32,
+    /// The bounds of the widget (x, y, width, height), relative to the quad
+    pub bounds: Vec4,
+    /// The corner radii of the widget (top left, top right, bottom right, bottom left)
+    pub radius: Vec4,
+    /// The border widths (top, right, bottom, left)
+    pub border: Vec4,
+    /// The (linear) border colors (top, right, bottom, left)
+    pub border_colors: [Vec4; 4],
+    /// The shadow offset (x, y), blur radius, and spread
+    pub shadow: Vec4,
+    /// The gradient direction (x, y) if linear, or its relative center (x, y) if radial
+    pub gradient: Vec4,
+    /// The (linear) gradient stop colors
+    pub stop_colors: [Vec4; Gradient::MAX_STOPS],
+    /// The gradient stop positions, packed four per vector
+    pub stop_positions: [Vec4; Gradient::MAX_STOPS / 4],
+    /// The bounds of the rounded clip mask (x, y, width, height), relative to the quad
+    pub clip_bounds: Vec4,
+    /// The corner radii of the rounded clip mask (top left, top right, bottom right, bottom left)
+    pub clip_radius: Vec4,
+}
+
+impl QuadType {
+    fn new(t: i32) -> Self {
+        Self {
+            t,
+            inset: 0,
+            stop_count: 0,
+            gradient_kind: 0,
+            border_style: 0,
+            _padding_1: 0,
+            _padding_2: 0,
+            _padding_3: 0,
+            bounds: Vec4::ZERO,
+            radius: Vec4::ZERO,
+            border: Vec4::ZERO,
+            border_colors: [Vec4::ZERO; 4],
+            shadow: Vec4::ZERO,
+            gradient: Vec4::ZERO,
+            stop_colors: [Vec4::ZERO; Gradient::MAX_STOPS],
+            stop_positions: [Vec4::ZERO; Gradient::MAX_STOPS / 4],
+            clip_bounds: Vec4::ZERO,
+            clip_radius: Vec4::ZERO,
+        }
+    }
+
+    fn with_params(t: i32, quad_rect: Rect, params: &QuadParams) -> Self {
+        let offset = params.bounds.min - quad_rect.min;
+        let clip_offset = params.clip_bounds.min - quad_rect.min;
+        let mut quad_type = Self {
+            t,
+            inset: params.shadow.inset as i32,
+            border_style: match params.border_style {
+                BorderStyle::Solid => 0,
+                BorderStyle::Dashed => 1,
+                BorderStyle::Dotted => 2,
+            },
+            bounds: Vec4::new(
+                offset.x,
+                offset.y,
+                params.bounds.width(),
+                params.bounds.height(),
+            ),
+            radius: Vec4::new(
+                params.radius.top_left,
+                params.radius.top_right,
+                params.radius.bottom_right,
+                params.radius.bottom_left,
+            ),
+            border: Vec4::new(
+                params.border.top,
+                params.border.right,
+                params.border.bottom,
+                params.border.left,
+            ),
+            border_colors: [
+                Vec4::from(params.border_color.top.as_linear_rgba_f32()),
+                Vec4::from(params.border_color.right.as_linear_rgba_f32()),
+                Vec4::from(params.border_color.bottom.as_linear_rgba_f32()),
+                Vec4::from(params.border_color.left.as_linear_rgba_f32()),
+            ],
+            shadow: Vec4::new(
+                params.shadow.offset.x,
+                params.shadow.offset.y,
+                params.shadow.blur_radius,
+                params.shadow.spread,
+            ),
+            clip_bounds: Vec4::new(
+                clip_offset.x,
+                clip_offset.y,
+                params.clip_bounds.width(),
+                params.clip_bounds.height(),
+            ),
+            clip_radius: Vec4::new(
+                params.clip_radius.top_left,
+                params.clip_radius.top_right,
+                params.clip_radius.bottom_right,
+                params.clip_radius.bottom_left,
+            ),
+            ..Self::new(t)
+        };
+
+        if let Some(gradient) = &params.gradient {
+            quad_type.gradient = match gradient.kind {
+                GradientKind::Linear { angle } => {
+                    // 0 degrees points up (towards negative y) and turns clockwise
+                    let angle = angle.to_radians();
+                    Vec4::new(angle.sin(), -angle.cos(), 0.0, 0.0)
+                }
+                GradientKind::Radial { center } => {
+                    quad_type.gradient_kind = 1;
+                    Vec4::new(center.x, center.y, 0.0, 0.0)
+                }
+            };
+
+            let stops = gradient.stops.iter().take(Gradient::MAX_STOPS);
+            quad_type.stop_count = stops.len() as i32;
+            for (index, stop) in stops.enumerate() {
+                quad_type.stop_colors[index] = Vec4::from(stop.color.as_linear_rgba_f32());
+                quad_type.stop_positions[index / 4][index % 4] = stop.position;
+            }
+        }
+
+        quad_type
+    }
 }
 
 #[derive(Resource)]
@@ -382,28 +539,9 @@ pub fn prepare_quads(
 
     sprite_meta.types_buffer.clear();
     // sprite_meta.types_buffer.reserve(2, &render_device);
-    let quad_type_offset = sprite_meta.types_buffer.push(QuadType {
-        t: 0,
-        _padding_1: 0,
-        _padding_2: 0,
-        _padding_3: 0,
-    });
-    let text_type_offset = sprite_meta.types_buffer.push(QuadType {
-        t: 1,
-        _padding_1: 0,
-        _padding_2: 0,
-        _padding_3: 0,
-    });
-    let image_type_offset = sprite_meta.types_buffer.push(QuadType {
-        t: 2,
-        _padding_1: 0,
-        _padding_2: 0,
-        _padding_3: 0,
-    });
-
-    sprite_meta
-        .types_buffer
-        .write_buffer(&render_device, &render_queue);
+    let quad_type_offset = sprite_meta.types_buffer.push(QuadType::new(0));
+    let text_type_offset = sprite_meta.types_buffer.push(QuadType::new(1));
+    let image_type_offset = sprite_meta.types_buffer.push(QuadType::new(2));
 
     sprite_meta.vertices.clear();
     sprite_meta.vertices.reserve(
@@ -419,11 +557,28 @@ pub fn prepare_quads(
         let sprite_rect = extracted_sprite.rect;
         let color = extracted_sprite.color.as_linear_rgba_f32();
 
-        match extracted_sprite.quad_type {
-            UIQuadType::Quad => extracted_sprite.type_index = quad_type_offset,
-            UIQuadType::Text => extracted_sprite.type_index = text_type_offset,
-            UIQuadType::Image => extracted_sprite.type_index = image_type_offset,
-            UIQuadType::Clip => {}
+        let t = match extracted_sprite.quad_type {
+            UIQuadType::Quad => 0,
+            UIQuadType::Text => 1,
+            UIQuadType::Image => 2,
+            UIQuadType::BoxShadow => 3,
+            UIQuadType::Gradient => 4,
+            UIQuadType::Clip => unreachable!(),
+        };
+
+        // Quads with extra parameters (radii, borders, shadows, gradients) need their own entry
+        extracted_sprite.type_index = if extracted_sprite.params == QuadParams::default() {
+            match extracted_sprite.quad_type {
+                UIQuadType::Text => text_type_offset,
+                UIQuadType::Image => image_type_offset,
+                _ => quad_type_offset,
+            }
+        } else {
+            sprite_meta.types_buffer.push(QuadType::with_params(
+                t,
+                sprite_rect,
+                &extracted_sprite.params,
+            ))
         };
 
         let uv_min = extracted_sprite.uv_min.unwrap_or(Vec2::ZERO);
@@ -470,20 +625,30 @@ pub fn prepare_quads(
                 Quat::default(),
                 sprite_rect.min.extend(0.0),
             );
-            let final_position = (world * Vec3::from(*vertex_position).extend(1.0)).truncate();
+            let position = (world * Vec3::from(*vertex_position).extend(1.0)).truncate();
+            let final_position = extracted_sprite
+                .transform
+                .transform_point2(position.truncate())
+                .extend(position.z);
+            // The shader works in the untransformed space of the quad, so offset the quad's
+            // origin by however far the transform moved this vertex
+            let origin = sprite_rect.min + (final_position - position).truncate();
             sprite_meta.vertices.push(QuadVertex {
                 position: final_position.into(),
                 color,
                 uv: uvs[index],
                 pos_size: [
-                    sprite_rect.min.x,
-                    sprite_rect.min.y,
+                    origin.x,
+                    origin.y,
                     sprite_rect.size().x,
                     sprite_rect.size().y,
                 ],
             });
         }
     }
+    sprite_meta
+        .types_buffer
+        .write_buffer(&render_device, &render_queue);
     sprite_meta
         .vertices
         .write_buffer(&render_device, &render_queue);
@@ -675,3 +840,139 @@ impl Draw<TransparentUI> for DrawUI {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::{Vec2, Vec4},
+        prelude::{Color, Rect},
+    };
+
+    use super::{QuadParams, QuadType};
+    use crate::styles::{BorderStyle, ColorStop, Corner, Edge, Gradient};
+
+    fn gradient_type(gradient: Gradient) -> QuadType {
+        let rect = Rect {
+            min: Vec2::ZERO,
+            max: Vec2::new(100.0, 50.0),
+        };
+        QuadType::with_params(
+            5,
+            rect,
+            &QuadParams {
+                bounds: rect,
+                gradient: Some(gradient),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn stops(count: usize) -> Vec<ColorStop> {
+        (0..count)
+            .map(|index| ColorStop::new(Color::WHITE, index as f32 / count as f32))
+            .collect()
+    }
+
+    #[test]
+    fn borders_should_be_packed_clockwise_from_the_top() {
+        let rect = Rect {
+            min: Vec2::ZERO,
+            max: Vec2::new(100.0, 50.0),
+        };
+        let quad_type = QuadType::with_params(
+            0,
+            rect,
+            &QuadParams {
+                bounds: rect,
+                radius: Corner::new(1.0, 2.0, 3.0, 4.0),
+                border: Edge::new(5.0, 6.0, 7.0, 8.0),
+                border_color: Edge::new(Color::RED, Color::GREEN, Color::BLUE, Color::WHITE),
+                ..Default::default()
+            },
+        );
+        // Corners start from the top left, so the bottom right comes before the bottom left
+        assert_eq!(quad_type.radius, Vec4::new(1.0, 2.0, 4.0, 3.0));
+        assert_eq!(quad_type.border, Vec4::new(5.0, 6.0, 7.0, 8.0));
+        assert_eq!(
+            quad_type.border_colors[1],
+            Vec4::from(Color::GREEN.as_linear_rgba_f32())
+        );
+    }
+
+    #[test]
+    fn border_styles_should_match_the_shader() {
+        for (border_style, expected) in [
+            (BorderStyle::Solid, 0),
+            (BorderStyle::Dashed, 1),
+            (BorderStyle::Dotted, 2),
+        ] {
+            let quad_type = QuadType::with_params(
+                0,
+                Rect::default(),
+                &QuadParams {
+                    border_style,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(quad_type.border_style, expected);
+        }
+    }
+
+    #[test]
+    fn linear_gradients_should_point_along_their_angle() {
+        // 0 degrees points up, turning clockwise
+        for (angle, direction) in [
+            (0.0, Vec2::new(0.0, -1.0)),
+            (90.0, Vec2::new(1.0, 0.0)),
+            (180.0, Vec2::new(0.0, 1.0)),
+        ] {
+            let quad_type = gradient_type(Gradient::linear(angle, stops(2)));
+            assert_eq!(quad_type.gradient_kind, 0);
+            assert!(
+                quad_type
+                    .gradient
+                    .abs_diff_eq(direction.extend(0.0).extend(0.0), 1e-6),
+                "{} degrees should point towards {}",
+                angle,
+                direction
+            );
+        }
+    }
+
+    #[test]
+    fn radial_gradients_should_keep_their_center() {
+        let quad_type = gradient_type(Gradient::radial(Vec2::new(0.25, 0.75), stops(2)));
+        assert_eq!(quad_type.gradient_kind, 1);
+        assert_eq!(quad_type.gradient, Vec4::new(0.25, 0.75, 0.0, 0.0));
+    }
+
+    #[test]
+    fn gradient_stops_should_be_packed_in_order() {
+        let quad_type = gradient_type(Gradient::linear(
+            0.0,
+            vec![
+                ColorStop::new(Color::RED, 0.0),
+                ColorStop::new(Color::BLUE, 0.4),
+                ColorStop::new(Color::GREEN, 1.0),
+            ],
+        ));
+        assert_eq!(quad_type.stop_count, 3);
+        assert_eq!(
+            quad_type.stop_colors[1],
+            Vec4::from(Color::BLUE.as_linear_rgba_f32())
+        );
+        assert_eq!(quad_type.stop_positions[0], Vec4::new(0.0, 0.4, 1.0, 0.0));
+    }
+
+    #[test]
+    fn gradient_stops_past_the_maximum_should_be_ignored() {
+        let quad_type = gradient_type(Gradient::linear(0.0, stops(Gradient::MAX_STOPS + 2)));
+        assert_eq!(quad_type.stop_count, Gradient::MAX_STOPS as i32);
+        // The last stop rendered is packed into the last component of the last vector
+        let last = Gradient::MAX_STOPS - 1;
+        assert_eq!(
+            quad_type.stop_positions[last / 4][last % 4],
+            last as f32 / (Gradient::MAX_STOPS + 2) as f32
+        );
+    }
+}
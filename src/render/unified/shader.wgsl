@@ -7,9 +7,35 @@ var<uniform> view: View;
 
 struct QuadType {
     t: i32,
+    inset: i32,
+    stop_count: i32,
+    // 0 for linear gradients, 1 for radial gradients
+    gradient_kind: i32,
+    // 0 for solid borders, 1 for dashed borders, 2 for dotted borders
+    border_style: i32,
     _padding_1: i32,
     _padding_2: i32,
     _padding_3: i32,
+    // The bounds of the widget (x, y, width, height), relative to the quad
+    bounds: vec4<f32>,
+    // The corner radii of the widget (top left, top right, bottom right, bottom left)
+    radius: vec4<f32>,
+    // The border widths (top, right, bottom, left)
+    border: vec4<f32>,
+    // The (linear) border colors (top, right, bottom, left)
+    border_colors: array<vec4<f32>, 4>,
+    // The shadow offset (x, y), blur radius, and spread
+    shadow: vec4<f32>,
+    // The gradient direction (x, y) if linear, or its relative center (x, y) if radial
+    gradient: vec4<f32>,
+    // The (linear) gradient stop colors
+    stop_colors: array<vec4<f32>, 8>,
+    // The gradient stop positions, packed four per vector
+    stop_positions: array<vec4<f32>, 2>,
+    // The bounds of the rounded clip mask (x, y, width, height), relative to the quad
+    clip_bounds: vec4<f32>,
+    // The corner radii of the rounded clip mask (top left, top right, bottom right, bottom left)
+    clip_radius: vec4<f32>,
 };
 
 @group(2) @binding(0)
@@ -61,20 +87,105 @@ fn sdRoundBox(p: vec2<f32>, b: vec2<f32>, r: f32) -> f32 {
     return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - r;
 }
 
-@fragment
-fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+// Where P is the position relative to the center of the box, B is half the size of the box and R holds
+// the radius of each corner (top left, top right, bottom right, bottom left).
+fn sdRoundBoxCorners(p: vec2<f32>, b: vec2<f32>, r: vec4<f32>) -> f32 {
+    var top = select(r.x, r.y, p.x > 0.0);
+    var bottom = select(r.w, r.z, p.x > 0.0);
+    // Lock the radius to the size of the box
+    var radius = min(select(top, bottom, p.y > 0.0), min(b.x, b.y));
+    var q = abs(p) - b + radius;
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - radius;
+}
+
+fn gradientStopPosition(index: i32) -> f32 {
+    return quad_type.stop_positions[index / 4][index % 4];
+}
+
+// Where T is the position along the gradient, from 0 to 1.
+fn gradientColor(t: f32) -> vec4<f32> {
+    var color = quad_type.stop_colors[0];
+    for (var i: i32 = 1; i < quad_type.stop_count; i = i + 1) {
+        var start = gradientStopPosition(i - 1);
+        var end = gradientStopPosition(i);
+        var f = clamp((t - start) / max(end - start, 0.0001), 0.0, 1.0);
+        color = mix(color, quad_type.stop_colors[i], f);
+    }
+    return color;
+}
+
+fn quadColor(in: VertexOutput) -> vec4<f32> {
     if (quad_type.t == 0) {
-        var size = in.size;
-        var pos = in.pos.xy * 2.0;
-        // Lock border to max size. This is similar to how HTML/CSS handles border radius.
-        var bs = min(in.border_radius * 2.0, min(size.x, size.y));
-        var rect_dist = sdRoundBox(
-            pos - size,
-            size,
-            bs,
+        var half_size = in.size * 0.5;
+        var outer_dist = sdRoundBoxCorners(in.pos - half_size, half_size, quad_type.radius);
+        var outer_mask = 1.0 - smoothstep(0.0, fwidth(outer_dist), outer_dist);
+
+        // The inner edge of the border, whose corners shrink by the width of the adjacent sides
+        var border = quad_type.border;
+        var inner_min = vec2<f32>(border.w, border.x);
+        var inner_max = in.size - vec2<f32>(border.y, border.z);
+        var inner_half_size = max((inner_max - inner_min) * 0.5, vec2<f32>(0.0));
+        var inner_radius = max(
+            quad_type.radius - vec4<f32>(
+                max(border.x, border.w),
+                max(border.x, border.y),
+                max(border.z, border.y),
+                max(border.z, border.w),
+            ),
+            vec4<f32>(0.0),
+        );
+        var inner_dist = sdRoundBoxCorners(in.pos - (inner_min + inner_max) * 0.5, inner_half_size, inner_radius);
+        var inner_mask = 1.0 - smoothstep(0.0, fwidth(inner_dist), inner_dist);
+
+        // Find the side this pixel belongs to, splitting the corners diagonally
+        var side_dist = vec4<f32>(
+            in.pos.y / max(border.x, 0.0001),
+            (in.size.x - in.pos.x) / max(border.y, 0.0001),
+            (in.size.y - in.pos.y) / max(border.z, 0.0001),
+            in.pos.x / max(border.w, 0.0001),
         );
-        rect_dist = 1.0 - smoothstep(0.0, fwidth(rect_dist), rect_dist);
-        return vec4<f32>(in.color.rgb, rect_dist * in.color.a);
+        var side = 0;
+        var closest = side_dist.x;
+        if (side_dist.y < closest) {
+            side = 1;
+            closest = side_dist.y;
+        }
+        if (side_dist.z < closest) {
+            side = 2;
+            closest = side_dist.z;
+        }
+        if (side_dist.w < closest) {
+            side = 3;
+            closest = side_dist.w;
+        }
+        var width = border[side];
+        var border_color = quad_type.border_colors[side];
+
+        // The position along the side and the distance from its outer edge
+        var along = select(in.pos.y, in.pos.x, side == 0 || side == 2);
+        var across = closest * width;
+
+        var pattern = 1.0;
+        if (quad_type.border_style == 1 && width > 0.0) {
+            // Dashes three times as long as the border is wide, with gaps twice as long
+            var period = width * 5.0;
+            var d = along - floor(along / period) * period;
+            pattern = 1.0 - smoothstep(width * 3.0 - 0.5, width * 3.0 + 0.5, d);
+        }
+        if (quad_type.border_style == 2 && width > 0.0) {
+            // Round dots as wide as the border, with gaps just as wide
+            var period = width * 2.0;
+            var d = along - floor(along / period) * period - width * 0.5;
+            var dot_dist = length(vec2<f32>(d, across - width * 0.5)) - width * 0.5;
+            pattern = 1.0 - smoothstep(-0.5, 0.5, dot_dist);
+        }
+
+        // Draw the border over the background
+        var border_alpha = border_color.a * max(outer_mask - inner_mask, 0.0) * pattern;
+        var background_alpha = in.color.a * inner_mask * (1.0 - border_alpha);
+        var alpha = border_alpha + background_alpha;
+        var color = (border_color.rgb * border_alpha + in.color.rgb * background_alpha) / max(alpha, 0.0001);
+        return vec4<f32>(color, alpha);
     }
     if (quad_type.t == 1) {
         var px_range = 3.5;
@@ -84,18 +195,70 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         var v = max(min(x.r, x.g), min(max(x.r, x.g), x.b));
         var sig_dist = (v - 0.5) * dot(msdf_unit, 0.5 / fwidth(in.uv.xy));
         var a = clamp(sig_dist + 0.5, 0.0, 1.0);
-        return vec4<f32>(in.color.rgb, a);
+        return vec4<f32>(in.color.rgb, a * in.color.a);
     }
     if (quad_type.t == 2) {
-        var bs = min(in.border_radius, min(in.size.x, in.size.y));
-        var mask = sdRoundBox(
-            in.pos.xy * 2.0 - (in.size.xy),
-            in.size.xy,
-            bs,
-        );
+        var half_size = in.size * 0.5;
+        var mask = sdRoundBoxCorners(in.pos - half_size, half_size, quad_type.radius);
         mask = 1.0 - smoothstep(0.0, fwidth(mask), mask);
         var color = textureSample(image_texture, image_sampler, vec2<f32>(in.uv.x, 1.0 - in.uv.y));
         return vec4<f32>(color.rgb * in.color.rgb, color.a * in.color.a * mask);
     }
+    if (quad_type.t == 3) {
+        var half_size = quad_type.bounds.zw * 0.5;
+        var center = quad_type.bounds.xy + half_size;
+        var widget_dist = sdRoundBoxCorners(in.pos - center, half_size, quad_type.radius);
+        var widget_mask = 1.0 - smoothstep(0.0, fwidth(widget_dist), widget_dist);
+
+        // Inset shadows shrink by their spread, outer shadows grow
+        var spread = select(quad_type.shadow.w, -quad_type.shadow.w, quad_type.inset == 1);
+        var shadow_half_size = max(half_size + vec2<f32>(spread), vec2<f32>(0.0));
+        var shadow_radius = max(quad_type.radius + vec4<f32>(spread), vec4<f32>(0.0));
+        var shadow_dist = sdRoundBoxCorners(
+            in.pos - center - quad_type.shadow.xy,
+            shadow_half_size,
+            shadow_radius,
+        );
+        var blur = max(quad_type.shadow.z, fwidth(shadow_dist));
+        var shadow = 1.0 - smoothstep(-blur, blur, shadow_dist);
+
+        // Shadows are only visible outside (or, if inset, inside) the widget
+        var mask = select((1.0 - widget_mask) * shadow, widget_mask * (1.0 - shadow), quad_type.inset == 1);
+        return vec4<f32>(in.color.rgb, in.color.a * mask);
+    }
+    if (quad_type.t == 4) {
+        var half_size = in.size * 0.5;
+        var p = in.pos - half_size;
+        var dist = sdRoundBoxCorners(p, half_size, quad_type.radius);
+        var mask = 1.0 - smoothstep(0.0, fwidth(dist), dist);
+
+        var t = 0.0;
+        if (quad_type.gradient_kind == 0) {
+            // The gradient line is just long enough for its ends to touch the corners
+            var direction = quad_type.gradient.xy;
+            var line_length = abs(in.size.x * direction.x) + abs(in.size.y * direction.y);
+            t = dot(p, direction) / max(line_length, 0.0001) + 0.5;
+        } else {
+            // The gradient ends at the farthest corner
+            var center = quad_type.gradient.xy * in.size;
+            var corner = max(center, in.size - center);
+            t = distance(in.pos, center) / max(length(corner), 0.0001);
+        }
+
+        var color = gradientColor(t);
+        return vec4<f32>(color.rgb * in.color.rgb, color.a * in.color.a * mask);
+    }
     return in.color;
 }
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = quadColor(in);
+    if (any(quad_type.clip_radius > vec4<f32>(0.0))) {
+        // Mask the quad to the rounded rect it is clipped to
+        var half_clip = quad_type.clip_bounds.zw * 0.5;
+        var clip_dist = sdRoundBoxCorners(in.pos - quad_type.clip_bounds.xy - half_clip, half_clip, quad_type.clip_radius);
+        color.a = color.a * (1.0 - smoothstep(-0.5, 0.5, clip_dist));
+    }
+    return color;
+}
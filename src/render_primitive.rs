@@ -1,22 +1,34 @@
 use crate::{
     layout::Rect,
-    styles::{Corner, Edge, KStyle, RenderCommand},
+    styles::{
+        Background, BorderStyle, BoxShadow, Corner, Edge, Gradient, KStyle, RenderCommand,
+        StyleProp,
+    },
+};
+use bevy::{
+    math::Affine2,
+    prelude::{Color, Handle, Image, Vec2},
 };
-use bevy::prelude::{Color, Handle, Image, Vec2};
-use kayak_font::{TextLayout, TextProperties};
+use kayak_font::{GlyphRect, TextLayout, TextProperties};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum RenderPrimitive {
     Empty,
     Clip {
         layout: Rect,
+        /// The rounded rect that content is masked to, if `border_radius` is not zero
+        mask_layout: Rect,
+        border_radius: Corner<f32>,
     },
     Quad {
         layout: Rect,
         background_color: Color,
-        border_color: Color,
+        background_gradient: Option<Gradient>,
+        border_color: Edge<Color>,
         border: Edge<f32>,
         border_radius: Corner<f32>,
+        border_style: BorderStyle,
+        box_shadow: Vec<BoxShadow>,
     },
     Text {
         color: Color,
@@ -30,24 +42,114 @@ pub enum RenderPrimitive {
         border_radius: Corner<f32>,
         layout: Rect,
         handle: Handle<Image>,
+        opacity: f32,
+        box_shadow: Vec<BoxShadow>,
     },
     TextureAtlas {
         size: Vec2,
         position: Vec2,
         layout: Rect,
         handle: Handle<Image>,
+        opacity: f32,
     },
     NinePatch {
         border: Edge<f32>,
         layout: Rect,
         handle: Handle<Image>,
+        opacity: f32,
     },
+    /// Sets the transform of every primitive that follows, up to the next `Transform`
+    Transform {
+        transform: Affine2,
+    },
+}
+
+/// Visual adjustments applied to every primitive in a subtree
+///
+/// Positions are mapped as `position * scale + offset`, after layout and before extraction.
+/// These only affect rendering, not layout or hit-testing.
+///
+/// The `transform` is applied to the vertices of each quad at extraction, after everything else.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct RenderEffects {
+    pub scale: f32,
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub transform: Affine2,
+}
+
+impl Default for RenderEffects {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: Vec2::ZERO,
+            opacity: 1.0,
+            transform: Affine2::IDENTITY,
+        }
+    }
+}
+
+impl RenderEffects {
+    /// Effects that translate by the given offset
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            offset: Vec2::new(x, y),
+            ..Default::default()
+        }
+    }
+
+    /// Effects that scale by the given factor around the given origin
+    pub fn scale_around(origin: Vec2, scale: f32) -> Self {
+        Self {
+            scale,
+            offset: origin * (1.0 - scale),
+            ..Default::default()
+        }
+    }
+
+    /// Effects that multiply the alpha of all colors by the given opacity
+    pub fn opacity(opacity: f32) -> Self {
+        Self {
+            opacity,
+            ..Default::default()
+        }
+    }
+
+    /// Combines these (outer) effects with the given (inner) effects
+    ///
+    /// The inner effects are applied first.
+    pub fn then(&self, inner: &RenderEffects) -> Self {
+        Self {
+            scale: self.scale * inner.scale,
+            offset: inner.offset * self.scale + self.offset,
+            opacity: self.opacity * inner.opacity,
+            transform: self.transform * inner.transform,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply_to_rect(&self, layout: &mut Rect) {
+        layout.posx = layout.posx * self.scale + self.offset.x;
+        layout.posy = layout.posy * self.scale + self.offset.y;
+        layout.width *= self.scale;
+        layout.height *= self.scale;
+    }
 }
 
 impl RenderPrimitive {
     pub fn set_layout(&mut self, new_layout: Rect) {
         match self {
-            RenderPrimitive::Clip { layout, .. } => *layout = new_layout,
+            RenderPrimitive::Clip {
+                layout,
+                mask_layout,
+                ..
+            } => {
+                *layout = new_layout;
+                *mask_layout = new_layout;
+            }
             RenderPrimitive::Quad { layout, .. } => *layout = new_layout,
             RenderPrimitive::Text { layout, .. } => *layout = new_layout,
             RenderPrimitive::Image { layout, .. } => *layout = new_layout,
@@ -56,6 +158,124 @@ impl RenderPrimitive {
             _ => (),
         }
     }
+
+    /// Applies the given effects to this primitive
+    pub(crate) fn apply_effects(&mut self, effects: &RenderEffects) {
+        if effects.is_identity() {
+            return;
+        }
+
+        fn fade(color: &mut Color, opacity: f32) {
+            let alpha = color.a() * opacity;
+            color.set_a(alpha);
+        }
+
+        fn apply_to_shadows(box_shadow: &mut [BoxShadow], effects: &RenderEffects) {
+            for shadow in box_shadow.iter_mut() {
+                shadow.offset *= effects.scale;
+                shadow.blur_radius *= effects.scale;
+                shadow.spread *= effects.scale;
+                fade(&mut shadow.color, effects.opacity);
+            }
+        }
+
+        match self {
+            RenderPrimitive::Empty | RenderPrimitive::Transform { .. } => {}
+            RenderPrimitive::Clip {
+                layout,
+                mask_layout,
+                border_radius,
+            } => {
+                effects.apply_to_rect(layout);
+                effects.apply_to_rect(mask_layout);
+                *border_radius *= effects.scale;
+            }
+            RenderPrimitive::Quad {
+                layout,
+                background_color,
+                background_gradient,
+                border_color,
+                border,
+                border_radius,
+                box_shadow,
+                ..
+            } => {
+                effects.apply_to_rect(layout);
+                *border *= effects.scale;
+                *border_radius *= effects.scale;
+                fade(background_color, effects.opacity);
+                fade(&mut border_color.top, effects.opacity);
+                fade(&mut border_color.right, effects.opacity);
+                fade(&mut border_color.bottom, effects.opacity);
+                fade(&mut border_color.left, effects.opacity);
+                if let Some(gradient) = background_gradient {
+                    for stop in gradient.stops.iter_mut() {
+                        fade(&mut stop.color, effects.opacity);
+                    }
+                }
+                apply_to_shadows(box_shadow, effects);
+            }
+            RenderPrimitive::Text {
+                color,
+                text_layout,
+                layout,
+                properties,
+                ..
+            } => {
+                effects.apply_to_rect(layout);
+                fade(color, effects.opacity);
+                if effects.scale != 1.0 {
+                    let scale = effects.scale;
+                    properties.font_size *= scale;
+                    properties.line_height *= scale;
+                    let glyphs = text_layout
+                        .glyphs()
+                        .iter()
+                        .map(|glyph| GlyphRect {
+                            position: (glyph.position.0 * scale, glyph.position.1 * scale),
+                            size: (glyph.size.0 * scale, glyph.size.1 * scale),
+                            content: glyph.content,
+                        })
+                        .collect();
+                    let size = text_layout.size();
+                    *text_layout = TextLayout::new(
+                        glyphs,
+                        text_layout.lines().to_vec(),
+                        (size.0 * scale, size.1 * scale),
+                        *properties,
+                    );
+                }
+            }
+            RenderPrimitive::Image {
+                border_radius,
+                layout,
+                opacity,
+                box_shadow,
+                ..
+            } => {
+                effects.apply_to_rect(layout);
+                *border_radius *= effects.scale;
+                *opacity *= effects.opacity;
+                apply_to_shadows(box_shadow, effects);
+            }
+            RenderPrimitive::TextureAtlas {
+                layout, opacity, ..
+            } => {
+                effects.apply_to_rect(layout);
+                *opacity *= effects.opacity;
+            }
+            RenderPrimitive::NinePatch {
+                border,
+                layout,
+                opacity,
+                ..
+            } => {
+                effects.apply_to_rect(layout);
+                *border *= effects.scale;
+                *opacity *= effects.opacity;
+            }
+        }
+    }
 }
 
 impl From<&KStyle> for RenderPrimitive {
@@ -66,10 +286,26 @@ impl From<&KStyle> for RenderPrimitive {
             .background_color
             .resolve_or(Color::rgba(1.0, 1.0, 1.0, 0.0));
 
+        let (background_color, background_gradient) = match &style.background {
+            StyleProp::Value(Background::Color(color)) => (*color, None),
+            StyleProp::Value(Background::Gradient(gradient)) => {
+                (Color::rgba(1.0, 1.0, 1.0, 0.0), Some(gradient.clone()))
+            }
+            _ => (background_color, None),
+        };
+
         let border_color = style
             .border_color
             .resolve_or(Color::rgba(1.0, 1.0, 1.0, 0.0));
 
+        // The specific sides take precedence over `border_color`
+        let border_color = Edge::new(
+            style.border_color_top.resolve_or(border_color),
+            style.border_color_right.resolve_or(border_color),
+            style.border_color_bottom.resolve_or(border_color),
+            style.border_color_left.resolve_or(border_color),
+        );
+
         let font = style
             .font
             .resolve_or_else(|| String::from(crate::DEFAULT_FONT));
@@ -83,12 +319,17 @@ impl From<&KStyle> for RenderPrimitive {
             RenderCommand::Layout => Self::Empty,
             RenderCommand::Clip => Self::Clip {
                 layout: Rect::default(),
+                mask_layout: Rect::default(),
+                border_radius: style.border_radius.resolve(),
             },
             RenderCommand::Quad => Self::Quad {
                 background_color,
+                background_gradient,
                 border_color,
                 border_radius: style.border_radius.resolve(),
+                border_style: style.border_style.resolve(),
                 border: style.border.resolve(),
+                box_shadow: style.box_shadow.resolve(),
                 layout: Rect::default(),
             },
             RenderCommand::Text { content, alignment } => Self::Text {
@@ -108,6 +349,8 @@ impl From<&KStyle> for RenderPrimitive {
                 border_radius: style.border_radius.resolve(),
                 layout: Rect::default(),
                 handle,
+                opacity: 1.0,
+                box_shadow: style.box_shadow.resolve(),
             },
             RenderCommand::TextureAtlas {
                 handle,
@@ -118,12 +361,154 @@ impl From<&KStyle> for RenderPrimitive {
                 layout: Rect::default(),
                 size,
                 position,
+                opacity: 1.0,
             },
             RenderCommand::NinePatch { handle, border } => Self::NinePatch {
                 border,
                 layout: Rect::default(),
                 handle,
+                opacity: 1.0,
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Color, Handle, Vec2};
+
+    use super::{RenderEffects, RenderPrimitive};
+    use crate::{
+        layout::Rect,
+        styles::{BorderStyle, BoxShadow, ColorStop, Corner, Edge, Gradient},
+    };
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    fn apply(effects: &RenderEffects, mut layout: Rect) -> Rect {
+        effects.apply_to_rect(&mut layout);
+        layout
+    }
+
+    fn quad() -> RenderPrimitive {
+        RenderPrimitive::Quad {
+            layout: rect(0.0, 0.0, 10.0, 10.0),
+            background_color: Color::rgba(1.0, 0.0, 0.0, 0.8),
+            background_gradient: Some(Gradient::linear(
+                90.0,
+                vec![
+                    ColorStop::new(Color::WHITE, 0.0),
+                    ColorStop::new(Color::rgba(0.0, 0.0, 0.0, 0.5), 1.0),
+                ],
+            )),
+            border_color: Edge::all(Color::BLACK),
+            border: Edge::all(1.0),
+            border_radius: Corner::all(2.0),
+            border_style: BorderStyle::Solid,
+            box_shadow: vec![BoxShadow::new(
+                2.0,
+                2.0,
+                4.0,
+                0.0,
+                Color::rgba(0.0, 0.0, 0.0, 0.5),
+            )],
+        }
+    }
+
+    #[test]
+    fn then_should_apply_inner_effects_first() {
+        let translation = RenderEffects::translation(10.0, 20.0);
+        let scale = RenderEffects::scale_around(Vec2::ZERO, 2.0);
+        let layout = rect(1.0, 1.0, 5.0, 5.0);
+
+        let scaled_then_translated = translation.then(&scale);
+        assert_eq!(
+            apply(&scaled_then_translated, layout),
+            apply(&translation, apply(&scale, layout))
+        );
+        assert_eq!(
+            apply(&scaled_then_translated, layout),
+            rect(12.0, 22.0, 10.0, 10.0)
+        );
+
+        let translated_then_scaled = scale.then(&translation);
+        assert_eq!(
+            apply(&translated_then_scaled, layout),
+            rect(22.0, 42.0, 10.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn then_should_multiply_opacity() {
+        let half = RenderEffects::opacity(0.5);
+        assert_eq!(half.then(&half).opacity, 0.25);
+        assert_eq!(
+            half.then(&RenderEffects::translation(1.0, 1.0)).opacity,
+            0.5
+        );
+        // Default effects change nothing on either side
+        assert_eq!(RenderEffects::default().then(&half), half);
+        assert_eq!(half.then(&RenderEffects::default()), half);
+    }
+
+    #[test]
+    fn apply_effects_should_multiply_the_alpha_of_every_color() {
+        let mut primitive = quad();
+        primitive.apply_effects(&RenderEffects::opacity(0.5));
+
+        let (background_color, background_gradient, border_color, box_shadow) = match primitive {
+            RenderPrimitive::Quad {
+                background_color,
+                background_gradient,
+                border_color,
+                box_shadow,
+                ..
+            } => (
+                background_color,
+                background_gradient,
+                border_color,
+                box_shadow,
+            ),
+            _ => unreachable!(),
+        };
+        assert_eq!(background_color.a(), 0.4);
+        let stops = background_gradient.unwrap().stops;
+        assert_eq!((stops[0].color.a(), stops[1].color.a()), (0.5, 0.25));
+        assert_eq!(border_color, Edge::all(Color::rgba(0.0, 0.0, 0.0, 0.5)));
+        assert_eq!(box_shadow[0].color.a(), 0.25);
+    }
+
+    #[test]
+    fn apply_effects_should_multiply_nested_opacity() {
+        let mut primitive = RenderPrimitive::Image {
+            border_radius: Corner::all(0.0),
+            layout: rect(0.0, 0.0, 10.0, 10.0),
+            handle: Handle::default(),
+            opacity: 1.0,
+            box_shadow: Vec::new(),
+        };
+        let parent = RenderEffects::opacity(0.5);
+        let child = parent.then(&RenderEffects::opacity(0.5));
+        primitive.apply_effects(&child);
+
+        match primitive {
+            RenderPrimitive::Image { opacity, .. } => assert_eq!(opacity, 0.25),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn default_effects_should_leave_primitives_unchanged() {
+        let mut primitive = quad();
+        primitive.apply_effects(&RenderEffects::default());
+        assert_eq!(primitive, quad());
+    }
+}
@@ -0,0 +1,945 @@
+use std::{fmt, path::Path};
+
+use bevy::{
+    app::AppTypeRegistry,
+    ecs::reflect::ReflectComponent,
+    prelude::{Entity, World},
+    reflect::{
+        serde::{ReflectSerializer, UntypedReflectDeserializer},
+        Reflect, ReflectMut, TypeRegistry,
+    },
+    utils::HashMap,
+};
+use serde::{
+    de::{DeserializeSeed, Error as _, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeSeq, SerializeStruct},
+    Deserializer, Serialize, Serializer,
+};
+
+use crate::context::{Context, Mounted};
+
+/// A copy of the widget tree, along with the state of each widget
+///
+/// Each widget is stored with its reflected components, such as its props and styles, and with
+/// the context entities it provides. Only components whose types are registered with
+/// `#[reflect(Component)]` are included, so things like [`OnEvent`](crate::prelude::OnEvent)
+/// handlers must be added back once the snapshot is restored.
+///
+/// Snapshots can be saved as RON, which makes them usable for restoring a saved game's UI or as
+/// golden files for tests:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use kayak_ui::prelude::UiSnapshot;
+/// fn save_ui(world: &mut World) {
+///     let registry = world.resource::<AppTypeRegistry>().clone();
+///     let snapshot = UiSnapshot::capture(world).unwrap();
+///     snapshot.save("saves/ui.ron", &registry).unwrap();
+/// }
+///
+/// // Run in place of the startup system that builds the UI
+/// fn load_ui(world: &mut World) {
+///     let registry = world.resource::<AppTypeRegistry>().clone();
+///     let snapshot = UiSnapshot::load("saves/ui.ron", &registry).unwrap();
+///     snapshot.restore(world).unwrap();
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct UiSnapshot {
+    /// The widgets in the tree, with each parent listed before its children
+    pub widgets: Vec<WidgetSnapshot>,
+}
+
+/// A widget within a [`UiSnapshot`]
+#[derive(Debug)]
+pub struct WidgetSnapshot {
+    /// The index of the widget's entity when the snapshot was taken
+    pub entity: u32,
+    /// The index of the widget's parent, or `None` for the root widget
+    pub parent: Option<u32>,
+    /// The reflected components of the widget, sorted by type name
+    pub components: Vec<Box<dyn Reflect>>,
+    /// The context entities provided by the widget
+    pub context_entities: Vec<ContextEntitySnapshot>,
+}
+
+/// A context entity within a [`UiSnapshot`]
+#[derive(Debug)]
+pub struct ContextEntitySnapshot {
+    /// The type name of the context, as given to
+    /// [`set_context_entity`](crate::prelude::WidgetContext::set_context_entity)
+    pub context: String,
+    /// The index of the context entity when the snapshot was taken
+    pub entity: u32,
+    /// The reflected components of the context entity, sorted by type name
+    pub components: Vec<Box<dyn Reflect>>,
+}
+
+impl UiSnapshot {
+    /// Captures the current widget tree of the [`Context`]
+    pub fn capture(world: &World) -> Result<Self, SnapshotError> {
+        let context = world
+            .get_resource::<Context>()
+            .ok_or(SnapshotError::MissingContext)?;
+        let registry = world.resource::<AppTypeRegistry>().read();
+
+        let tree = context.tree.read().unwrap();
+        let mut widgets = Vec::new();
+        for index in tree.flatten() {
+            let entity = index.0;
+
+            let mut context_entities = Vec::new();
+            for (type_id, context_entity) in context.context_entities.get_context_entities(entity) {
+                let registration = registry
+                    .get(type_id)
+                    .ok_or(SnapshotError::UnregisteredContext(entity))?;
+                context_entities.push(ContextEntitySnapshot {
+                    context: registration.type_name().to_string(),
+                    entity: context_entity.index(),
+                    components: capture_components(world, &registry, context_entity),
+                });
+            }
+            context_entities.sort_by(|a, b| a.context.cmp(&b.context));
+
+            widgets.push(WidgetSnapshot {
+                entity: entity.index(),
+                parent: tree.get_parent(index).map(|parent| parent.0.index()),
+                components: capture_components(world, &registry, entity),
+                context_entities,
+            });
+        }
+
+        Ok(Self { widgets })
+    }
+
+    /// Spawns the widgets in the snapshot and adds them to the [`Context`]'s widget tree
+    ///
+    /// The tree must be empty, so this should be used in place of the system that would
+    /// otherwise build the UI. References to other entities within the snapshot, such as a
+    /// widget's [`KChildren`](crate::prelude::KChildren), are updated to point to the new
+    /// entities.
+    ///
+    /// Returns the entity spawned for each entity index in the snapshot.
+    pub fn restore(&self, world: &mut World) -> Result<HashMap<u32, Entity>, SnapshotError> {
+        match world.get_resource::<Context>() {
+            Some(context) => {
+                if context.tree.read().unwrap().root_node.is_some() {
+                    return Err(SnapshotError::TreeNotEmpty);
+                }
+            }
+            None => return Err(SnapshotError::MissingContext),
+        }
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        // Spawn everything first so that references between entities can be mapped
+        let mut entity_map = HashMap::default();
+        for widget in self.widgets.iter() {
+            entity_map.insert(widget.entity, world.spawn(Mounted).id());
+            for context_entity in widget.context_entities.iter() {
+                entity_map.insert(context_entity.entity, world.spawn_empty().id());
+            }
+        }
+
+        for widget in self.widgets.iter() {
+            restore_components(
+                world,
+                &registry,
+                &entity_map,
+                widget.entity,
+                &widget.components,
+            )?;
+            for context_entity in widget.context_entities.iter() {
+                restore_components(
+                    world,
+                    &registry,
+                    &entity_map,
+                    context_entity.entity,
+                    &context_entity.components,
+                )?;
+            }
+        }
+
+        let mut context = world.resource_mut::<Context>();
+        for widget in self.widgets.iter() {
+            let entity = entity_map[&widget.entity];
+            let parent = match widget.parent {
+                Some(parent) => Some(
+                    *entity_map
+                        .get(&parent)
+                        .ok_or(SnapshotError::MissingParent(widget.entity))?,
+                ),
+                None => None,
+            };
+            context.add_widget(parent, entity);
+
+            for context_entity in widget.context_entities.iter() {
+                let context_type = &context_entity.context;
+                let registration = registry
+                    .get_with_name(context_type)
+                    .ok_or_else(|| SnapshotError::UnregisteredType(context_type.clone()))?;
+                context.context_entities.add_context_entity_by_id(
+                    entity,
+                    registration.type_id(),
+                    entity_map[&context_entity.entity],
+                );
+            }
+        }
+
+        Ok(entity_map)
+    }
+
+    /// Serializes the snapshot as RON
+    pub fn to_ron(&self, registry: &AppTypeRegistry) -> Result<String, SnapshotError> {
+        let registry = registry.read();
+        let config = ron::ser::PrettyConfig::default().indentor(String::from("  "));
+        ron::ser::to_string_pretty(
+            &SnapshotSerializer {
+                snapshot: self,
+                registry: &registry,
+            },
+            config,
+        )
+        .map_err(|err| SnapshotError::Serialize(err.to_string()))
+    }
+
+    /// Deserializes a snapshot from RON
+    ///
+    /// Every component type in the snapshot must be registered with the type registry.
+    pub fn from_ron(source: &str, registry: &AppTypeRegistry) -> Result<Self, SnapshotError> {
+        let registry = registry.read();
+        let mut deserializer = ron::de::Deserializer::from_str(source)
+            .map_err(|err| SnapshotError::Deserialize(err.to_string()))?;
+        SnapshotDeserializer {
+            registry: &registry,
+        }
+        .deserialize(&mut deserializer)
+        .map_err(|err| SnapshotError::Deserialize(err.to_string()))
+    }
+
+    /// Saves the snapshot to a RON file
+    pub fn save(
+        &self,
+        path: impl AsRef<Path>,
+        registry: &AppTypeRegistry,
+    ) -> Result<(), SnapshotError> {
+        std::fs::write(path, self.to_ron(registry)?).map_err(SnapshotError::Io)
+    }
+
+    /// Loads a snapshot from a RON file
+    pub fn load(path: impl AsRef<Path>, registry: &AppTypeRegistry) -> Result<Self, SnapshotError> {
+        let source = std::fs::read_to_string(path).map_err(SnapshotError::Io)?;
+        Self::from_ron(&source, registry)
+    }
+}
+
+/// An error produced when capturing, restoring, or (de)serializing a [`UiSnapshot`]
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The world has no [`Context`]
+    MissingContext,
+    /// The widget tree already has a root widget, so the snapshot can't be restored into it
+    TreeNotEmpty,
+    /// A widget's parent isn't in the snapshot
+    MissingParent(u32),
+    /// A context entity's type isn't registered with the type registry, so the context can't be
+    /// named
+    UnregisteredContext(Entity),
+    /// A type isn't registered with the type registry
+    UnregisteredType(String),
+    /// A type is registered, but not with `#[reflect(Component)]`
+    UnregisteredComponent(String),
+    Serialize(String),
+    Deserialize(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::MissingContext => write!(f, "no kayak Context was found"),
+            SnapshotError::TreeNotEmpty => {
+                write!(
+                    f,
+                    "a snapshot can only be restored into an empty widget tree"
+                )
+            }
+            SnapshotError::MissingParent(entity) => {
+                write!(f, "the parent of widget {} isn't in the snapshot", entity)
+            }
+            SnapshotError::UnregisteredContext(entity) => write!(
+                f,
+                "widget {:?} provides a context entity whose type isn't registered",
+                entity
+            ),
+            SnapshotError::UnregisteredType(type_name) => {
+                write!(f, "`{}` isn't registered with the type registry", type_name)
+            }
+            SnapshotError::UnregisteredComponent(type_name) => {
+                write!(f, "`{}` isn't registered as a component", type_name)
+            }
+            SnapshotError::Serialize(message) => {
+                write!(f, "failed to serialize snapshot: {}", message)
+            }
+            SnapshotError::Deserialize(message) => {
+                write!(f, "failed to deserialize snapshot: {}", message)
+            }
+            SnapshotError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Clones the reflected components of an entity, sorted by type name
+fn capture_components(
+    world: &World,
+    registry: &TypeRegistry,
+    entity: Entity,
+) -> Vec<Box<dyn Reflect>> {
+    let entity_ref = match world.get_entity(entity) {
+        Some(entity_ref) => entity_ref,
+        None => return Vec::new(),
+    };
+
+    let mut components = entity_ref
+        .archetype()
+        .components()
+        .filter_map(|component_id| {
+            let type_id = world.components().get_info(component_id)?.type_id()?;
+            let reflect_component = registry.get(type_id)?.data::<ReflectComponent>()?;
+            reflect_component
+                .reflect(world, entity)
+                .map(|component| component.clone_value())
+        })
+        .collect::<Vec<_>>();
+    components.sort_by(|a, b| a.type_name().cmp(b.type_name()));
+    components
+}
+
+fn restore_components(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity_map: &HashMap<u32, Entity>,
+    index: u32,
+    components: &[Box<dyn Reflect>],
+) -> Result<(), SnapshotError> {
+    let entity = entity_map[&index];
+    for component in components.iter() {
+        let type_name = component.type_name();
+        let reflect_component = registry
+            .get_with_name(type_name)
+            .ok_or_else(|| SnapshotError::UnregisteredType(type_name.to_string()))?
+            .data::<ReflectComponent>()
+            .ok_or_else(|| SnapshotError::UnregisteredComponent(type_name.to_string()))?;
+
+        let mut component = component.clone_value();
+        map_entities(&mut *component, entity_map);
+        reflect_component.insert(world, entity, &*component);
+    }
+    Ok(())
+}
+
+/// Points any entity within the value that was captured in the snapshot to its restored entity
+///
+/// Entities used as map keys are left as-is.
+fn map_entities(value: &mut dyn Reflect, entity_map: &HashMap<u32, Entity>) {
+    if let Some(entity) = value.downcast_mut::<Entity>() {
+        if let Some(mapped) = entity_map.get(&entity.index()) {
+            *entity = *mapped;
+        }
+        return;
+    }
+
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for i in 0..value.field_len() {
+                map_entities(value.field_at_mut(i).unwrap(), entity_map);
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for i in 0..value.field_len() {
+                map_entities(value.field_mut(i).unwrap(), entity_map);
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for i in 0..value.field_len() {
+                map_entities(value.field_mut(i).unwrap(), entity_map);
+            }
+        }
+        ReflectMut::List(value) => {
+            for i in 0..value.len() {
+                map_entities(value.get_mut(i).unwrap(), entity_map);
+            }
+        }
+        ReflectMut::Array(value) => {
+            for i in 0..value.len() {
+                map_entities(value.get_mut(i).unwrap(), entity_map);
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for i in 0..value.field_len() {
+                map_entities(value.field_at_mut(i).unwrap(), entity_map);
+            }
+        }
+        ReflectMut::Map(_) | ReflectMut::Value(_) => {}
+    }
+}
+
+struct SnapshotSerializer<'a> {
+    snapshot: &'a UiSnapshot,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for SnapshotSerializer<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("UiSnapshot", 1)?;
+        state.serialize_field(
+            "widgets",
+            &WidgetsSerializer {
+                widgets: &self.snapshot.widgets,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct WidgetsSerializer<'a> {
+    widgets: &'a [WidgetSnapshot],
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for WidgetsSerializer<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_seq(Some(self.widgets.len()))?;
+        for widget in self.widgets.iter() {
+            state.serialize_element(&WidgetSerializer {
+                widget,
+                registry: self.registry,
+            })?;
+        }
+        state.end()
+    }
+}
+
+struct WidgetSerializer<'a> {
+    widget: &'a WidgetSnapshot,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for WidgetSerializer<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let context_entities = self
+            .widget
+            .context_entities
+            .iter()
+            .map(|context_entity| ContextEntitySerializer {
+                context_entity,
+                registry: self.registry,
+            })
+            .collect::<Vec<_>>();
+
+        let mut state = serializer.serialize_struct("Widget", 4)?;
+        state.serialize_field("entity", &self.widget.entity)?;
+        state.serialize_field("parent", &self.widget.parent)?;
+        state.serialize_field(
+            "components",
+            &ComponentsSerializer {
+                components: &self.widget.components,
+                registry: self.registry,
+            },
+        )?;
+        state.serialize_field("context_entities", &context_entities)?;
+        state.end()
+    }
+}
+
+struct ContextEntitySerializer<'a> {
+    context_entity: &'a ContextEntitySnapshot,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for ContextEntitySerializer<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ContextEntity", 3)?;
+        state.serialize_field("context", &self.context_entity.context)?;
+        state.serialize_field("entity", &self.context_entity.entity)?;
+        state.serialize_field(
+            "components",
+            &ComponentsSerializer {
+                components: &self.context_entity.components,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct ComponentsSerializer<'a> {
+    components: &'a [Box<dyn Reflect>],
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for ComponentsSerializer<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_seq(Some(self.components.len()))?;
+        for component in self.components.iter() {
+            state.serialize_element(&ReflectSerializer::new(&**component, self.registry))?;
+        }
+        state.end()
+    }
+}
+
+struct SnapshotDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for SnapshotDeserializer<'a> {
+    type Value = UiSnapshot;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("UiSnapshot", &["widgets"], self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for SnapshotDeserializer<'a> {
+    type Value = UiSnapshot;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a UI snapshot")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut widgets = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "widgets" => {
+                    widgets = Some(map.next_value_seed(SeqDeserializer {
+                        element: WidgetDeserializer {
+                            registry: self.registry,
+                        },
+                    })?)
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(UiSnapshot {
+            widgets: widgets.ok_or_else(|| A::Error::missing_field("widgets"))?,
+        })
+    }
+}
+
+/// Deserializes a sequence, using a copy of the given seed for each element
+struct SeqDeserializer<T> {
+    element: T,
+}
+
+impl<'de, T> DeserializeSeed<'de> for SeqDeserializer<T>
+where
+    T: DeserializeSeed<'de> + Copy,
+{
+    type Value = Vec<T::Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T> Visitor<'de> for SeqDeserializer<T>
+where
+    T: DeserializeSeed<'de> + Copy,
+{
+    type Value = Vec<T::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element_seed(self.element)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct WidgetDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for WidgetDeserializer<'a> {
+    type Value = WidgetSnapshot;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct(
+            "Widget",
+            &["entity", "parent", "components", "context_entities"],
+            self,
+        )
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for WidgetDeserializer<'a> {
+    type Value = WidgetSnapshot;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a widget")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entity = None;
+        let mut parent = None;
+        let mut components = Vec::new();
+        let mut context_entities = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "entity" => entity = Some(map.next_value()?),
+                "parent" => parent = map.next_value()?,
+                "components" => {
+                    components = map.next_value_seed(SeqDeserializer {
+                        element: ComponentDeserializer {
+                            registry: self.registry,
+                        },
+                    })?
+                }
+                "context_entities" => {
+                    context_entities = map.next_value_seed(SeqDeserializer {
+                        element: ContextEntityDeserializer {
+                            registry: self.registry,
+                        },
+                    })?
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(WidgetSnapshot {
+            entity: entity.ok_or_else(|| A::Error::missing_field("entity"))?,
+            parent,
+            components,
+            context_entities,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ContextEntityDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for ContextEntityDeserializer<'a> {
+    type Value = ContextEntitySnapshot;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("ContextEntity", &["context", "entity", "components"], self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for ContextEntityDeserializer<'a> {
+    type Value = ContextEntitySnapshot;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a context entity")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut context = None;
+        let mut entity = None;
+        let mut components = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "context" => context = Some(map.next_value()?),
+                "entity" => entity = Some(map.next_value()?),
+                "components" => {
+                    components = map.next_value_seed(SeqDeserializer {
+                        element: ComponentDeserializer {
+                            registry: self.registry,
+                        },
+                    })?
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(ContextEntitySnapshot {
+            context: context.ok_or_else(|| A::Error::missing_field("context"))?,
+            entity: entity.ok_or_else(|| A::Error::missing_field("entity"))?,
+            components,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ComponentDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for ComponentDeserializer<'a> {
+    type Value = Box<dyn Reflect>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        UntypedReflectDeserializer::new(self.registry).deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, AppTypeRegistry, Color, Entity, FromReflect, Reflect, Vec2},
+        reflect::serde::{ReflectSerializer, UntypedReflectDeserializer},
+        utils::HashMap,
+        window::CursorIcon,
+    };
+    use kayak_font::Alignment;
+    use serde::de::DeserializeSeed;
+
+    use super::{map_entities, UiSnapshot};
+    use crate::{
+        children::KChildren,
+        context::{Context, WidgetName},
+        node::WrappedIndex,
+        styles::{Breakpoint, Edge, KCursorIcon, KStyle, Overlay, Placement, StyleProp, Units},
+        widgets::{KButtonBundle, ScrollBoxBundle, ScrollContext, TextProps},
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.register_type::<WidgetName>()
+            .register_type::<KChildren>()
+            .register_type::<Vec<Entity>>()
+            .register_type::<Color>()
+            .register_type::<Vec2>()
+            .insert_resource(Context::new());
+        crate::styles::register_types(&mut app);
+        crate::widgets::register_types(&mut app);
+        app
+    }
+
+    /// Numbers the entities of the snapshot in the order they appear, so that snapshots of
+    /// different worlds can be compared
+    fn normalize(snapshot: &mut UiSnapshot) {
+        let mut entity_map = HashMap::default();
+        for widget in snapshot.widgets.iter() {
+            let next = entity_map.len() as u32;
+            entity_map.insert(widget.entity, Entity::from_raw(next));
+            for context_entity in widget.context_entities.iter() {
+                let next = entity_map.len() as u32;
+                entity_map.insert(context_entity.entity, Entity::from_raw(next));
+            }
+        }
+
+        for widget in snapshot.widgets.iter_mut() {
+            widget.entity = entity_map[&widget.entity].index();
+            widget.parent = widget.parent.map(|parent| entity_map[&parent].index());
+            for component in widget.components.iter_mut() {
+                map_entities(&mut **component, &entity_map);
+            }
+            for context_entity in widget.context_entities.iter_mut() {
+                context_entity.entity = entity_map[&context_entity.entity].index();
+                for component in context_entity.components.iter_mut() {
+                    map_entities(&mut **component, &entity_map);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_restore_tree_and_components() {
+        let mut app = app();
+
+        // Tree Structure:
+        //      A
+        //    B   C
+        let b = app.world.spawn(WidgetName::from(String::from("B"))).id();
+        let c = app.world.spawn(WidgetName::from(String::from("C"))).id();
+        let mut children = KChildren::new();
+        children.add(b);
+        children.add(c);
+        let a = app
+            .world
+            .spawn((
+                WidgetName::from(String::from("A")),
+                KStyle {
+                    width: Units::Pixels(100.0).into(),
+                    ..Default::default()
+                },
+                children,
+            ))
+            .id();
+        {
+            let mut context = app.world.resource_mut::<Context>();
+            context.add_widget(None, a);
+            context.add_widget(Some(a), b);
+            context.add_widget(Some(a), c);
+        }
+
+        let registry = app.world.resource::<AppTypeRegistry>().clone();
+        let source = UiSnapshot::capture(&app.world)
+            .unwrap()
+            .to_ron(&registry)
+            .unwrap();
+
+        let mut restored = self::app();
+        // Offset the entities so that the restored ones differ from the captured ones
+        restored.world.spawn_empty();
+        let registry = restored.world.resource::<AppTypeRegistry>().clone();
+        let snapshot = UiSnapshot::from_ron(&source, &registry).unwrap();
+        let entity_map = snapshot.restore(&mut restored.world).unwrap();
+
+        let (a, b, c) = (
+            entity_map[&a.index()],
+            entity_map[&b.index()],
+            entity_map[&c.index()],
+        );
+        let context = restored.world.resource::<Context>();
+        let tree = context.tree.read().unwrap();
+        assert_eq!(tree.root_node, Some(WrappedIndex(a)));
+        assert_eq!(
+            tree.children.get(&WrappedIndex(a)),
+            Some(&vec![WrappedIndex(b), WrappedIndex(c)])
+        );
+
+        let world = &restored.world;
+        assert_eq!(world.get::<WidgetName>(c).unwrap().0, "C");
+        assert_eq!(
+            world.get::<KStyle>(a).unwrap().width,
+            Units::Pixels(100.0).into()
+        );
+        let children = world.get::<KChildren>(a).unwrap();
+        assert_eq!((children.get(0), children.get(1)), (Some(b), Some(c)));
+
+        // A snapshot of the restored UI serializes the same way, apart from the entities
+        let mut expected = UiSnapshot::from_ron(&source, &registry).unwrap();
+        normalize(&mut expected);
+        let mut snapshot = UiSnapshot::capture(world).unwrap();
+        normalize(&mut snapshot);
+        assert_eq!(
+            expected.to_ron(&registry).unwrap(),
+            snapshot.to_ron(&registry).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_restore_built_in_widgets_and_context_entities() {
+        let mut app = app();
+
+        // Tree Structure:
+        //      ScrollBox (provides ScrollContext)
+        //        Button
+        let button = app
+            .world
+            .spawn(KButtonBundle {
+                styles: KStyle {
+                    background_color: StyleProp::Value(Color::rgb(0.2, 0.3, 0.4)),
+                    cursor: StyleProp::Value(KCursorIcon(CursorIcon::Hand)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .id();
+        let mut children = KChildren::new();
+        children.add(button);
+        let scroll_box = app
+            .world
+            .spawn(ScrollBoxBundle {
+                children,
+                ..Default::default()
+            })
+            .id();
+        let scroll_context = app.world.spawn(ScrollContext::default()).id();
+        {
+            let mut context = app.world.resource_mut::<Context>();
+            context.add_widget(None, scroll_box);
+            context.add_widget(Some(scroll_box), button);
+            context
+                .context_entities
+                .add_context_entity::<ScrollContext>(scroll_box, scroll_context);
+        }
+
+        let registry = app.world.resource::<AppTypeRegistry>().clone();
+        let mut snapshot = UiSnapshot::capture(&app.world).unwrap();
+        let source = snapshot.to_ron(&registry).unwrap();
+        normalize(&mut snapshot);
+        let expected = snapshot.to_ron(&registry).unwrap();
+
+        let mut restored = self::app();
+        // Offset the entities so that the restored ones differ from the captured ones
+        restored.world.spawn_empty();
+        let registry = restored.world.resource::<AppTypeRegistry>().clone();
+        let entity_map = UiSnapshot::from_ron(&source, &registry)
+            .unwrap()
+            .restore(&mut restored.world)
+            .unwrap();
+
+        let scroll_box = entity_map[&scroll_box.index()];
+        let context = restored.world.resource::<Context>();
+        let scroll_context = context
+            .context_entities
+            .get_context_entity::<ScrollContext>(scroll_box)
+            .unwrap();
+        assert!(restored
+            .world
+            .get::<ScrollContext>(scroll_context)
+            .is_some());
+
+        let mut snapshot = UiSnapshot::capture(&restored.world).unwrap();
+        normalize(&mut snapshot);
+        assert_eq!(expected, snapshot.to_ron(&registry).unwrap());
+    }
+
+    /// Serializes the value to RON and back, through the type registry
+    fn round_trip(app: &App, value: &dyn Reflect) -> Box<dyn Reflect> {
+        let registry = app.world.resource::<AppTypeRegistry>().read();
+        let serialized = ron::to_string(&ReflectSerializer::new(value, &registry)).unwrap();
+        let mut deserializer = ron::Deserializer::from_str(&serialized).unwrap();
+        UntypedReflectDeserializer::new(&registry)
+            .deserialize(&mut deserializer)
+            .unwrap()
+    }
+
+    #[test]
+    fn styles_and_props_should_round_trip_through_reflection() {
+        let app = app();
+
+        let styles = KStyle {
+            color: StyleProp::Value(Color::rgb(0.25, 0.5, 0.75)),
+            padding: StyleProp::Value(Edge::axis(Units::Pixels(4.0), Units::Stretch(1.0))),
+            overlay: StyleProp::Value(Overlay::anchored(Entity::from_raw(3), Placement::Below)),
+            breakpoints: StyleProp::Value(vec![Breakpoint::max_width(
+                600.0,
+                KStyle {
+                    width: StyleProp::Value(Units::Percentage(100.0)),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let reflected = round_trip(&app, &styles);
+        assert_eq!(Some(styles), KStyle::from_reflect(reflected.as_ref()));
+
+        let props = TextProps {
+            content: "Hello".to_string(),
+            font: Some("roboto".to_string()),
+            line_height: Some(20.0),
+            show_cursor: true,
+            size: 16.0,
+            alignment: Alignment::Middle,
+        };
+        let reflected = round_trip(&app, &props);
+        let mut restored = TextProps::default();
+        restored.apply(reflected.as_ref());
+        assert!(restored.reflect_partial_eq(&props).unwrap_or_default());
+        assert_eq!(restored.content, "Hello");
+        assert_eq!(restored.alignment, Alignment::Middle);
+    }
+}
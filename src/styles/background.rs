@@ -0,0 +1,121 @@
+use bevy::prelude::{Color, Vec2};
+use bevy::reflect::{FromReflect, Reflect};
+
+/// The fill of a widget, used by the [`background`](crate::styles::KStyle::background) style
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
+pub enum Background {
+    /// A single, solid color
+    Color(Color),
+    /// A color gradient
+    Gradient(Gradient),
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Color(Color::rgba(1.0, 1.0, 1.0, 0.0))
+    }
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Background::Color(color)
+    }
+}
+
+impl From<Gradient> for Background {
+    fn from(gradient: Gradient) -> Self {
+        Background::Gradient(gradient)
+    }
+}
+
+/// A color at a specific point along a [`Gradient`]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ColorStop {
+    /// The color at this stop
+    pub color: Color,
+    /// The position of this stop along the gradient, from `0.0` to `1.0`
+    pub position: f32,
+}
+
+impl ColorStop {
+    pub fn new(color: Color, position: f32) -> Self {
+        Self { color, position }
+    }
+}
+
+/// The shape of a [`Gradient`]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub enum GradientKind {
+    /// A gradient along a straight line
+    Linear {
+        /// The direction of the gradient line, in degrees
+        ///
+        /// `0.0` points up and increasing values turn clockwise, so `90.0` points right.
+        angle: f32,
+    },
+    /// A gradient radiating out from a point
+    Radial {
+        /// The center of the gradient, relative to the widget's size (`(0.5, 0.5)` is the middle)
+        ///
+        /// The gradient ends at the corner farthest from the center.
+        center: Vec2,
+    },
+}
+
+/// A smooth transition between two or more colors
+///
+/// Only the first [`Gradient::MAX_STOPS`] stops are rendered. Stops should be given in
+/// increasing order of position.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::Color;
+/// # use kayak_ui::prelude::{ColorStop, Gradient};
+/// // Red at the top, fading to blue at the bottom
+/// let gradient = Gradient::linear(
+///     180.0,
+///     vec![
+///         ColorStop::new(Color::RED, 0.0),
+///         ColorStop::new(Color::BLUE, 1.0),
+///     ],
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub stops: Vec<ColorStop>,
+}
+
+impl Gradient {
+    /// The maximum number of stops that can be rendered
+    pub const MAX_STOPS: usize = 8;
+
+    /// Creates a linear gradient
+    ///
+    /// # Arguments
+    ///
+    /// * `angle`: The direction of the gradient line, in degrees (`0.0` points up, `90.0` points right)
+    /// * `stops`: The colors along the gradient
+    ///
+    pub fn linear(angle: f32, stops: Vec<ColorStop>) -> Self {
+        Self {
+            kind: GradientKind::Linear { angle },
+            stops,
+        }
+    }
+
+    /// Creates a radial gradient
+    ///
+    /// # Arguments
+    ///
+    /// * `center`: The center of the gradient, relative to the widget's size
+    /// * `stops`: The colors from the center outwards
+    ///
+    pub fn radial(center: Vec2, stops: Vec<ColorStop>) -> Self {
+        Self {
+            kind: GradientKind::Radial { center },
+            stops,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use bevy::reflect::{FromReflect, Reflect};
+
+/// The line style of a widget's border, used by the [`border_style`](crate::styles::KStyle::border_style) style
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+pub enum BorderStyle {
+    /// A single, continuous line
+    Solid,
+    /// A series of short dashes
+    Dashed,
+    /// A series of round dots
+    Dotted,
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        BorderStyle::Solid
+    }
+}
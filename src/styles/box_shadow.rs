@@ -0,0 +1,56 @@
+use bevy::prelude::{Color, Vec2};
+use bevy::reflect::{FromReflect, Reflect};
+
+/// A shadow cast by (or into) a widget, used by the [`box_shadow`](crate::styles::KStyle::box_shadow) style
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::Color;
+/// # use kayak_ui::prelude::BoxShadow;
+/// // A soft drop shadow, slightly below the widget
+/// let drop_shadow = BoxShadow::new(0.0, 4.0, 12.0, 0.0, Color::rgba(0.0, 0.0, 0.0, 0.5));
+/// // A thin inner highlight
+/// let highlight = BoxShadow::new(0.0, 1.0, 0.0, 0.0, Color::rgba(1.0, 1.0, 1.0, 0.1)).inset();
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct BoxShadow {
+    /// The offset of the shadow from the widget, in pixels
+    pub offset: Vec2,
+    /// How far the edges of the shadow are blurred, in pixels
+    pub blur_radius: f32,
+    /// How far the shadow grows (or shrinks, if negative) past the widget's edges, in pixels
+    pub spread: f32,
+    /// The color of the shadow
+    pub color: Color,
+    /// If true, the shadow is cast inside the widget rather than behind it
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    /// Creates a drop shadow
+    ///
+    /// # Arguments
+    ///
+    /// * `offset_x`: The horizontal offset of the shadow, in pixels
+    /// * `offset_y`: The vertical offset of the shadow, in pixels
+    /// * `blur_radius`: How far the edges of the shadow are blurred, in pixels
+    /// * `spread`: How far the shadow grows past the widget's edges, in pixels
+    /// * `color`: The color of the shadow
+    ///
+    pub fn new(offset_x: f32, offset_y: f32, blur_radius: f32, spread: f32, color: Color) -> Self {
+        Self {
+            offset: Vec2::new(offset_x, offset_y),
+            blur_radius,
+            spread,
+            color,
+            inset: false,
+        }
+    }
+
+    /// Turns this into an inset shadow
+    pub fn inset(mut self) -> Self {
+        self.inset = true;
+        self
+    }
+}
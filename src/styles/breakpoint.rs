@@ -0,0 +1,152 @@
+use bevy::prelude::Vec2;
+use bevy::reflect::{FromReflect, Reflect};
+
+use super::KStyle;
+
+/// A condition on the size of the viewport, used by [`Breakpoint`]
+///
+/// Every bound that is set must hold for the query to match. All bounds are inclusive.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ViewportQuery {
+    /// The minimum width of the viewport, in pixels
+    pub min_width: Option<f32>,
+    /// The maximum width of the viewport, in pixels
+    pub max_width: Option<f32>,
+    /// The minimum height of the viewport, in pixels
+    pub min_height: Option<f32>,
+    /// The maximum height of the viewport, in pixels
+    pub max_height: Option<f32>,
+    /// The minimum ratio of the viewport's width to its height
+    pub min_aspect_ratio: Option<f32>,
+    /// The maximum ratio of the viewport's width to its height
+    pub max_aspect_ratio: Option<f32>,
+}
+
+impl ViewportQuery {
+    /// Returns true if a viewport of the given size satisfies this query
+    pub fn matches(&self, viewport: Vec2) -> bool {
+        let aspect_ratio = if viewport.y > 0.0 {
+            viewport.x / viewport.y
+        } else {
+            0.0
+        };
+        let within = |value: f32, min: Option<f32>, max: Option<f32>| {
+            min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+        };
+
+        within(viewport.x, self.min_width, self.max_width)
+            && within(viewport.y, self.min_height, self.max_height)
+            && within(aspect_ratio, self.min_aspect_ratio, self.max_aspect_ratio)
+    }
+}
+
+/// A style that only applies while the viewport matches a query, used by the
+/// [`breakpoints`](crate::styles::KStyle::breakpoints) style
+///
+/// # Example
+///
+/// ```
+/// # use kayak_ui::prelude::{Breakpoint, KStyle, Units};
+/// // A sidebar that collapses to a strip of icons on narrow windows
+/// let sidebar = KStyle {
+///     width: Units::Pixels(240.0).into(),
+///     breakpoints: vec![Breakpoint::max_width(
+///         800.0,
+///         KStyle {
+///             width: Units::Pixels(48.0).into(),
+///             ..Default::default()
+///         },
+///     )]
+///     .into(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Reflect, FromReflect)]
+pub struct Breakpoint {
+    /// The condition under which the style applies
+    pub query: ViewportQuery,
+    /// The style applied over the widget's own while the query matches
+    pub style: KStyle,
+}
+
+impl Breakpoint {
+    /// Creates a breakpoint with the given condition
+    pub fn new(query: ViewportQuery, style: KStyle) -> Self {
+        Self { query, style }
+    }
+
+    /// Creates a breakpoint that applies while the viewport is at least `width` pixels wide
+    pub fn min_width(width: f32, style: KStyle) -> Self {
+        Self::new(
+            ViewportQuery {
+                min_width: Some(width),
+                ..Default::default()
+            },
+            style,
+        )
+    }
+
+    /// Creates a breakpoint that applies while the viewport is at most `width` pixels wide
+    pub fn max_width(width: f32, style: KStyle) -> Self {
+        Self::new(
+            ViewportQuery {
+                max_width: Some(width),
+                ..Default::default()
+            },
+            style,
+        )
+    }
+
+    /// Creates a breakpoint that applies while the viewport is wider than it is tall
+    pub fn landscape(style: KStyle) -> Self {
+        Self::new(
+            ViewportQuery {
+                min_aspect_ratio: Some(1.0),
+                ..Default::default()
+            },
+            style,
+        )
+    }
+
+    /// Creates a breakpoint that applies while the viewport is taller than it is wide
+    pub fn portrait(style: KStyle) -> Self {
+        Self::new(
+            ViewportQuery {
+                max_aspect_ratio: Some(1.0),
+                ..Default::default()
+            },
+            style,
+        )
+    }
+
+    /// Returns true if the style applies to a viewport of the given size
+    pub fn matches(&self, viewport: Vec2) -> bool {
+        self.query.matches(viewport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Breakpoint, ViewportQuery};
+    use crate::styles::KStyle;
+    use bevy::prelude::Vec2;
+
+    #[test]
+    fn max_width_should_be_inclusive() {
+        let breakpoint = Breakpoint::max_width(800.0, KStyle::default());
+        assert!(breakpoint.matches(Vec2::new(800.0, 600.0)));
+        assert!(!breakpoint.matches(Vec2::new(801.0, 600.0)));
+    }
+
+    #[test]
+    fn query_should_require_every_bound() {
+        let query = ViewportQuery {
+            min_width: Some(600.0),
+            max_aspect_ratio: Some(1.0),
+            ..Default::default()
+        };
+        assert!(query.matches(Vec2::new(700.0, 900.0)));
+        assert!(!query.matches(Vec2::new(500.0, 900.0)));
+        assert!(!query.matches(Vec2::new(1200.0, 900.0)));
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::reflect::{FromReflect, Reflect};
+
+/// Whether a widget takes part in the layout, used by the [`display`](crate::styles::KStyle::display) style
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+pub enum Display {
+    /// The widget is laid out, rendered, and receives events as normal
+    Normal,
+    /// The widget and its descendants are removed from layout, rendering, and events
+    ///
+    /// Unlike removing the widget from the tree, its state is kept.
+    None,
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Display::Normal
+    }
+}
+
+/// Whether a widget is drawn, used by the [`visibility`](crate::styles::KStyle::visibility) style
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+pub enum Visibility {
+    /// The widget is rendered and receives events as normal
+    Visible,
+    /// The widget keeps its space in the layout, but isn't rendered and doesn't receive events
+    Hidden,
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Visibility::Visible
+    }
+}
@@ -0,0 +1,73 @@
+use bevy::reflect::{FromReflect, Reflect};
+
+/// How a widget lays out its children, used by the
+/// [`layout_type`](crate::styles::KStyle::layout_type) style
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+pub enum LayoutType {
+    /// Places children next to each other, from left to right
+    Row,
+    /// Places children below each other, from top to bottom
+    Column,
+    /// Places children in the cells of a grid
+    Grid,
+}
+
+impl Default for LayoutType {
+    fn default() -> Self {
+        morphorm::LayoutType::default().into()
+    }
+}
+
+impl From<morphorm::LayoutType> for LayoutType {
+    fn from(layout_type: morphorm::LayoutType) -> Self {
+        match layout_type {
+            morphorm::LayoutType::Row => LayoutType::Row,
+            morphorm::LayoutType::Column => LayoutType::Column,
+            morphorm::LayoutType::Grid => LayoutType::Grid,
+        }
+    }
+}
+
+impl From<LayoutType> for morphorm::LayoutType {
+    fn from(layout_type: LayoutType) -> Self {
+        match layout_type {
+            LayoutType::Row => morphorm::LayoutType::Row,
+            LayoutType::Column => morphorm::LayoutType::Column,
+            LayoutType::Grid => morphorm::LayoutType::Grid,
+        }
+    }
+}
+
+/// How a widget is positioned, used by the
+/// [`position_type`](crate::styles::KStyle::position_type) style
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+pub enum PositionType {
+    /// Positioned by the widget itself, relative to its parent and ignoring its siblings
+    SelfDirected,
+    /// Positioned by the parent, alongside the widget's siblings
+    ParentDirected,
+}
+
+impl Default for PositionType {
+    fn default() -> Self {
+        morphorm::PositionType::default().into()
+    }
+}
+
+impl From<morphorm::PositionType> for PositionType {
+    fn from(position_type: morphorm::PositionType) -> Self {
+        match position_type {
+            morphorm::PositionType::SelfDirected => PositionType::SelfDirected,
+            morphorm::PositionType::ParentDirected => PositionType::ParentDirected,
+        }
+    }
+}
+
+impl From<PositionType> for morphorm::PositionType {
+    fn from(position_type: PositionType) -> Self {
+        match position_type {
+            PositionType::SelfDirected => morphorm::PositionType::SelfDirected,
+            PositionType::ParentDirected => morphorm::PositionType::ParentDirected,
+        }
+    }
+}
@@ -1,11 +1,109 @@
+use bevy::prelude::{App, Color, Entity};
+use kayak_font::Alignment;
+
+use crate::cursor::PointerEvents;
+
+mod background;
+mod border_style;
+mod box_shadow;
+mod breakpoint;
 mod corner;
+mod display;
 mod edge;
+mod layout_type;
+mod mount_animation;
 mod options_ref;
+mod overlay;
+mod parse;
 mod render_command;
 mod style;
+mod transform;
+mod transition;
+mod units;
 
+pub use background::{Background, ColorStop, Gradient, GradientKind};
+pub use border_style::BorderStyle;
+pub use box_shadow::BoxShadow;
+pub use breakpoint::{Breakpoint, ViewportQuery};
 pub use corner::Corner;
+pub use display::{Display, Visibility};
 pub use edge::Edge;
+pub use layout_type::{LayoutType, PositionType};
+pub use mount_animation::{AnimationEffect, MountAnimation};
 pub use options_ref::AsRefOption;
+pub use overlay::{Overlay, Placement};
+pub use parse::{parse_color, StyleParseError};
 pub use render_command::RenderCommand;
 pub use style::*;
+pub use transform::KTransform;
+pub use transition::{Easing, Lerp, Transition, TransitionProperty, TransitionSpec};
+pub use units::{RelativeSizes, Units};
+
+/// Registers [`KStyle`] and the types it's made of, so styles can be reflected (and saved in
+/// scenes or edited by an inspector)
+pub(crate) fn register_types(app: &mut App) {
+    app.register_type::<KStyle>()
+        .register_type::<Units>()
+        .register_type::<Edge<f32>>()
+        .register_type::<Edge<Units>>()
+        .register_type::<Corner<f32>>()
+        .register_type::<Background>()
+        .register_type::<Gradient>()
+        .register_type::<GradientKind>()
+        .register_type::<ColorStop>()
+        .register_type::<BorderStyle>()
+        .register_type::<BoxShadow>()
+        .register_type::<Breakpoint>()
+        .register_type::<ViewportQuery>()
+        .register_type::<KCursorIcon>()
+        .register_type::<Display>()
+        .register_type::<Visibility>()
+        .register_type::<MountAnimation>()
+        .register_type::<AnimationEffect>()
+        .register_type::<Easing>()
+        .register_type::<LayoutType>()
+        .register_type::<PositionType>()
+        .register_type::<Overlay>()
+        .register_type::<Placement>()
+        .register_type::<PointerEvents>()
+        .register_type::<RenderCommand>()
+        .register_type::<Alignment>()
+        .register_type::<KTransform>()
+        .register_type::<Transition>()
+        .register_type::<TransitionSpec>()
+        .register_type::<TransitionProperty>()
+        .register_type::<Vec<AnimationEffect>>()
+        .register_type::<Vec<BoxShadow>>()
+        .register_type::<Vec<Breakpoint>>()
+        .register_type::<Vec<ColorStop>>()
+        .register_type::<Vec<TransitionSpec>>()
+        .register_type::<Vec<Units>>()
+        .register_type::<Option<Entity>>()
+        .register_type::<Option<f32>>()
+        .register_type::<StyleProp<f32>>()
+        .register_type::<StyleProp<i32>>()
+        .register_type::<StyleProp<usize>>()
+        .register_type::<StyleProp<bool>>()
+        .register_type::<StyleProp<String>>()
+        .register_type::<StyleProp<Color>>()
+        .register_type::<StyleProp<Units>>()
+        .register_type::<StyleProp<Vec<Units>>>()
+        .register_type::<StyleProp<Edge<f32>>>()
+        .register_type::<StyleProp<Edge<Units>>>()
+        .register_type::<StyleProp<Corner<f32>>>()
+        .register_type::<StyleProp<Background>>()
+        .register_type::<StyleProp<BorderStyle>>()
+        .register_type::<StyleProp<Vec<BoxShadow>>>()
+        .register_type::<StyleProp<Vec<Breakpoint>>>()
+        .register_type::<StyleProp<KCursorIcon>>()
+        .register_type::<StyleProp<Display>>()
+        .register_type::<StyleProp<Visibility>>()
+        .register_type::<StyleProp<MountAnimation>>()
+        .register_type::<StyleProp<LayoutType>>()
+        .register_type::<StyleProp<PositionType>>()
+        .register_type::<StyleProp<Overlay>>()
+        .register_type::<StyleProp<PointerEvents>>()
+        .register_type::<StyleProp<RenderCommand>>()
+        .register_type::<StyleProp<KTransform>>()
+        .register_type::<StyleProp<Transition>>();
+}
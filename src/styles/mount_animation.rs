@@ -0,0 +1,73 @@
+use bevy::reflect::{FromReflect, Reflect};
+
+use super::Easing;
+
+/// A visual effect played while a widget enters or exits the tree
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub enum AnimationEffect {
+    /// Fades the widget in from (or out to) full transparency
+    Fade,
+    /// Slides the widget in from (or out to) the given offset, in pixels
+    Slide { x: f32, y: f32 },
+    /// Scales the widget in from (or out to) the given factor, around its center
+    Scale(f32),
+}
+
+/// An animation played when a widget is added to or removed from the tree
+///
+/// Used by the [`enter_animation`](crate::styles::KStyle::enter_animation) and
+/// [`exit_animation`](crate::styles::KStyle::exit_animation) styles.
+///
+/// # Example
+///
+/// ```
+/// # use kayak_ui::prelude::{Easing, MountAnimation};
+/// // Fade in while sliding up from 20 pixels below
+/// let animation = MountAnimation::new(0.25, Easing::EaseOut)
+///     .fade()
+///     .slide(0.0, 20.0);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Reflect, FromReflect)]
+pub struct MountAnimation {
+    /// The effects to play, all at once
+    pub effects: Vec<AnimationEffect>,
+    /// The length of the animation, in seconds
+    pub duration: f32,
+    /// The easing curve of the animation
+    pub easing: Easing,
+}
+
+impl MountAnimation {
+    /// Creates an animation without any effects
+    ///
+    /// # Arguments
+    ///
+    /// * `duration`: The length of the animation, in seconds
+    /// * `easing`: The easing curve of the animation
+    ///
+    pub fn new(duration: f32, easing: Easing) -> Self {
+        Self {
+            effects: Vec::new(),
+            duration,
+            easing,
+        }
+    }
+
+    /// Adds a [`AnimationEffect::Fade`] effect
+    pub fn fade(mut self) -> Self {
+        self.effects.push(AnimationEffect::Fade);
+        self
+    }
+
+    /// Adds a [`AnimationEffect::Slide`] effect from (or to) the given offset
+    pub fn slide(mut self, x: f32, y: f32) -> Self {
+        self.effects.push(AnimationEffect::Slide { x, y });
+        self
+    }
+
+    /// Adds a [`AnimationEffect::Scale`] effect from (or to) the given factor
+    pub fn scale(mut self, factor: f32) -> Self {
+        self.effects.push(AnimationEffect::Scale(factor));
+        self
+    }
+}
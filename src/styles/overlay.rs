@@ -0,0 +1,78 @@
+use bevy::prelude::{Entity, Vec2};
+use bevy::reflect::{FromReflect, Reflect};
+
+use crate::layout::Rect;
+
+/// Where an [`Overlay`] is placed relative to its anchor widget
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect, FromReflect)]
+pub enum Placement {
+    /// Below the anchor, aligned to its left edge
+    Below,
+    /// Above the anchor, aligned to its left edge
+    Above,
+    /// To the left of the anchor, aligned to its top edge
+    Left,
+    /// To the right of the anchor, aligned to its top edge
+    Right,
+    /// On top of the anchor, aligned to its top left corner
+    Over,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Placement::Below
+    }
+}
+
+/// Renders a widget and its descendants in the overlay layer, used by the
+/// [`overlay`](crate::styles::KStyle::overlay) style
+///
+/// Overlaid widgets are drawn above the rest of the tree and escape the clipping of their
+/// ancestors. They keep their place in the widget tree, so events still bubble up through
+/// their logical parents and context lookups are unaffected.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct Overlay {
+    /// The widget to position against
+    ///
+    /// If `None`, the widget keeps the position given to it by layout.
+    pub anchor: Option<Entity>,
+    /// Where the widget is placed relative to its anchor
+    pub placement: Placement,
+    /// An additional offset from the anchored position, in pixels
+    pub offset: Vec2,
+}
+
+impl Overlay {
+    /// Creates an overlay positioned against the given widget
+    ///
+    /// # Arguments
+    ///
+    /// * `anchor`: The widget to position against
+    /// * `placement`: Where the widget is placed relative to its anchor
+    ///
+    pub fn anchored(anchor: Entity, placement: Placement) -> Self {
+        Self {
+            anchor: Some(anchor),
+            placement,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// Adds an offset from the anchored position, in pixels
+    pub fn with_offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = Vec2::new(x, y);
+        self
+    }
+
+    /// Returns the top left position of a widget of the given size placed against `anchor`
+    pub fn position(&self, anchor: &Rect, width: f32, height: f32) -> Vec2 {
+        let position = match self.placement {
+            Placement::Below => Vec2::new(anchor.posx, anchor.posy + anchor.height),
+            Placement::Above => Vec2::new(anchor.posx, anchor.posy - height),
+            Placement::Left => Vec2::new(anchor.posx - width, anchor.posy),
+            Placement::Right => Vec2::new(anchor.posx + anchor.width, anchor.posy),
+            Placement::Over => Vec2::new(anchor.posx, anchor.posy),
+        };
+        position + self.offset
+    }
+}
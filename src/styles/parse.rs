@@ -0,0 +1,617 @@
+use std::{fmt, str::FromStr};
+
+use bevy::{prelude::Color, reflect::FromReflect};
+
+use super::{
+    Background, BorderStyle, Corner, Display, Edge, KStyle, LayoutType, PositionType, StyleProp,
+    Units, Visibility,
+};
+use crate::cursor::PointerEvents;
+
+/// An error produced when parsing a style (or one of its values) from a string
+///
+/// Lines and columns start at `1`. When parsing a single value, such as with [`Units::from_str`],
+/// they're relative to the start of that value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleParseError {
+    /// The line the error occurred on
+    pub line: usize,
+    /// The column (in characters) the error occurred at
+    pub column: usize,
+    /// A description of what went wrong
+    pub message: String,
+}
+
+impl StyleParseError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: 1,
+            column: 1,
+            message: message.into(),
+        }
+    }
+
+    /// Moves this error from a position within a value to one within the string it came from
+    fn offset_by(mut self, line: usize, column: usize) -> Self {
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self.line += line - 1;
+        self
+    }
+}
+
+impl fmt::Display for StyleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (line {}, column {})",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for StyleParseError {}
+
+impl KStyle {
+    /// Parses a style from a CSS-like string of `property: value` declarations separated by `;`
+    ///
+    /// Property names are the names of the fields of [`KStyle`], in either kebab-case or
+    /// snake_case. Any property can also be set to `inherit`, `initial` (for
+    /// [`StyleProp::Default`]), or `unset`. Values are written as follows:
+    ///
+    /// * Units: `100px` (or just `100`), `50%`, `1s` (stretch), `auto`, `10vw`, `10vh`, `10vmin`,
+    /// `1.5em`, or `2rem`
+    /// * Colors: see [`parse_color`]
+    /// * Edges ([`padding`](KStyle::padding), [`offset`](KStyle::offset), and
+    /// [`border`](KStyle::border)): one to four values, in the same order as CSS
+    /// * Corners ([`border_radius`](KStyle::border_radius)): one to four values, in the same
+    /// order as CSS
+    /// * Grid tracks ([`grid_rows`](KStyle::grid_rows) and [`grid_cols`](KStyle::grid_cols)):
+    /// a space-separated list of units
+    /// * Keywords: the name of the variant in kebab-case (e.g. `self-directed`)
+    ///
+    /// Properties that hold more complex values, like [`transition`](KStyle::transition) or
+    /// [`box_shadow`](KStyle::box_shadow), can't be set from a string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kayak_ui::prelude::{KStyle, StyleProp, Units};
+    /// let style = KStyle::parse(
+    ///     "width: 100px; background-color: #1e1e1e; border-radius: 4px; padding: 5px 10px",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(style.width, StyleProp::Value(Units::Pixels(100.0)));
+    /// ```
+    pub fn parse(source: &str) -> Result<KStyle, StyleParseError> {
+        let mut style = KStyle::default();
+        let mut declaration_start = 0;
+        for declaration in source.split(';') {
+            let start = declaration_start;
+            declaration_start += declaration.len() + 1;
+            if declaration.trim().is_empty() {
+                continue;
+            }
+
+            let name_index = start + leading_whitespace(declaration);
+            let colon = match declaration.find(':') {
+                Some(colon) => colon,
+                None => {
+                    return Err(error_at(source, name_index, "expected `property: value`"));
+                }
+            };
+            let name = declaration[..colon].trim();
+            let value = declaration[colon + 1..].trim();
+            let value_index = start + colon + 1 + leading_whitespace(&declaration[colon + 1..]);
+            if value.is_empty() {
+                return Err(error_at(
+                    source,
+                    value_index,
+                    format!("expected a value for `{}`", name),
+                ));
+            }
+
+            match style.set_property(name, value) {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Err(error_at(
+                        source,
+                        name_index,
+                        format!("unknown property `{}`", name),
+                    ));
+                }
+                Err(error) => {
+                    let (line, column) = position(source, value_index);
+                    return Err(error.offset_by(line, column));
+                }
+            }
+        }
+        Ok(style)
+    }
+
+    /// Sets the property with the given name, returning false if there is no such property
+    fn set_property(&mut self, name: &str, value: &str) -> Result<bool, StyleParseError> {
+        let name = name.replace('_', "-").to_ascii_lowercase();
+        match name.as_str() {
+            "aspect-ratio" => self.aspect_ratio = parse_prop(value, parse_ratio)?,
+            "background" => {
+                self.background =
+                    parse_prop(value, |value| parse_color(value).map(Background::from))?
+            }
+            "background-color" => self.background_color = parse_prop(value, parse_color)?,
+            "border" => self.border = parse_prop(value, Edge::<f32>::from_str)?,
+            "border-color" => self.border_color = parse_prop(value, parse_color)?,
+            "border-color-bottom" => self.border_color_bottom = parse_prop(value, parse_color)?,
+            "border-color-left" => self.border_color_left = parse_prop(value, parse_color)?,
+            "border-color-right" => self.border_color_right = parse_prop(value, parse_color)?,
+            "border-color-top" => self.border_color_top = parse_prop(value, parse_color)?,
+            "border-radius" => self.border_radius = parse_prop(value, Corner::<f32>::from_str)?,
+            "border-style" => {
+                self.border_style = parse_prop(value, |value| {
+                    parse_keyword(
+                        value,
+                        &[
+                            ("solid", BorderStyle::Solid),
+                            ("dashed", BorderStyle::Dashed),
+                            ("dotted", BorderStyle::Dotted),
+                        ],
+                    )
+                })?
+            }
+            "bottom" => self.bottom = parse_prop(value, Units::from_str)?,
+            "color" => self.color = parse_prop(value, parse_color)?,
+            "col-between" => self.col_between = parse_prop(value, Units::from_str)?,
+            "col-index" => self.col_index = parse_prop(value, parse_integer)?,
+            "col-span" => self.col_span = parse_prop(value, parse_integer)?,
+            "display" => {
+                self.display = parse_prop(value, |value| {
+                    parse_keyword(
+                        value,
+                        &[("normal", Display::Normal), ("none", Display::None)],
+                    )
+                })?
+            }
+            "font" => {
+                self.font = parse_prop(value, |value| {
+                    Ok(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+                })?
+            }
+            "font-size" => self.font_size = parse_prop(value, parse_pixels)?,
+            "grid-cols" => self.grid_cols = parse_prop(value, parse_tracks)?,
+            "grid-rows" => self.grid_rows = parse_prop(value, parse_tracks)?,
+            "height" => self.height = parse_prop(value, Units::from_str)?,
+            "layout-type" => {
+                self.layout_type = parse_prop(value, |value| {
+                    parse_keyword(
+                        value,
+                        &[
+                            ("row", LayoutType::Row),
+                            ("column", LayoutType::Column),
+                            ("grid", LayoutType::Grid),
+                        ],
+                    )
+                })?
+            }
+            "left" => self.left = parse_prop(value, Units::from_str)?,
+            "line-height" => self.line_height = parse_prop(value, parse_pixels)?,
+            "max-height" => self.max_height = parse_prop(value, Units::from_str)?,
+            "max-width" => self.max_width = parse_prop(value, Units::from_str)?,
+            "min-height" => self.min_height = parse_prop(value, Units::from_str)?,
+            "min-width" => self.min_width = parse_prop(value, Units::from_str)?,
+            "offset" => self.offset = parse_prop(value, Edge::<Units>::from_str)?,
+            "opacity" => self.opacity = parse_prop(value, parse_number)?,
+            "padding" => self.padding = parse_prop(value, Edge::<Units>::from_str)?,
+            "padding-bottom" => self.padding_bottom = parse_prop(value, Units::from_str)?,
+            "padding-left" => self.padding_left = parse_prop(value, Units::from_str)?,
+            "padding-right" => self.padding_right = parse_prop(value, Units::from_str)?,
+            "padding-top" => self.padding_top = parse_prop(value, Units::from_str)?,
+            "pointer-events" => {
+                self.pointer_events = parse_prop(value, |value| {
+                    parse_keyword(
+                        value,
+                        &[
+                            ("all", PointerEvents::All),
+                            ("self-only", PointerEvents::SelfOnly),
+                            ("children-only", PointerEvents::ChildrenOnly),
+                            ("none", PointerEvents::None),
+                        ],
+                    )
+                })?
+            }
+            "position-type" => {
+                self.position_type = parse_prop(value, |value| {
+                    parse_keyword(
+                        value,
+                        &[
+                            ("parent-directed", PositionType::ParentDirected),
+                            ("self-directed", PositionType::SelfDirected),
+                        ],
+                    )
+                })?
+            }
+            "right" => self.right = parse_prop(value, Units::from_str)?,
+            "row-between" => self.row_between = parse_prop(value, Units::from_str)?,
+            "row-index" => self.row_index = parse_prop(value, parse_integer)?,
+            "row-span" => self.row_span = parse_prop(value, parse_integer)?,
+            "top" => self.top = parse_prop(value, Units::from_str)?,
+            "visibility" => {
+                self.visibility = parse_prop(value, |value| {
+                    parse_keyword(
+                        value,
+                        &[
+                            ("visible", Visibility::Visible),
+                            ("hidden", Visibility::Hidden),
+                        ],
+                    )
+                })?
+            }
+            "width" => self.width = parse_prop(value, Units::from_str)?,
+            "wrap" => {
+                self.wrap = parse_prop(value, |value| {
+                    parse_keyword(value, &[("true", true), ("false", false)])
+                })?
+            }
+            "z-index" => self.z_index = parse_prop(value, parse_integer)?,
+            "box-shadow" | "breakpoints" | "cursor" | "enter-animation" | "exit-animation"
+            | "overlay" | "render-command" | "transform" | "transition" => {
+                return Err(StyleParseError::new(format!(
+                    "`{}` can't be set from a string",
+                    name
+                )));
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+impl FromStr for KStyle {
+    type Err = StyleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KStyle::parse(s)
+    }
+}
+
+impl FromStr for Units {
+    type Err = StyleParseError;
+
+    /// Parses units such as `100px`, `50%`, `1s` (stretch), `auto`, `10vw`, or `1.5em`
+    ///
+    /// A number without a suffix is in pixels.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Units::Auto);
+        }
+
+        // `rem` is checked before `em`, since it shares the same suffix
+        let suffixes: [(&str, fn(f32) -> Units); 9] = [
+            ("px", Units::Pixels),
+            ("%", Units::Percentage),
+            ("vmin", Units::Vmin),
+            ("vw", Units::Vw),
+            ("vh", Units::Vh),
+            ("rem", Units::Rem),
+            ("em", Units::Em),
+            ("s", Units::Stretch),
+            ("", Units::Pixels),
+        ];
+        for (suffix, units) in suffixes {
+            if let Some(number) = s.strip_suffix(suffix) {
+                if suffix.is_empty() || parse_number(number).is_ok() {
+                    return parse_number(number)
+                        .map(units)
+                        .map_err(|_| StyleParseError::new(format!("invalid units `{}`", s)));
+                }
+            }
+        }
+        unreachable!("the empty suffix always matches")
+    }
+}
+
+impl FromStr for Edge<Units> {
+    type Err = StyleParseError;
+
+    /// Parses one to four units, in the same order as CSS (e.g. `5px 10px`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [top, right, bottom, left] = parse_shorthand(s, Units::from_str)?;
+        Ok(Edge::new(top, right, bottom, left))
+    }
+}
+
+impl FromStr for Edge<f32> {
+    type Err = StyleParseError;
+
+    /// Parses one to four pixel values, in the same order as CSS (e.g. `1px 2px`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [top, right, bottom, left] = parse_shorthand(s, parse_pixels)?;
+        Ok(Edge::new(top, right, bottom, left))
+    }
+}
+
+impl FromStr for Corner<f32> {
+    type Err = StyleParseError;
+
+    /// Parses one to four pixel values, in the same order as CSS (starting at the top left
+    /// corner and going clockwise)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [top_left, top_right, bottom_right, bottom_left] = parse_shorthand(s, parse_pixels)?;
+        Ok(Corner::new(top_left, top_right, bottom_left, bottom_right))
+    }
+}
+
+/// Parses a color from a string
+///
+/// Supports hex codes (`#rgb`, `#rgba`, `#rrggbb`, and `#rrggbbaa`), the `rgb(r, g, b)` and
+/// `rgba(r, g, b, a)` functions (with color channels from `0` to `255` and alpha from `0` to `1`),
+/// and a few basic names like `white` or `transparent`.
+///
+/// This is a function, rather than a [`FromStr`] implementation, since [`Color`] belongs to bevy.
+pub fn parse_color(s: &str) -> Result<Color, StyleParseError> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix('#') {
+        let invalid = || StyleParseError::new(format!("invalid hex color `{}`", s));
+        let digits = hex
+            .chars()
+            .map(|c| c.to_digit(16))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        let channels = match digits.len() {
+            3 | 4 => digits
+                .iter()
+                .map(|digit| (digit * 17) as f32 / 255.0)
+                .collect::<Vec<_>>(),
+            6 | 8 => digits
+                .chunks(2)
+                .map(|pair| (pair[0] * 16 + pair[1]) as f32 / 255.0)
+                .collect::<Vec<_>>(),
+            _ => return Err(invalid()),
+        };
+        let alpha = channels.get(3).copied().unwrap_or(1.0);
+        return Ok(Color::rgba(channels[0], channels[1], channels[2], alpha));
+    }
+
+    let lowercase = s.to_ascii_lowercase();
+    let arguments = lowercase
+        .strip_prefix("rgba(")
+        .or_else(|| lowercase.strip_prefix("rgb("))
+        .and_then(|arguments| arguments.strip_suffix(')'));
+    if let Some(arguments) = arguments {
+        let channels = arguments
+            .split(',')
+            .map(parse_number)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| StyleParseError::new(format!("invalid color `{}`", s)))?;
+        return match channels[..] {
+            [red, green, blue] => Ok(Color::rgb(red / 255.0, green / 255.0, blue / 255.0)),
+            [red, green, blue, alpha] => {
+                Ok(Color::rgba(red / 255.0, green / 255.0, blue / 255.0, alpha))
+            }
+            _ => Err(StyleParseError::new(format!(
+                "expected 3 or 4 color channels but found {}",
+                channels.len()
+            ))),
+        };
+    }
+
+    parse_keyword(
+        s,
+        &[
+            ("transparent", Color::NONE),
+            ("black", Color::BLACK),
+            ("white", Color::WHITE),
+            ("gray", Color::GRAY),
+            ("red", Color::RED),
+            ("orange", Color::ORANGE),
+            ("yellow", Color::YELLOW),
+            ("green", Color::GREEN),
+            ("cyan", Color::CYAN),
+            ("blue", Color::BLUE),
+            ("purple", Color::PURPLE),
+        ],
+    )
+    .map_err(|_| StyleParseError::new(format!("invalid color `{}`", s)))
+}
+
+/// Parses a property value, handling the keywords shared by every property
+fn parse_prop<T: Default + Clone + FromReflect>(
+    value: &str,
+    parse: impl FnOnce(&str) -> Result<T, StyleParseError>,
+) -> Result<StyleProp<T>, StyleParseError> {
+    Ok(match value {
+        "inherit" => StyleProp::Inherit,
+        "initial" => StyleProp::Default,
+        "unset" => StyleProp::Unset,
+        _ => StyleProp::Value(parse(value)?),
+    })
+}
+
+fn parse_number(s: &str) -> Result<f32, StyleParseError> {
+    let s = s.trim();
+    s.parse::<f32>()
+        .map_err(|_| StyleParseError::new(format!("expected a number but found `{}`", s)))
+}
+
+fn parse_integer<T: FromStr>(s: &str) -> Result<T, StyleParseError> {
+    s.trim()
+        .parse::<T>()
+        .map_err(|_| StyleParseError::new(format!("expected an integer but found `{}`", s)))
+}
+
+fn parse_pixels(s: &str) -> Result<f32, StyleParseError> {
+    let s = s.trim();
+    parse_number(s.strip_suffix("px").unwrap_or(s))
+}
+
+/// Parses a ratio like `1.5` or `16 / 9`
+fn parse_ratio(s: &str) -> Result<f32, StyleParseError> {
+    match s.split_once('/') {
+        Some((numerator, denominator)) => Ok(parse_number(numerator)? / parse_number(denominator)?),
+        None => parse_number(s),
+    }
+}
+
+fn parse_tracks(s: &str) -> Result<Vec<Units>, StyleParseError> {
+    words(s)
+        .into_iter()
+        .map(|(index, word)| {
+            Units::from_str(word).map_err(|error| error.offset_by(1, column(s, index)))
+        })
+        .collect()
+}
+
+fn parse_keyword<T: Copy>(s: &str, keywords: &[(&str, T)]) -> Result<T, StyleParseError> {
+    keywords
+        .iter()
+        .find(|(keyword, _)| keyword.eq_ignore_ascii_case(s.trim()))
+        .map(|(_, value)| *value)
+        .ok_or_else(|| {
+            let expected = keywords
+                .iter()
+                .map(|(keyword, _)| format!("`{}`", keyword))
+                .collect::<Vec<_>>()
+                .join(", ");
+            StyleParseError::new(format!("expected one of {} but found `{}`", expected, s))
+        })
+}
+
+/// Parses a CSS-like shorthand of one to four values into `[top, right, bottom, left]`
+fn parse_shorthand<T: Copy>(
+    s: &str,
+    parse: impl Fn(&str) -> Result<T, StyleParseError>,
+) -> Result<[T; 4], StyleParseError> {
+    let values = words(s)
+        .into_iter()
+        .map(|(index, word)| parse(word).map_err(|error| error.offset_by(1, column(s, index))))
+        .collect::<Result<Vec<_>, _>>()?;
+    match values[..] {
+        [all] => Ok([all; 4]),
+        [vertical, horizontal] => Ok([vertical, horizontal, vertical, horizontal]),
+        [top, horizontal, bottom] => Ok([top, horizontal, bottom, horizontal]),
+        [top, right, bottom, left] => Ok([top, right, bottom, left]),
+        _ => Err(StyleParseError::new(format!(
+            "expected 1 to 4 values but found {}",
+            values.len()
+        ))),
+    }
+}
+
+/// Splits a string on whitespace, keeping the byte index of each word
+fn words(s: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (index, c) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+        match (word_start, c.is_whitespace()) {
+            (None, false) => word_start = Some(index),
+            (Some(start), true) => {
+                words.push((start, &s[start..index]));
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+fn leading_whitespace(s: &str) -> usize {
+    s.len() - s.trim_start().len()
+}
+
+/// Returns the column of the given byte index, assuming it's on the first line
+fn column(s: &str, index: usize) -> usize {
+    s[..index].chars().count() + 1
+}
+
+/// Returns the line and column of the given byte index
+fn position(source: &str, index: usize) -> (usize, usize) {
+    let before = &source[..index];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    let line = before.matches('\n').count() + 1;
+    (
+        line,
+        column(&before[line_start..], before.len() - line_start),
+    )
+}
+
+fn error_at(source: &str, index: usize, message: impl Into<String>) -> StyleParseError {
+    let (line, column) = position(source, index);
+    StyleParseError::new(message).offset_by(line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_color, StyleParseError};
+    use crate::styles::{Corner, Edge, KStyle, StyleProp, Units};
+    use bevy::prelude::Color;
+
+    #[test]
+    fn should_parse_units() {
+        let parse = |s: &str| s.parse::<Units>().unwrap();
+        assert_eq!(parse("100px"), Units::Pixels(100.0));
+        assert_eq!(parse("50%"), Units::Percentage(50.0));
+        assert_eq!(parse("1s"), Units::Stretch(1.0));
+        assert_eq!(parse("2rem"), Units::Rem(2.0));
+        assert_eq!(parse("1.5em"), Units::Em(1.5));
+        assert_eq!(parse("auto"), Units::Auto);
+        assert!("wide".parse::<Units>().is_err());
+    }
+
+    #[test]
+    fn should_expand_shorthands() {
+        assert_eq!(
+            "5px 10px".parse::<Edge<Units>>().unwrap(),
+            Edge::new(
+                Units::Pixels(5.0),
+                Units::Pixels(10.0),
+                Units::Pixels(5.0),
+                Units::Pixels(10.0)
+            )
+        );
+        assert_eq!(
+            "1 2 3 4".parse::<Corner<f32>>().unwrap(),
+            Corner::new(1.0, 2.0, 4.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn should_parse_colors() {
+        assert_eq!(parse_color("#ff0000"), Ok(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(parse_color("#f00f"), Ok(Color::rgba(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(
+            parse_color("rgba(0, 0, 255, 0.5)"),
+            Ok(Color::rgba(0.0, 0.0, 1.0, 0.5))
+        );
+        assert_eq!(parse_color("transparent"), Ok(Color::NONE));
+    }
+
+    #[test]
+    fn should_parse_style() {
+        let style = KStyle::parse(
+            "width: 100px; background-color: #1e1e1e; border-radius: 4px; padding: 5px 10px",
+        )
+        .unwrap();
+        assert_eq!(style.width, StyleProp::Value(Units::Pixels(100.0)));
+        assert_eq!(style.border_radius, StyleProp::Value(Corner::all(4.0)));
+        assert_eq!(style.height, StyleProp::Unset);
+        assert_eq!(
+            KStyle::parse("font_size: inherit").unwrap().font_size,
+            StyleProp::Inherit
+        );
+    }
+
+    #[test]
+    fn errors_should_have_position() {
+        let error = KStyle::parse("width: 10px;\n  padding: 1px wide").unwrap_err();
+        assert_eq!(
+            error,
+            StyleParseError {
+                line: 2,
+                column: 16,
+                message: "invalid units `wide`".to_string(),
+            }
+        );
+        assert_eq!(KStyle::parse("\n  size: 1px").unwrap_err().column, 3);
+    }
+}
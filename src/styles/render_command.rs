@@ -1,9 +1,10 @@
 use bevy::prelude::{Handle, Image, Vec2};
+use bevy::reflect::{FromReflect, Reflect};
 use kayak_font::Alignment;
 
 use super::Edge;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
 pub enum RenderCommand {
     Empty,
     /// Represents a node that has no renderable object but contributes to the layout.
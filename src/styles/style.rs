@@ -2,20 +2,41 @@
 
 use std::ops::Add;
 
+use bevy::ecs::reflect::ReflectComponent;
 use bevy::prelude::Color;
 use bevy::prelude::Component;
+use bevy::prelude::Vec2;
+use bevy::reflect::{FromReflect, Reflect};
 use bevy::window::CursorIcon;
-pub use morphorm::{LayoutType, PositionType, Units};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::cursor::PointerEvents;
 
 use super::AsRefOption;
+use super::Background;
+use super::BorderStyle;
+use super::BoxShadow;
+use super::Breakpoint;
 pub use super::Corner;
+use super::Display;
 pub use super::Edge;
+use super::LayoutType;
+use super::MountAnimation;
+use super::Overlay;
+use super::PositionType;
+use super::RelativeSizes;
 use super::RenderCommand;
+use super::KTransform;
+use super::Transition;
+use super::Units;
+use super::Visibility;
 
 /// Just a wrapper around bevy's CursorIcon so we can define a default.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Since `CursorIcon` can't be reflected, this is reflected as an opaque value. It's serialized
+/// as the name of the icon (e.g. `"Hand"`).
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
+#[reflect_value(PartialEq, Serialize, Deserialize)]
 pub struct KCursorIcon(pub CursorIcon);
 
 impl Default for KCursorIcon {
@@ -24,11 +45,71 @@ impl Default for KCursorIcon {
     }
 }
 
+/// The serialized name of every [`CursorIcon`]
+const CURSOR_ICONS: [(&str, CursorIcon); 35] = [
+    ("Default", CursorIcon::Default),
+    ("Crosshair", CursorIcon::Crosshair),
+    ("Hand", CursorIcon::Hand),
+    ("Arrow", CursorIcon::Arrow),
+    ("Move", CursorIcon::Move),
+    ("Text", CursorIcon::Text),
+    ("Wait", CursorIcon::Wait),
+    ("Help", CursorIcon::Help),
+    ("Progress", CursorIcon::Progress),
+    ("NotAllowed", CursorIcon::NotAllowed),
+    ("ContextMenu", CursorIcon::ContextMenu),
+    ("Cell", CursorIcon::Cell),
+    ("VerticalText", CursorIcon::VerticalText),
+    ("Alias", CursorIcon::Alias),
+    ("Copy", CursorIcon::Copy),
+    ("NoDrop", CursorIcon::NoDrop),
+    ("Grab", CursorIcon::Grab),
+    ("Grabbing", CursorIcon::Grabbing),
+    ("AllScroll", CursorIcon::AllScroll),
+    ("ZoomIn", CursorIcon::ZoomIn),
+    ("ZoomOut", CursorIcon::ZoomOut),
+    ("EResize", CursorIcon::EResize),
+    ("NResize", CursorIcon::NResize),
+    ("NeResize", CursorIcon::NeResize),
+    ("NwResize", CursorIcon::NwResize),
+    ("SResize", CursorIcon::SResize),
+    ("SeResize", CursorIcon::SeResize),
+    ("SwResize", CursorIcon::SwResize),
+    ("WResize", CursorIcon::WResize),
+    ("EwResize", CursorIcon::EwResize),
+    ("NsResize", CursorIcon::NsResize),
+    ("NeswResize", CursorIcon::NeswResize),
+    ("NwseResize", CursorIcon::NwseResize),
+    ("ColResize", CursorIcon::ColResize),
+    ("RowResize", CursorIcon::RowResize),
+];
+
+impl Serialize for KCursorIcon {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, _) = CURSOR_ICONS
+            .iter()
+            .find(|(_, icon)| *icon == self.0)
+            .ok_or_else(|| ser::Error::custom(format!("unnamed cursor icon `{:?}`", self.0)))?;
+        serializer.serialize_str(name)
+    }
+}
+
+impl<'de> Deserialize<'de> for KCursorIcon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        CURSOR_ICONS
+            .iter()
+            .find(|(other, _)| *other == name)
+            .map(|(_, icon)| Self(*icon))
+            .ok_or_else(|| de::Error::custom(format!("unknown cursor icon `{}`", name)))
+    }
+}
+
 /// The base container of all style properties
 ///
 /// The default value for this enum is [`StyleProp::Unset`].
-#[derive(Debug, Clone, PartialEq)]
-pub enum StyleProp<T: Default + Clone> {
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect)]
+pub enum StyleProp<T: Default + Clone + FromReflect> {
     /// This prop is unset, meaning its actual value is not determined until style resolution,
     /// wherein it will be set to the property's default value.
     ///
@@ -46,7 +127,7 @@ pub enum StyleProp<T: Default + Clone> {
 
 impl<T> Default for StyleProp<T>
 where
-    T: Default + Clone,
+    T: Default + Clone + FromReflect,
 {
     fn default() -> Self {
         Self::Unset
@@ -55,7 +136,7 @@ where
 
 impl<T> StyleProp<T>
 where
-    T: Default + Clone,
+    T: Default + Clone + FromReflect,
 {
     /// Resolves this style property into a concrete value.
     ///
@@ -122,7 +203,7 @@ where
     }
 }
 
-impl<T: Default + Clone> From<T> for StyleProp<T> {
+impl<T: Default + Clone + FromReflect> From<T> for StyleProp<T> {
     fn from(value: T) -> Self {
         StyleProp::Value(value)
     }
@@ -239,24 +320,63 @@ define_styles! {
     ///   // Applied second (sets any remaining `StyleProp::Unset` fields)
     ///   .with_style(&style_b);
     /// ```
-    #[derive(Component, Debug, Default, Clone, PartialEq)]
+    #[derive(Component, Debug, Default, Clone, PartialEq, Reflect, FromReflect)]
+    #[reflect(Component)]
     pub struct KStyle {
+        /// The ratio of this widget's width to its height (e.g. `16.0 / 9.0`)
+        ///
+        /// If one dimension is [`Units::Auto`], it's derived from the other. Otherwise the widget
+        /// shrinks to the largest size with this ratio that fits the space given to it by layout.
+        /// The derived size still respects [`min_width`](Self::min_width),
+        /// [`max_width`](Self::max_width), [`min_height`](Self::min_height), and
+        /// [`max_height`](Self::max_height), which take precedence over the ratio.
+        pub aspect_ratio: StyleProp<f32>,
+        /// The fill of this widget, either a solid color or a gradient
+        ///
+        /// This property takes precedence over [`background_color`](Self::background_color) and
+        /// respects [`border_radius`](Self::border_radius).
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub background: StyleProp<Background>,
         /// The background color of this widget
         ///
         /// Only applies to widgets marked [`RenderCommand::Quad`]
         pub background_color : StyleProp<Color>,
         /// The color of the border around this widget
         ///
-        /// Currently, this controls all border sides.
+        /// This property has lower precedence than its more specific counterparts
+        /// ([`border_color_top`](Self::border_color_top), [`border_color_right`](Self::border_color_right),
+        /// [`border_color_bottom`](Self::border_color_bottom), and [`border_color_left`](Self::border_color_left)),
+        /// allowing it to be overridden.
         ///
         /// Only applies to widgets marked [`RenderCommand::Quad`]
         pub border_color: StyleProp<Color>,
+        /// The color of the bottom border of this widget
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_color_bottom: StyleProp<Color>,
+        /// The color of the left border of this widget
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_color_left: StyleProp<Color>,
+        /// The color of the right border of this widget
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_color_right: StyleProp<Color>,
+        /// The color of the top border of this widget
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_color_top: StyleProp<Color>,
         /// The radius of the corners (in pixels)
         ///
         /// The order is (Top, Right, Bottom, Left).
         ///
         /// Only applies to widgets marked [`RenderCommand::Quad`] and [`RenderCommand::Image`]
         pub border_radius: StyleProp<Corner<f32>>,
+        /// The line style of the border around this widget
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_style: StyleProp<BorderStyle>,
         /// The widths of the borders (in pixels)
         ///
         /// The order is (Top, Right, Bottom, Left).
@@ -265,6 +385,16 @@ define_styles! {
         pub border: StyleProp<Edge<f32>>,
         /// The distance between the bottom edge of this widget and the bottom edge of its containing widget
         pub bottom: StyleProp<Units>,
+        /// The shadows cast by (or into) this widget, drawn in order
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`] and [`RenderCommand::Image`]
+        pub box_shadow: StyleProp<Vec<BoxShadow>>,
+        /// Styles that apply while the viewport matches a query, such as a maximum width
+        ///
+        /// Matching breakpoints are applied over the rest of this style, with later breakpoints
+        /// taking precedence over earlier ones. Only widgets whose breakpoints change are updated
+        /// when the window is resized. Breakpoints nested within a breakpoint's style are ignored.
+        pub breakpoints: StyleProp<Vec<Breakpoint>>,
         /// The text color for this widget
         ///
         /// This property defaults to [`StyleProp::Inherit`] meaning that setting this field to some value will
@@ -274,8 +404,41 @@ define_styles! {
         pub color: StyleProp<Color>,
         /// The spacing between child widgets along the horizontal axis
         pub col_between: StyleProp<Units>,
+        /// The column this widget is placed in, starting from `0`
+        ///
+        /// Columns past the last one of the grid are clamped to it.
+        ///
+        /// Only applies to children of widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Grid`]
+        pub col_index: StyleProp<usize>,
+        /// The number of columns this widget spans
+        ///
+        /// Spans stop at the last column of the grid.
+        ///
+        /// Only applies to children of widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Grid`]
+        pub col_span: StyleProp<usize>,
         /// The cursor icon to display when hovering this widget
         pub cursor: StyleProp<KCursorIcon>,
+        /// Whether this widget takes part in layout
+        ///
+        /// A value of [`Display::None`] removes this widget and its descendants from layout, rendering,
+        /// and events (including focus), while keeping their state.
+        pub display: StyleProp<Display>,
+        /// The animation played when this widget is added to the tree
+        ///
+        /// This property is not inherited, though its effects apply to all descendants.
+        pub enter_animation: StyleProp<MountAnimation>,
+        /// The animation played when this widget is removed from the tree
+        ///
+        /// The widget (and its descendants) keep rendering until the animation completes, after
+        /// which they are despawned. They no longer take part in layout or receive events. If
+        /// the widget is added back with the same `key` before then, the animation is cancelled.
+        ///
+        /// Widgets without a `key` are matched with the previous render by position, so removing
+        /// one from the middle of a list plays the animation on the last one. Give list items a
+        /// `key` so the removed item is the one that exits.
+        ///
+        /// This property is not inherited, though its effects apply to all descendants.
+        pub exit_animation: StyleProp<MountAnimation>,
         /// The font name for this widget
         ///
         /// Only applies to [`RenderCommand::Text`]
@@ -284,7 +447,22 @@ define_styles! {
         ///
         /// Only applies to [`RenderCommand::Text`]
         pub font_size: StyleProp<f32>,
+        /// The widths of the columns of this widget
+        ///
+        /// A grid without columns has a single one that fills it.
+        ///
+        /// Only applies to widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Grid`]
+        pub grid_cols: StyleProp<Vec<Units>>,
+        /// The heights of the rows of this widget
+        ///
+        /// A grid without rows has a single one that fills it.
+        ///
+        /// Only applies to widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Grid`]
+        pub grid_rows: StyleProp<Vec<Units>>,
         /// The height of this widget
+        ///
+        /// [`Units::Auto`] sizes the widget to fit its content: text uses its measured height,
+        /// and other widgets use the extent of their children.
         pub height: StyleProp<Units>,
         /// The layout method for children of this widget
         pub layout_type: StyleProp<LayoutType>,
@@ -312,6 +490,21 @@ define_styles! {
         /// this acts like margin around the widget. For [`PositionType`](PositionType::SelfDirected) this
         /// acts as the actual position from the parent.
         pub offset: StyleProp<Edge<Units>>,
+        /// The opacity of this widget and all of its descendants, from `0.0` to `1.0`
+        ///
+        /// This is multiplied with the opacity of any ancestors and the alpha of every color
+        /// rendered in the subtree. The subtree isn't composited offscreen first, so overlapping
+        /// descendants are blended individually and show through each other while faded.
+        pub opacity: StyleProp<f32>,
+        /// Renders this widget and its descendants in the overlay layer
+        ///
+        /// Overlaid widgets are drawn and hit-tested above everything outside the overlay layer
+        /// (regardless of [`z_index`](Self::z_index)) and are not clipped by their ancestors. They
+        /// can optionally be positioned against another widget.
+        ///
+        /// Overlaid widgets still take up space in their parent, unless given a
+        /// [`position_type`](Self::position_type) of [`PositionType::SelfDirected`].
+        pub overlay: StyleProp<Overlay>,
         /// The inner padding between the edges of this widget and its children
         ///
         /// This property has lower precedence than its more specific counterparts
@@ -357,13 +550,113 @@ define_styles! {
         pub right: StyleProp<Units>,
         /// The spacing between child widgets along the vertical axis
         pub row_between: StyleProp<Units>,
+        /// The row this widget is placed in, starting from `0`
+        ///
+        /// Rows past the last one of the grid are clamped to it.
+        ///
+        /// Only applies to children of widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Grid`]
+        pub row_index: StyleProp<usize>,
+        /// The number of rows this widget spans
+        ///
+        /// Spans stop at the last row of the grid.
+        ///
+        /// Only applies to children of widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Grid`]
+        pub row_span: StyleProp<usize>,
         /// The distance between the top edge of this widget and the top edge of its containing widget
         pub top: StyleProp<Units>,
+        /// A 2D transform (translation, scale, and rotation) applied to this widget and its descendants
+        ///
+        /// Transforms compose down the tree and affect rendering and hit-testing, but not layout.
+        /// Clipping is applied to the bounding box of a transformed clip.
+        ///
+        /// This property is not inherited, though its effects apply to all descendants.
+        pub transform: StyleProp<KTransform>,
+        /// How changes to this widget's styles are animated
+        ///
+        /// When a transitioned property's resolved value changes, the widget animates from its
+        /// current value to the new one over the spec's duration. Only [`Color`], [`Units`]
+        /// (of the same kind), [`Corner<f32>`], [`Edge<f32>`], and [`KTransform`] values are interpolated.
+        ///
+        /// This property is not inherited.
+        pub transition: StyleProp<Transition>,
+        /// Whether this widget is drawn
+        ///
+        /// A value of [`Visibility::Hidden`] keeps this widget's space in the layout, but skips
+        /// rendering it and sending it events (including focus).
+        ///
+        /// This property defaults to [`StyleProp::Inherit`] meaning that setting this field to some value will
+        /// cause all descendents to receive that value, up to the next set value.
+        pub visibility: StyleProp<Visibility>,
         /// The width of this widget
+        ///
+        /// [`Units::Auto`] sizes the widget to fit its content: text uses its measured width,
+        /// and other widgets use the extent of their children.
         pub width: StyleProp<Units>,
+        /// If true, children that don't fit in the width of this widget wrap onto new rows
+        ///
+        /// Children are placed left-to-right, separated by their own spacing or by
+        /// [`col_between`](Self::col_between), with rows separated by
+        /// [`row_between`](Self::row_between). Stretched children wrap once their minimum width
+        /// no longer fits, and share the space left on their row. A widget with a height of
+        /// [`Units::Auto`] grows to fit its rows.
+        ///
+        /// Only applies to widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Row`]
+        pub wrap: StyleProp<bool>,
+        /// The stacking order of this widget and its descendants
+        ///
+        /// Widgets with a z-index are drawn and hit-tested above those with a lower one, or none at
+        /// all (which is the same as `0`). Widgets with the same z-index keep their tree order.
+        /// Unlike [`overlay`](Self::overlay), this doesn't escape the clipping of ancestors.
+        ///
+        /// This property is not inherited, though its effects apply to all descendants.
+        pub z_index: StyleProp<i32>,
     }
 }
 
+/// Calls `$f` on every [`Units`] value set on the given style, borrowing each one with
+/// `$borrow` (`&` or `&mut`), so that both versions share the list of fields
+macro_rules! for_each_units {
+    ($style: ident, $f: ident, $($borrow: tt)+) => {
+        for prop in [
+            $($borrow)+ $style.bottom,
+            $($borrow)+ $style.col_between,
+            $($borrow)+ $style.height,
+            $($borrow)+ $style.left,
+            $($borrow)+ $style.max_height,
+            $($borrow)+ $style.max_width,
+            $($borrow)+ $style.min_height,
+            $($borrow)+ $style.min_width,
+            $($borrow)+ $style.padding_bottom,
+            $($borrow)+ $style.padding_left,
+            $($borrow)+ $style.padding_right,
+            $($borrow)+ $style.padding_top,
+            $($borrow)+ $style.right,
+            $($borrow)+ $style.row_between,
+            $($borrow)+ $style.top,
+            $($borrow)+ $style.width,
+        ] {
+            if let StyleProp::Value(units) = prop {
+                $f(units);
+            }
+        }
+        for prop in [$($borrow)+ $style.offset, $($borrow)+ $style.padding] {
+            if let StyleProp::Value(edge) = prop {
+                $f($($borrow)+ edge.top);
+                $f($($borrow)+ edge.right);
+                $f($($borrow)+ edge.bottom);
+                $f($($borrow)+ edge.left);
+            }
+        }
+        for prop in [$($borrow)+ $style.grid_cols, $($borrow)+ $style.grid_rows] {
+            if let StyleProp::Value(tracks) = prop {
+                for units in tracks {
+                    $f(units);
+                }
+            }
+        }
+    };
+}
+
 impl KStyle {
     /// Returns a `Style` object where all fields are set to their own initial values
     ///
@@ -371,16 +664,32 @@ impl KStyle {
     /// resolving the style.
     pub fn initial() -> Self {
         Self {
+            aspect_ratio: StyleProp::Default,
+            background: StyleProp::Default,
             background_color: StyleProp::Default,
             border: StyleProp::Default,
             border_color: StyleProp::Default,
+            border_color_bottom: StyleProp::Default,
+            border_color_left: StyleProp::Default,
+            border_color_right: StyleProp::Default,
+            border_color_top: StyleProp::Default,
             border_radius: StyleProp::Default,
+            border_style: StyleProp::Default,
             bottom: StyleProp::Default,
+            box_shadow: StyleProp::Default,
+            breakpoints: StyleProp::Default,
             color: StyleProp::Inherit,
             cursor: StyleProp::Inherit,
             col_between: StyleProp::Default,
+            col_index: StyleProp::Default,
+            col_span: StyleProp::Default,
+            display: StyleProp::Default,
+            enter_animation: StyleProp::Default,
+            exit_animation: StyleProp::Default,
             font: StyleProp::Inherit,
             font_size: StyleProp::Inherit,
+            grid_cols: StyleProp::Default,
+            grid_rows: StyleProp::Default,
             height: StyleProp::Default,
             layout_type: StyleProp::Default,
             line_height: StyleProp::Inherit,
@@ -390,6 +699,8 @@ impl KStyle {
             min_height: StyleProp::Default,
             min_width: StyleProp::Default,
             offset: StyleProp::Default,
+            opacity: StyleProp::Default,
+            overlay: StyleProp::Default,
             padding: StyleProp::Default,
             padding_bottom: StyleProp::Default,
             padding_left: StyleProp::Default,
@@ -400,10 +711,84 @@ impl KStyle {
             render_command: StyleProp::Value(RenderCommand::Empty),
             right: StyleProp::Default,
             row_between: StyleProp::Default,
+            row_index: StyleProp::Default,
+            row_span: StyleProp::Default,
             top: StyleProp::Default,
+            transform: StyleProp::Default,
+            transition: StyleProp::Default,
+            visibility: StyleProp::Inherit,
             width: StyleProp::Default,
+            wrap: StyleProp::Default,
+            z_index: StyleProp::Default,
         }
     }
+
+    /// Converts any layout property relative to the viewport or font size into pixels
+    pub(crate) fn resolve_relative_units(&mut self, sizes: &RelativeSizes) {
+        self.for_each_units(|units| *units = units.resolve_relative(sizes));
+    }
+
+    /// Returns this style with the breakpoints that match the given viewport applied over it
+    pub(crate) fn with_breakpoints(&self, viewport: Vec2) -> KStyle {
+        let breakpoints = match &self.breakpoints {
+            StyleProp::Value(breakpoints) => breakpoints,
+            _ => return self.clone(),
+        };
+
+        let mut style = KStyle::default();
+        for breakpoint in breakpoints.iter().rev() {
+            if breakpoint.matches(viewport) {
+                style.apply(&breakpoint.style);
+            }
+        }
+        style.apply(self);
+        style
+    }
+
+    /// Returns true if resizing the viewport from `old` to `new` changes how this style resolves
+    pub(crate) fn depends_on_viewport(&self, old: Vec2, new: Vec2) -> bool {
+        if self.uses_viewport_units() {
+            return true;
+        }
+        match &self.breakpoints {
+            StyleProp::Value(breakpoints) => breakpoints.iter().any(|breakpoint| {
+                breakpoint.matches(old) != breakpoint.matches(new)
+                    || breakpoint.style.uses_viewport_units()
+            }),
+            _ => false,
+        }
+    }
+
+    /// Returns true if any layout property is relative to the size of the viewport
+    pub(crate) fn uses_viewport_units(&self) -> bool {
+        let mut uses_viewport_units = false;
+        self.for_each_units_ref(|units| uses_viewport_units |= units.is_viewport_relative());
+        uses_viewport_units
+    }
+
+    /// Returns true if any layout property, including those of breakpoints, is relative to the
+    /// root widget's font size
+    pub(crate) fn uses_root_font_size(&self) -> bool {
+        let mut uses_root_font_size = false;
+        self.for_each_units_ref(|units| uses_root_font_size |= units.is_root_font_relative());
+        uses_root_font_size
+            || match &self.breakpoints {
+                StyleProp::Value(breakpoints) => breakpoints
+                    .iter()
+                    .any(|breakpoint| breakpoint.style.uses_root_font_size()),
+                _ => false,
+            }
+    }
+
+    /// Calls `f` on every [`Units`] value set on this style
+    fn for_each_units(&mut self, mut f: impl FnMut(&mut Units)) {
+        for_each_units!(self, f, &mut);
+    }
+
+    /// Calls `f` on every [`Units`] value set on this style, without modifying them
+    fn for_each_units_ref(&self, mut f: impl FnMut(&Units)) {
+        for_each_units!(self, f, &);
+    }
 }
 
 impl Add for KStyle {
@@ -420,7 +805,13 @@ impl Add for KStyle {
 
 #[cfg(test)]
 mod tests {
-    use super::{Edge, KStyle, StyleProp, Units};
+    use bevy::prelude::{App, AppTypeRegistry};
+    use bevy::reflect::serde::{ReflectSerializer, UntypedReflectDeserializer};
+    use bevy::reflect::FromReflect;
+    use bevy::window::CursorIcon;
+    use serde::de::DeserializeSeed;
+
+    use super::{Edge, KCursorIcon, KStyle, StyleProp, Units, CURSOR_ICONS};
 
     #[test]
     fn styles_should_equal() {
@@ -606,4 +997,39 @@ mod tests {
         assert_eq!(expected, property.resolve_or_else(|| expected));
         assert_eq!(f32::default(), property.resolve_or_default());
     }
+
+    #[test]
+    fn style_with_cursor_should_round_trip_through_reflection() {
+        let mut app = App::new();
+        crate::styles::register_types(&mut app);
+        let registry = app.world.resource::<AppTypeRegistry>().read();
+
+        let expected = KStyle {
+            cursor: StyleProp::Value(KCursorIcon(CursorIcon::Hand)),
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            ..Default::default()
+        };
+
+        let serializer = ReflectSerializer::new(&expected, &registry);
+        let serialized = ron::to_string(&serializer).unwrap();
+        assert!(serialized.contains("\"Hand\""));
+
+        let mut deserializer = ron::Deserializer::from_str(&serialized).unwrap();
+        let reflected = UntypedReflectDeserializer::new(&registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let style = KStyle::from_reflect(reflected.as_ref()).unwrap();
+
+        assert_eq!(expected, style);
+    }
+
+    #[test]
+    fn cursor_icons_should_round_trip_through_their_names() {
+        for (name, icon) in CURSOR_ICONS {
+            let serialized = ron::to_string(&KCursorIcon(icon)).unwrap();
+            assert_eq!(serialized, format!("\"{}\"", name));
+            let deserialized: KCursorIcon = ron::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, KCursorIcon(icon));
+        }
+    }
 }
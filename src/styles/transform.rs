@@ -0,0 +1,157 @@
+use bevy::math::{Affine2, Vec2};
+use bevy::reflect::{FromReflect, Reflect};
+
+use super::Lerp;
+use crate::layout::Rect;
+
+/// A 2D transform applied to a widget after layout, used by the [`transform`](crate::styles::KStyle::transform) style
+///
+/// Transforms only affect rendering and hit-testing. The widget (and its siblings) are laid out
+/// as if it wasn't transformed.
+///
+/// # Example
+///
+/// ```
+/// # use kayak_ui::prelude::KTransform;
+/// // Grow slightly, around the widget's center
+/// let pop = KTransform::from_scale(1.05);
+/// // Rotate a quarter turn clockwise around the top left corner
+/// let rotated = KTransform::from_rotation(90.0).with_origin(0.0, 0.0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct KTransform {
+    /// The offset of the widget, in pixels
+    pub translation: Vec2,
+    /// The scale of the widget along each axis
+    pub scale: Vec2,
+    /// The rotation of the widget, in degrees (clockwise)
+    pub rotation: f32,
+    /// The point the widget is scaled and rotated around, relative to its size
+    ///
+    /// `(0.0, 0.0)` is the top left corner and `(0.5, 0.5)` is the center.
+    pub origin: Vec2,
+}
+
+impl Default for KTransform {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            scale: Vec2::ONE,
+            rotation: 0.0,
+            origin: Vec2::splat(0.5),
+        }
+    }
+}
+
+impl KTransform {
+    /// Creates a transform that offsets the widget by the given amount, in pixels
+    pub fn from_translation(x: f32, y: f32) -> Self {
+        Self::default().with_translation(x, y)
+    }
+
+    /// Creates a transform that uniformly scales the widget by the given factor
+    pub fn from_scale(scale: f32) -> Self {
+        Self::default().with_scale(scale, scale)
+    }
+
+    /// Creates a transform that rotates the widget clockwise by the given angle, in degrees
+    pub fn from_rotation(degrees: f32) -> Self {
+        Self::default().with_rotation(degrees)
+    }
+
+    /// Sets the offset of the widget, in pixels
+    pub fn with_translation(mut self, x: f32, y: f32) -> Self {
+        self.translation = Vec2::new(x, y);
+        self
+    }
+
+    /// Sets the scale of the widget along each axis
+    pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vec2::new(x, y);
+        self
+    }
+
+    /// Sets the clockwise rotation of the widget, in degrees
+    pub fn with_rotation(mut self, degrees: f32) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /// Sets the point the widget is scaled and rotated around, relative to its size
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Vec2::new(x, y);
+        self
+    }
+
+    /// Returns the matrix that maps points of a widget with the given layout to their transformed position
+    pub fn to_affine(&self, layout: &Rect) -> Affine2 {
+        let origin = Vec2::new(
+            layout.posx + layout.width * self.origin.x,
+            layout.posy + layout.height * self.origin.y,
+        );
+        // The y axis points down, so a positive angle turns clockwise on screen
+        Affine2::from_translation(origin + self.translation)
+            * Affine2::from_angle(self.rotation.to_radians())
+            * Affine2::from_scale(self.scale)
+            * Affine2::from_translation(-origin)
+    }
+}
+
+impl Lerp for KTransform {
+    /// Interpolates the translation, scale, and rotation
+    ///
+    /// The origin snaps to `other`.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            scale: self.scale.lerp(other.scale, t),
+            rotation: self.rotation.lerp(&other.rotation, t),
+            origin: other.origin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KTransform;
+    use crate::layout::Rect;
+    use bevy::math::Vec2;
+
+    fn layout() -> Rect {
+        Rect {
+            posx: 10.0,
+            posy: 20.0,
+            width: 100.0,
+            height: 50.0,
+            z_index: 0.0,
+        }
+    }
+
+    #[test]
+    fn default_should_be_identity() {
+        let affine = KTransform::default().to_affine(&layout());
+        let point = Vec2::new(35.0, 45.0);
+        assert!(affine.transform_point2(point).abs_diff_eq(point, 1e-4));
+    }
+
+    #[test]
+    fn scale_should_keep_origin_in_place() {
+        let affine = KTransform::from_scale(2.0).to_affine(&layout());
+        let center = Vec2::new(60.0, 45.0);
+        assert!(affine.transform_point2(center).abs_diff_eq(center, 1e-4));
+        assert!(affine
+            .transform_point2(Vec2::new(10.0, 20.0))
+            .abs_diff_eq(Vec2::new(-40.0, -5.0), 1e-4));
+    }
+
+    #[test]
+    fn rotation_should_turn_clockwise() {
+        let affine = KTransform::from_rotation(90.0)
+            .with_origin(0.0, 0.0)
+            .to_affine(&layout());
+        // A point to the right of the origin ends up below it (y points down)
+        assert!(affine
+            .transform_point2(Vec2::new(20.0, 20.0))
+            .abs_diff_eq(Vec2::new(10.0, 30.0), 1e-4));
+    }
+}
@@ -0,0 +1,310 @@
+use bevy::prelude::Color;
+use bevy::reflect::{FromReflect, Reflect};
+
+use super::{Corner, Edge, Units};
+
+/// An easing curve used to interpolate a transition over time
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Starts slow and speeds up (cubic)
+    EaseIn,
+    /// Starts fast and slows down (cubic)
+    EaseOut,
+    /// Starts and ends slow (cubic)
+    EaseInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Maps the linear progress `t` (clamped to `[0, 1]`) onto this easing curve
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// The style properties that can be transitioned
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect, FromReflect)]
+pub enum TransitionProperty {
+    /// Matches every transitionable property that doesn't have its own spec
+    All,
+    BackgroundColor,
+    BorderColor,
+    Color,
+    BorderRadius,
+    Border,
+    Opacity,
+    Transform,
+    Width,
+    Height,
+    MinWidth,
+    MinHeight,
+    MaxWidth,
+    MaxHeight,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl TransitionProperty {
+    /// Every concrete (non-[`All`](Self::All)) transitionable property
+    pub const PROPERTIES: [TransitionProperty; 17] = [
+        TransitionProperty::BackgroundColor,
+        TransitionProperty::BorderColor,
+        TransitionProperty::Color,
+        TransitionProperty::BorderRadius,
+        TransitionProperty::Border,
+        TransitionProperty::Opacity,
+        TransitionProperty::Transform,
+        TransitionProperty::Width,
+        TransitionProperty::Height,
+        TransitionProperty::MinWidth,
+        TransitionProperty::MinHeight,
+        TransitionProperty::MaxWidth,
+        TransitionProperty::MaxHeight,
+        TransitionProperty::Left,
+        TransitionProperty::Right,
+        TransitionProperty::Top,
+        TransitionProperty::Bottom,
+    ];
+}
+
+/// Describes how a single property transitions between values
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct TransitionSpec {
+    /// The property this spec applies to
+    pub property: TransitionProperty,
+    /// The length of the transition, in seconds
+    pub duration: f32,
+    /// The easing curve of the transition
+    pub easing: Easing,
+}
+
+/// A set of [`TransitionSpec`]s, used by the [`transition`](crate::styles::KStyle::transition) style
+///
+/// # Example
+///
+/// ```
+/// # use kayak_ui::prelude::{Easing, Transition, TransitionProperty};
+/// let transition = Transition::new()
+///     .with(TransitionProperty::BackgroundColor, 0.2, Easing::EaseOut)
+///     .with(TransitionProperty::Width, 0.35, Easing::EaseInOut);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Reflect, FromReflect)]
+pub struct Transition {
+    specs: Vec<TransitionSpec>,
+}
+
+impl Transition {
+    /// Creates an empty transition set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds (or replaces) the spec for the given property
+    ///
+    /// # Arguments
+    ///
+    /// * `property`: The property to transition
+    /// * `duration`: The length of the transition, in seconds
+    /// * `easing`: The easing curve of the transition
+    ///
+    pub fn with(mut self, property: TransitionProperty, duration: f32, easing: Easing) -> Self {
+        self.specs.retain(|spec| spec.property != property);
+        self.specs.push(TransitionSpec {
+            property,
+            duration,
+            easing,
+        });
+        self
+    }
+
+    /// Gets the spec for the given property
+    ///
+    /// A spec specific to the property takes precedence over [`TransitionProperty::All`].
+    pub fn get(&self, property: TransitionProperty) -> Option<TransitionSpec> {
+        self.specs
+            .iter()
+            .find(|spec| spec.property == property)
+            .or_else(|| {
+                self.specs
+                    .iter()
+                    .find(|spec| spec.property == TransitionProperty::All)
+            })
+            .map(|spec| TransitionSpec { property, ..*spec })
+    }
+
+    /// Returns true if no specs have been added
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+}
+
+/// Linear interpolation between two values
+pub trait Lerp {
+    /// Interpolates between `self` (at `t = 0`) and `other` (at `t = 1`)
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let [r1, g1, b1, a1] = self.as_rgba_f32();
+        let [r2, g2, b2, a2] = other.as_rgba_f32();
+        Color::rgba(
+            r1.lerp(&r2, t),
+            g1.lerp(&g2, t),
+            b1.lerp(&b2, t),
+            a1.lerp(&a2, t),
+        )
+    }
+}
+
+impl Lerp for Units {
+    /// Interpolates between units of the same kind
+    ///
+    /// Units of different kinds (or [`Units::Auto`]) can't be interpolated and snap to `other`.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        match (self, other) {
+            (Units::Pixels(a), Units::Pixels(b)) => Units::Pixels(a.lerp(b, t)),
+            (Units::Percentage(a), Units::Percentage(b)) => Units::Percentage(a.lerp(b, t)),
+            (Units::Stretch(a), Units::Stretch(b)) => Units::Stretch(a.lerp(b, t)),
+            (Units::Vw(a), Units::Vw(b)) => Units::Vw(a.lerp(b, t)),
+            (Units::Vh(a), Units::Vh(b)) => Units::Vh(a.lerp(b, t)),
+            (Units::Vmin(a), Units::Vmin(b)) => Units::Vmin(a.lerp(b, t)),
+            (Units::Em(a), Units::Em(b)) => Units::Em(a.lerp(b, t)),
+            (Units::Rem(a), Units::Rem(b)) => Units::Rem(a.lerp(b, t)),
+            _ => *other,
+        }
+    }
+}
+
+impl<T> Lerp for Corner<T>
+where
+    T: Lerp + Copy + Default + PartialEq + Reflect,
+{
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Corner::new(
+            self.top_left.lerp(&other.top_left, t),
+            self.top_right.lerp(&other.top_right, t),
+            self.bottom_left.lerp(&other.bottom_left, t),
+            self.bottom_right.lerp(&other.bottom_right, t),
+        )
+    }
+}
+
+impl<T> Lerp for Edge<T>
+where
+    T: Lerp + Copy + Default + PartialEq + Reflect,
+{
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Edge::new(
+            self.top.lerp(&other.top, t),
+            self.right.lerp(&other.right, t),
+            self.bottom.lerp(&other.bottom, t),
+            self.left.lerp(&other.left, t),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Corner, Easing, Edge, Lerp, Transition, TransitionProperty, Units};
+    use bevy::prelude::Color;
+
+    #[test]
+    fn easing_should_start_at_zero_and_end_at_one() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(0.0, easing.ease(0.0));
+            assert_eq!(1.0, easing.ease(1.0));
+            assert_eq!(1.0, easing.ease(2.0));
+        }
+    }
+
+    #[test]
+    fn units_should_lerp_same_kind() {
+        let a = Units::Pixels(10.0);
+        let b = Units::Pixels(20.0);
+        assert_eq!(Units::Pixels(15.0), a.lerp(&b, 0.5));
+    }
+
+    #[test]
+    fn units_should_snap_different_kind() {
+        let a = Units::Pixels(10.0);
+        let b = Units::Percentage(20.0);
+        assert_eq!(b, a.lerp(&b, 0.25));
+    }
+
+    #[test]
+    fn color_should_lerp() {
+        let a = Color::rgba(0.0, 0.0, 0.0, 0.0);
+        let b = Color::rgba(1.0, 0.5, 0.25, 1.0);
+        assert_eq!(Color::rgba(0.5, 0.25, 0.125, 0.5), a.lerp(&b, 0.5));
+    }
+
+    #[test]
+    fn edge_and_corner_should_lerp() {
+        let edge = Edge::all(0.0).lerp(&Edge::new(2.0, 4.0, 6.0, 8.0), 0.5);
+        assert_eq!(Edge::new(1.0, 2.0, 3.0, 4.0), edge);
+
+        let corner = Corner::all(0.0).lerp(&Corner::new(2.0, 4.0, 6.0, 8.0), 0.5);
+        assert_eq!(Corner::new(1.0, 2.0, 3.0, 4.0), corner);
+    }
+
+    #[test]
+    fn transition_should_prefer_specific_spec() {
+        let transition = Transition::new()
+            .with(TransitionProperty::All, 1.0, Easing::Linear)
+            .with(TransitionProperty::Width, 0.5, Easing::EaseIn);
+
+        let width = transition.get(TransitionProperty::Width).unwrap();
+        assert_eq!(0.5, width.duration);
+        assert_eq!(Easing::EaseIn, width.easing);
+
+        let height = transition.get(TransitionProperty::Height).unwrap();
+        assert_eq!(1.0, height.duration);
+        assert_eq!(TransitionProperty::Height, height.property);
+    }
+
+    #[test]
+    fn transition_should_replace_spec() {
+        let transition = Transition::new()
+            .with(TransitionProperty::Width, 1.0, Easing::Linear)
+            .with(TransitionProperty::Width, 0.5, Easing::EaseIn);
+
+        assert_eq!(
+            0.5,
+            transition.get(TransitionProperty::Width).unwrap().duration
+        );
+        assert_eq!(None, transition.get(TransitionProperty::Height));
+    }
+}
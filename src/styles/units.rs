@@ -0,0 +1,175 @@
+use bevy::prelude::Vec2;
+use bevy::reflect::{FromReflect, Reflect};
+
+/// A length used by the layout styles of a widget, such as [`width`](crate::styles::KStyle::width)
+///
+/// Along with the units supported by layout, this includes units relative to the viewport and to
+/// the font size. These are converted to [`Units::Pixels`] when a widget's styles are resolved, so
+/// they never reach layout.
+///
+/// # Example
+///
+/// ```
+/// # use kayak_ui::prelude::{KStyle, Units};
+/// let panel = KStyle {
+///     // A quarter of the window's width
+///     width: Units::Vw(25.0).into(),
+///     // Room for a couple lines of text
+///     height: Units::Em(3.0).into(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub enum Units {
+    /// A number of pixels
+    Pixels(f32),
+    /// A percentage of the parent's size
+    Percentage(f32),
+    /// A factor of the remaining free space
+    Stretch(f32),
+    /// Automatically determined by the layout, such as fitting the content of a widget
+    Auto,
+    /// A percentage of the viewport's width
+    Vw(f32),
+    /// A percentage of the viewport's height
+    Vh(f32),
+    /// A percentage of the viewport's smaller dimension
+    Vmin(f32),
+    /// A multiple of the widget's resolved [`font_size`](crate::styles::KStyle::font_size)
+    Em(f32),
+    /// A multiple of the root widget's resolved [`font_size`](crate::styles::KStyle::font_size)
+    Rem(f32),
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Units::Auto
+    }
+}
+
+impl Units {
+    /// Returns the size in pixels, given the size of the parent and the size to use for
+    /// [`Units::Auto`] and [`Units::Stretch`]
+    ///
+    /// Units relative to the viewport or font size are expected to be resolved beforehand, and
+    /// are treated like [`Units::Auto`].
+    pub fn value_or(&self, parent_value: f32, auto_value: f32) -> f32 {
+        match self {
+            Units::Pixels(pixels) => *pixels,
+            Units::Percentage(percentage) => (percentage / 100.0) * parent_value,
+            _ => auto_value,
+        }
+    }
+
+    /// Returns true if this is relative to the size of the viewport
+    pub fn is_viewport_relative(&self) -> bool {
+        matches!(self, Units::Vw(_) | Units::Vh(_) | Units::Vmin(_))
+    }
+
+    /// Returns true if this is relative to the root widget's font size
+    pub fn is_root_font_relative(&self) -> bool {
+        matches!(self, Units::Rem(_))
+    }
+
+    /// Converts units relative to the viewport or font size into [`Units::Pixels`]
+    ///
+    /// All other units are returned as-is.
+    pub fn resolve_relative(&self, sizes: &RelativeSizes) -> Self {
+        match self {
+            Units::Vw(percentage) => Units::Pixels(percentage / 100.0 * sizes.viewport.x),
+            Units::Vh(percentage) => Units::Pixels(percentage / 100.0 * sizes.viewport.y),
+            Units::Vmin(percentage) => {
+                Units::Pixels(percentage / 100.0 * sizes.viewport.x.min(sizes.viewport.y))
+            }
+            Units::Em(factor) => Units::Pixels(factor * sizes.font_size),
+            Units::Rem(factor) => Units::Pixels(factor * sizes.root_font_size),
+            units => *units,
+        }
+    }
+}
+
+impl From<Units> for morphorm::Units {
+    fn from(units: Units) -> Self {
+        match units {
+            Units::Pixels(pixels) => morphorm::Units::Pixels(pixels),
+            Units::Percentage(percentage) => morphorm::Units::Percentage(percentage),
+            Units::Stretch(factor) => morphorm::Units::Stretch(factor),
+            // Relative units are resolved along with the rest of the styles, before layout
+            _ => morphorm::Units::Auto,
+        }
+    }
+}
+
+/// Allows units written for layout to be used in styles
+impl From<morphorm::Units> for Units {
+    fn from(units: morphorm::Units) -> Self {
+        match units {
+            morphorm::Units::Pixels(pixels) => Units::Pixels(pixels),
+            morphorm::Units::Percentage(percentage) => Units::Percentage(percentage),
+            morphorm::Units::Stretch(factor) => Units::Stretch(factor),
+            morphorm::Units::Auto => Units::Auto,
+        }
+    }
+}
+
+/// The sizes that relative [`Units`] are resolved against
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct RelativeSizes {
+    /// The size of the viewport, in pixels
+    pub viewport: Vec2,
+    /// The resolved font size of the widget
+    pub font_size: f32,
+    /// The resolved font size of the root widget
+    pub root_font_size: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RelativeSizes, Units};
+    use bevy::prelude::Vec2;
+
+    fn sizes() -> RelativeSizes {
+        RelativeSizes {
+            viewport: Vec2::new(1280.0, 720.0),
+            font_size: 20.0,
+            root_font_size: 16.0,
+        }
+    }
+
+    #[test]
+    fn viewport_units_should_resolve_to_pixels() {
+        assert_eq!(
+            Units::Vw(50.0).resolve_relative(&sizes()),
+            Units::Pixels(640.0)
+        );
+        assert_eq!(
+            Units::Vh(10.0).resolve_relative(&sizes()),
+            Units::Pixels(72.0)
+        );
+        assert_eq!(
+            Units::Vmin(100.0).resolve_relative(&sizes()),
+            Units::Pixels(720.0)
+        );
+    }
+
+    #[test]
+    fn font_units_should_resolve_to_pixels() {
+        assert_eq!(
+            Units::Em(1.5).resolve_relative(&sizes()),
+            Units::Pixels(30.0)
+        );
+        assert_eq!(
+            Units::Rem(2.0).resolve_relative(&sizes()),
+            Units::Pixels(32.0)
+        );
+    }
+
+    #[test]
+    fn layout_units_should_be_unchanged() {
+        assert_eq!(
+            Units::Stretch(1.0).resolve_relative(&sizes()),
+            Units::Stretch(1.0)
+        );
+        assert_eq!(Units::Auto.resolve_relative(&sizes()), Units::Auto);
+    }
+}
@@ -0,0 +1,333 @@
+use bevy::prelude::{
+    Changed, Color, Component, Entity, FromReflect, Query, Reflect, ReflectComponent, Res, Resource,
+};
+
+use crate::{styles::KStyle, widget_context::WidgetContext};
+
+/// The set of colors, sizes, and spacings used by the built-in widgets.
+///
+/// A theme can be provided in two ways:
+///
+/// 1. Globally, by inserting (or mutating) the `KayakTheme` resource. Swapping the resource at
+///    runtime restyles every built-in widget that isn't covered by a provider.
+/// 2. Per-subtree, by wrapping widgets in a [`KayakThemeProviderBundle`](crate::widgets::KayakThemeProviderBundle).
+///    The closest provider up the tree wins.
+#[derive(Component, Resource, Debug, Clone, PartialEq, Reflect, FromReflect)]
+#[reflect(Component)]
+pub struct KayakTheme {
+    /// A human-readable name for this theme
+    pub name: String,
+    pub palette: ThemePalette,
+    pub font_sizes: ThemeFontSizes,
+    pub radii: ThemeRadii,
+    pub spacing: ThemeSpacing,
+}
+
+/// The colors used by a [`KayakTheme`]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ThemePalette {
+    /// The color of window bodies
+    pub background: Color,
+    /// The color of raised surfaces such as buttons and title bars
+    pub surface: Color,
+    /// The color of window borders
+    pub border: Color,
+    /// The color of text input fields
+    pub input: Color,
+    /// The default text color
+    pub text: Color,
+    /// The default color of a scrollbar thumb
+    pub scrollbar_thumb: Color,
+    /// The default color of a scrollbar track
+    pub scrollbar_track: Color,
+}
+
+/// The font sizes (and matching line heights) used by a [`KayakTheme`], in pixels
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ThemeFontSizes {
+    /// The font size of window titles
+    pub title: f32,
+    /// The line height of window titles
+    pub title_line_height: f32,
+    /// The font size of regular text, such as text box input
+    pub body: f32,
+    /// The line height of regular text
+    pub body_line_height: f32,
+}
+
+/// The border radii used by a [`KayakTheme`], in pixels
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ThemeRadii {
+    pub button: f32,
+    pub window: f32,
+    pub text_box: f32,
+}
+
+/// The sizes and spacings used by a [`KayakTheme`], in pixels
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+pub struct ThemeSpacing {
+    /// The padding above and below a button's content, which sets the button's height
+    pub button_padding: f32,
+    /// The width of a window's border
+    pub window_border: f32,
+    /// The padding between a window's border and its content
+    pub window_padding: f32,
+    /// The height of a window's title bar
+    pub title_bar_height: f32,
+    /// The padding before the title in a window's title bar
+    pub title_bar_padding: f32,
+    /// The padding above and below the text of a text box, which sets the text box's height
+    pub text_box_vertical_padding: f32,
+    /// The horizontal padding inside a text box
+    pub text_box_padding: f32,
+}
+
+impl Default for KayakTheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl KayakTheme {
+    /// The default dark theme
+    pub fn dark() -> Self {
+        Self {
+            name: "Dark".to_string(),
+            palette: ThemePalette {
+                background: Color::rgba(0.125, 0.125, 0.125, 1.0),
+                surface: Color::rgba(0.0781, 0.0898, 0.101, 1.0),
+                border: Color::rgba(0.0781, 0.0898, 0.101, 1.0),
+                input: Color::rgba(0.176, 0.196, 0.215, 1.0),
+                text: Color::WHITE,
+                scrollbar_thumb: Color::rgba(0.2981, 0.3098, 0.321, 0.95),
+                scrollbar_track: Color::rgba(0.1581, 0.1758, 0.191, 0.15),
+            },
+            font_sizes: ThemeFontSizes::default(),
+            radii: ThemeRadii::default(),
+            spacing: ThemeSpacing::default(),
+        }
+    }
+
+    /// A light theme
+    pub fn light() -> Self {
+        Self {
+            name: "Light".to_string(),
+            palette: ThemePalette {
+                background: Color::rgba(0.949, 0.953, 0.961, 1.0),
+                surface: Color::rgba(0.851, 0.863, 0.878, 1.0),
+                border: Color::rgba(0.741, 0.757, 0.776, 1.0),
+                input: Color::rgba(1.0, 1.0, 1.0, 1.0),
+                text: Color::rgba(0.106, 0.114, 0.125, 1.0),
+                scrollbar_thumb: Color::rgba(0.541, 0.557, 0.576, 0.95),
+                scrollbar_track: Color::rgba(0.741, 0.757, 0.776, 0.35),
+            },
+            font_sizes: ThemeFontSizes::default(),
+            radii: ThemeRadii::default(),
+            spacing: ThemeSpacing::default(),
+        }
+    }
+
+    /// Get the theme that applies to the given widget
+    ///
+    /// This is the theme of the closest [`KayakThemeProvider`](crate::widgets::KayakThemeProvider)
+    /// up the tree or, if there is none, the global theme resource.
+    pub fn resolve(
+        widget_context: &WidgetContext,
+        entity: Entity,
+        theme_query: &Query<&KayakTheme>,
+        global_theme: &KayakTheme,
+    ) -> KayakTheme {
+        widget_context
+            .get_context_entity::<KayakTheme>(entity)
+            .and_then(|theme_entity| theme_query.get(theme_entity).ok())
+            .unwrap_or(global_theme)
+            .clone()
+    }
+
+    /// Returns true if the theme that applies to the given widget changed since the calling
+    /// system last ran
+    ///
+    /// Changes to any other theme, such as that of a provider elsewhere in the tree, are ignored.
+    pub fn is_changed(
+        widget_context: &WidgetContext,
+        entity: Entity,
+        theme_query: &Query<&KayakTheme>,
+        changed_themes: &Query<Entity, Changed<KayakTheme>>,
+        global_theme: &Res<KayakTheme>,
+    ) -> bool {
+        match widget_context
+            .get_context_entity::<KayakTheme>(entity)
+            .filter(|theme_entity| theme_query.contains(*theme_entity))
+        {
+            Some(theme_entity) => changed_themes.contains(theme_entity),
+            None => global_theme.is_changed(),
+        }
+    }
+}
+
+impl Default for ThemeFontSizes {
+    fn default() -> Self {
+        Self {
+            title: 16.0,
+            title_line_height: 25.0,
+            body: 14.0,
+            body_line_height: 18.0,
+        }
+    }
+}
+
+impl Default for ThemeRadii {
+    fn default() -> Self {
+        Self {
+            button: 5.0,
+            window: 5.0,
+            text_box: 5.0,
+        }
+    }
+}
+
+impl Default for ThemeSpacing {
+    fn default() -> Self {
+        Self {
+            button_padding: 13.5,
+            window_border: 4.0,
+            window_padding: 10.0,
+            title_bar_height: 24.0,
+            title_bar_padding: 5.0,
+            text_box_vertical_padding: 4.0,
+            text_box_padding: 5.0,
+        }
+    }
+}
+
+/// The styles a themed widget was given by its parent, before any theme defaults were applied
+///
+/// Themed widgets merge their theme into their own [`KStyle`]. This keeps the original styles
+/// around so the widget can be restyled when the theme changes without its parent re-rendering.
+#[derive(Component, Default, Debug, Clone)]
+pub(crate) struct BaseStyle(pub KStyle);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use bevy::{
+        ecs::system::SystemState,
+        prelude::{Changed, Entity, Query, Res, World},
+    };
+
+    use super::KayakTheme;
+    use crate::{
+        context_entities::ContextEntities, node::WrappedIndex, tree::Tree,
+        widget_context::WidgetContext, widget_keys::WidgetKeys,
+    };
+
+    type ThemeParams = (
+        Res<'static, KayakTheme>,
+        Query<'static, 'static, &'static KayakTheme>,
+        Query<'static, 'static, Entity, Changed<KayakTheme>>,
+    );
+
+    /// A root widget with a theme provider around its grandchild
+    struct TestTree {
+        world: World,
+        widget_context: WidgetContext,
+        root: Entity,
+        grandchild: Entity,
+        provider: Entity,
+    }
+
+    impl TestTree {
+        fn new() -> Self {
+            let mut world = World::new();
+            world.insert_resource(KayakTheme::dark());
+            let root = world.spawn_empty().id();
+            let child = world.spawn_empty().id();
+            let grandchild = world.spawn_empty().id();
+            let provider = world.spawn(KayakTheme::light()).id();
+
+            let mut tree = Tree::default();
+            tree.add(WrappedIndex(root), None);
+            tree.add(WrappedIndex(child), Some(WrappedIndex(root)));
+            tree.add(WrappedIndex(grandchild), Some(WrappedIndex(child)));
+            let context_entities = ContextEntities::new();
+            context_entities.add_context_entity::<KayakTheme>(child, provider);
+            let widget_context = WidgetContext::new(
+                Arc::new(RwLock::new(tree)),
+                context_entities,
+                Default::default(),
+                WidgetKeys::new(),
+            );
+
+            Self {
+                world,
+                widget_context,
+                root,
+                grandchild,
+                provider,
+            }
+        }
+
+        /// Returns whether the theme of the root and the grandchild changed since the last check
+        fn changes(&self, state: &mut SystemState<ThemeParams>) -> (bool, bool) {
+            let (global_theme, theme_query, changed_themes) = state.get(&self.world);
+            let is_changed = |entity| {
+                KayakTheme::is_changed(
+                    &self.widget_context,
+                    entity,
+                    &theme_query,
+                    &changed_themes,
+                    &global_theme,
+                )
+            };
+            (is_changed(self.root), is_changed(self.grandchild))
+        }
+    }
+
+    #[test]
+    fn resolve_should_use_closest_provider() {
+        let mut tree = TestTree::new();
+        let mut state = SystemState::<ThemeParams>::new(&mut tree.world);
+        let (global_theme, theme_query, _) = state.get(&tree.world);
+        let resolve =
+            |entity| KayakTheme::resolve(&tree.widget_context, entity, &theme_query, &global_theme);
+
+        assert_eq!(resolve(tree.grandchild), KayakTheme::light());
+        assert_eq!(resolve(tree.root), KayakTheme::dark());
+    }
+
+    #[test]
+    fn resolve_should_fall_back_to_global_theme() {
+        let mut tree = TestTree::new();
+        tree.world.entity_mut(tree.provider).remove::<KayakTheme>();
+        let mut state = SystemState::<ThemeParams>::new(&mut tree.world);
+        let (global_theme, theme_query, _) = state.get(&tree.world);
+
+        assert_eq!(
+            KayakTheme::resolve(
+                &tree.widget_context,
+                tree.grandchild,
+                &theme_query,
+                &global_theme
+            ),
+            KayakTheme::dark()
+        );
+    }
+
+    #[test]
+    fn is_changed_should_only_follow_resolved_theme() {
+        let mut tree = TestTree::new();
+        let mut state = SystemState::<ThemeParams>::new(&mut tree.world);
+        assert_eq!(tree.changes(&mut state), (true, true));
+        assert_eq!(tree.changes(&mut state), (false, false));
+
+        tree.world
+            .get_mut::<KayakTheme>(tree.provider)
+            .unwrap()
+            .name = "custom".to_string();
+        assert_eq!(tree.changes(&mut state), (false, true));
+
+        tree.world.resource_mut::<KayakTheme>().name = "custom".to_string();
+        assert_eq!(tree.changes(&mut state), (true, false));
+    }
+}
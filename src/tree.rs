@@ -1,4 +1,3 @@
-use bevy::prelude::Entity;
 use bevy::utils::HashMap;
 use morphorm::Hierarchy;
 use std::iter::Rev;
@@ -176,6 +175,27 @@ impl Tree {
         false
     }
 
+    /// Creates a new tree containing only the given node and its descendants
+    pub fn subtree(&self, root_node: WrappedIndex) -> Tree {
+        let mut tree = Tree {
+            root_node: Some(root_node),
+            ..Default::default()
+        };
+
+        let mut stack = vec![root_node];
+        while let Some(node) = stack.pop() {
+            if let Some(children) = self.children.get(&node) {
+                tree.children.insert(node, children.clone());
+                for child in children.iter() {
+                    tree.parents.insert(*child, node);
+                    stack.push(*child);
+                }
+            }
+        }
+
+        tree
+    }
+
     pub fn flatten(&self) -> Vec<WrappedIndex> {
         if self.root_node.is_none() {
             return Vec::new();
@@ -561,43 +581,22 @@ impl Tree {
             }
             return;
         }
-        let mut children_a = children_a.unwrap();
-        let children_b = children_b.unwrap();
-        children_a.resize(children_b.len(), WrappedIndex(Entity::from_raw(0)));
-
-        for (id, node, parent_node, change) in changes.changes.iter() {
-            match change.as_slice() {
-                [Change::Deleted] => {
-                    // self.parents.remove(node);
-                    if children_a.get(*id).is_some() {
-                        children_a[*id] = WrappedIndex(Entity::from_raw(0));
-                    }
-                    self.remove(*node);
-                }
-                [Change::Inserted] => {
-                    children_a[*id] = *node;
-                    self.parents.insert(*node, *parent_node);
-                }
-                [Change::Moved, Change::Updated] => {
-                    children_a[*id] = *node;
-                    self.parents.insert(*node, *parent_node);
-                }
-                [Change::Updated] => {
-                    children_a[*id] = *node;
-                }
-                _ => {}
-            }
-        }
+        let children_b = children_b.unwrap().clone();
 
-        for (id, _node, _parent_node, _change) in changes.changes.iter() {
-            if let Some(child) = children_a.get(*id) {
-                if child.0.id() == 0 {
-                    children_a.remove(*id);
-                }
+        // Deleted children take their descendants with them
+        for (_id, node, parent_node, change) in changes.changes.iter() {
+            if change.as_slice() == [Change::Deleted] && self.parents.get(node) == Some(parent_node)
+            {
+                self.remove(*node);
             }
         }
 
-        self.children.insert(root_node, children_a);
+        // The remaining children are the same widgets as in `other`, which may have been
+        // inserted, moved or reordered
+        for child in children_b.iter() {
+            self.parents.insert(*child, root_node);
+        }
+        self.children.insert(root_node, children_b);
 
         if depth > 0 {
             for (child_id, children_of_child_changes) in changes.child_changes {
@@ -1196,6 +1195,26 @@ mod tests {
         assert!(tree.is_descendant(grandchild, root));
     }
 
+    #[test]
+    fn should_create_subtree() {
+        let mut tree = Tree::default();
+        let root = WrappedIndex(Entity::from_raw(0));
+        let child_a = WrappedIndex(Entity::from_raw(1));
+        let child_b = WrappedIndex(Entity::from_raw(2));
+        let grandchild = WrappedIndex(Entity::from_raw(3));
+        tree.add(root, None);
+        tree.add(child_a, Some(root));
+        tree.add(child_b, Some(root));
+        tree.add(grandchild, Some(child_a));
+
+        let subtree = tree.subtree(child_a);
+        assert_eq!(Some(child_a), subtree.root_node);
+        assert_eq!(Some(&vec![grandchild]), subtree.children.get(&child_a));
+        assert_eq!(Some(child_a), subtree.get_parent(grandchild));
+        assert!(!subtree.contains(child_b));
+        assert_eq!(2, subtree.len());
+    }
+
     #[test]
     fn should_give_len() {
         let mut tree = Tree::default();
@@ -1211,4 +1230,64 @@ mod tests {
         tree.add(grandchild, Some(child));
         assert_eq!(3, tree.len());
     }
+
+    #[test]
+    fn should_merge_reordered_children() {
+        let root = WrappedIndex(Entity::from_raw(0));
+        let child_a = WrappedIndex(Entity::from_raw(1));
+        let child_b = WrappedIndex(Entity::from_raw(2));
+        let child_c = WrappedIndex(Entity::from_raw(3));
+        let grandchild = WrappedIndex(Entity::from_raw(4));
+        let mut tree = Tree::default();
+        tree.add(root, None);
+        tree.add(child_a, Some(root));
+        tree.add(child_b, Some(root));
+        tree.add(child_c, Some(root));
+        tree.add(grandchild, Some(child_a));
+
+        let mut other = Tree::default();
+        other.add(root, None);
+        other.add(child_c, Some(root));
+        other.add(child_a, Some(root));
+        other.add(child_b, Some(root));
+
+        let changes = tree.diff_children(&other, root, 0);
+        tree.merge(&other, root, changes, 0);
+
+        assert_eq!(
+            Some(&vec![child_c, child_a, child_b]),
+            tree.children.get(&root)
+        );
+        assert_eq!(Some(child_a), tree.get_parent(grandchild));
+        assert_eq!(5, tree.len());
+    }
+
+    #[test]
+    fn should_merge_removed_children() {
+        let root = WrappedIndex(Entity::from_raw(0));
+        let child_a = WrappedIndex(Entity::from_raw(1));
+        let child_b = WrappedIndex(Entity::from_raw(2));
+        let child_c = WrappedIndex(Entity::from_raw(3));
+        let grandchild = WrappedIndex(Entity::from_raw(4));
+        let mut tree = Tree::default();
+        tree.add(root, None);
+        tree.add(child_a, Some(root));
+        tree.add(child_b, Some(root));
+        tree.add(child_c, Some(root));
+        tree.add(grandchild, Some(child_b));
+
+        // The middle child is removed
+        let mut other = Tree::default();
+        other.add(root, None);
+        other.add(child_a, Some(root));
+        other.add(child_c, Some(root));
+
+        let changes = tree.diff_children(&other, root, 0);
+        tree.merge(&other, root, changes, 0);
+
+        assert_eq!(Some(&vec![child_a, child_c]), tree.children.get(&root));
+        assert!(!tree.contains(child_b));
+        assert!(!tree.contains(grandchild));
+        assert_eq!(3, tree.len());
+    }
 }
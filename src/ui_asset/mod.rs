@@ -0,0 +1,235 @@
+use std::{fmt, str::FromStr};
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    reflect::TypeUuid,
+};
+
+use crate::styles::KStyle;
+
+mod parser;
+mod registry;
+
+pub(crate) use registry::UiBuildContext;
+pub use registry::UiWidgetRegistry;
+
+/// A tree of widgets loaded from a `.kui` file
+///
+/// The file describes widgets with the same syntax as the [`rsx`](crate::prelude::rsx) macro,
+/// except that attribute values are strings rather than Rust expressions:
+///
+/// ```xml
+/// <!-- Each tag is the name of a bundle registered with the UiWidgetRegistry -->
+/// <ElementBundle styles="layout-type: column; padding: 20px">
+///     <TextWidgetBundle content="Main Menu" size="32" />
+///     <KButtonBundle styles="height: 40px">
+///         <TextWidgetBundle content="Play" />
+///     </KButtonBundle>
+/// </ElementBundle>
+/// ```
+///
+/// Attribute values can be written as `"..."`, `'...'`, or `{...}`, and an attribute with no
+/// value is `true`. The tree is displayed with a
+/// [`UiAssetBundle`](crate::prelude::widgets::UiAssetBundle), which rebuilds its children
+/// whenever the asset is modified. Enable `watch_for_changes` on the `AssetPlugin` to pick up
+/// edits to the file while the app is running.
+#[derive(Debug, Clone, Default, PartialEq, TypeUuid)]
+#[uuid = "b5f6c3f2-3c1e-4c52-9a7e-2d0c6f0a8e41"]
+pub struct UiAsset {
+    /// The top-level widgets in the file
+    pub elements: Vec<UiElement>,
+}
+
+impl UiAsset {
+    /// Parses an asset from the contents of a `.kui` file
+    pub fn parse(source: &str) -> Result<Self, UiAssetError> {
+        Ok(Self {
+            elements: parser::parse_elements(source)?,
+        })
+    }
+}
+
+impl FromStr for UiAsset {
+    type Err = UiAssetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// A single widget within a [`UiAsset`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiElement {
+    /// The tag name, used to look up the widget in the [`UiWidgetRegistry`]
+    pub widget: String,
+    /// The attributes set on the tag
+    pub attributes: UiAttributes,
+    /// The widgets nested within the tag
+    pub children: Vec<UiElement>,
+    /// The line the tag starts on
+    pub line: usize,
+    /// The column the tag starts at
+    pub column: usize,
+}
+
+/// An attribute set on a [`UiElement`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiAttribute {
+    /// The name of the attribute
+    pub name: String,
+    /// The value of the attribute, with any quotes and escapes removed
+    pub value: String,
+    /// The line the value starts on
+    pub line: usize,
+    /// The column the value starts at
+    pub column: usize,
+}
+
+/// The attributes set on a [`UiElement`], used to build its widget
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiAttributes {
+    attributes: Vec<UiAttribute>,
+}
+
+impl UiAttributes {
+    pub fn new(attributes: Vec<UiAttribute>) -> Self {
+        Self { attributes }
+    }
+
+    /// Returns the attribute with the given name, if it was set
+    pub fn attribute(&self, name: &str) -> Option<&UiAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Returns the value of the attribute with the given name, if it was set
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attribute(name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Parses the value of the attribute with the given name, if it was set
+    pub fn parse<T>(&self, name: &str) -> Result<Option<T>, UiAssetError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        match self.attribute(name) {
+            Some(attribute) => attribute.value.trim().parse().map(Some).map_err(|err| {
+                UiAssetError::new(
+                    attribute.line,
+                    attribute.column,
+                    format!("invalid value for `{}`: {}", name, err),
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the `styles` attribute using [`KStyle::parse`], if it was set
+    pub fn styles(&self) -> Result<Option<KStyle>, UiAssetError> {
+        match self.attribute("styles") {
+            Some(attribute) => KStyle::parse(&attribute.value).map(Some).map_err(|err| {
+                // Report the error relative to the file rather than the attribute
+                let column = if err.line == 1 {
+                    attribute.column + err.column - 1
+                } else {
+                    err.column
+                };
+                UiAssetError::new(attribute.line + err.line - 1, column, err.message)
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns an error if any attribute isn't one of the given names
+    ///
+    /// This catches typos that would otherwise be silently ignored.
+    pub fn expect_only(&self, names: &[&str]) -> Result<(), UiAssetError> {
+        match self
+            .attributes
+            .iter()
+            .find(|attribute| !names.contains(&attribute.name.as_str()))
+        {
+            Some(attribute) => Err(UiAssetError::new(
+                attribute.line,
+                attribute.column,
+                format!("unknown attribute `{}`", attribute.name),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &UiAttribute> {
+        self.attributes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+/// An error produced when parsing a [`UiAsset`] or building its widgets
+///
+/// Lines and columns start at `1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UiAssetError {
+    /// The line the error occurred on
+    pub line: usize,
+    /// The column (in characters) the error occurred at
+    pub column: usize,
+    /// A description of what went wrong
+    pub message: String,
+}
+
+impl UiAssetError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for UiAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (line {}, column {})",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for UiAssetError {}
+
+#[derive(Default)]
+pub struct UiAssetLoader;
+
+impl AssetLoader for UiAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let source = std::str::from_utf8(bytes)?;
+            let asset = UiAsset::parse(source)
+                .map_err(|err| anyhow::anyhow!("{}: {}", load_context.path().display(), err))?;
+            load_context.set_default_asset(LoadedAsset::new(asset));
+
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["kui"];
+        EXTENSIONS
+    }
+}
@@ -0,0 +1,359 @@
+use super::{UiAssetError, UiAttribute, UiAttributes, UiElement};
+
+/// Parses the top-level elements of a `.kui` file
+pub(crate) fn parse_elements(source: &str) -> Result<Vec<UiElement>, UiAssetError> {
+    let mut parser = Parser { source, index: 0 };
+    parser.parse_children(None)
+}
+
+/// The XML entities that can be used within quoted attribute values
+const ENTITIES: &[(&str, char)] = &[
+    ("&amp;", '&'),
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&quot;", '"'),
+    ("&apos;", '\''),
+];
+
+struct Parser<'a> {
+    source: &'a str,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.index..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.index += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the line and column of the given byte index
+    fn position(&self, index: usize) -> (usize, usize) {
+        let before = &self.source[..index];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    fn error_at(&self, index: usize, message: impl Into<String>) -> UiAssetError {
+        let (line, column) = self.position(index);
+        UiAssetError::new(line, column, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.index = self.source.len() - trimmed.len();
+    }
+
+    /// Skips whitespace and `<!-- -->` comments
+    fn skip_trivia(&mut self) -> Result<(), UiAssetError> {
+        loop {
+            self.skip_whitespace();
+            if !self.rest().starts_with("<!--") {
+                return Ok(());
+            }
+            match self.rest().find("-->") {
+                Some(end) => self.index += end + 3,
+                None => return Err(self.error_at(self.index, "unterminated comment")),
+            }
+        }
+    }
+
+    /// Parses elements until the closing tag of the given parent, or the end of the file if
+    /// there is none
+    ///
+    /// The parent is given as its name and the index of its opening tag.
+    fn parse_children(
+        &mut self,
+        parent: Option<(&str, usize)>,
+    ) -> Result<Vec<UiElement>, UiAssetError> {
+        let mut children = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            let start = self.index;
+            if self.rest().is_empty() {
+                return match parent {
+                    Some((name, open)) => {
+                        Err(self.error_at(open, format!("`<{}>` is never closed", name)))
+                    }
+                    None => Ok(children),
+                };
+            }
+
+            if self.eat("</") {
+                let name = self.parse_name()?;
+                self.skip_whitespace();
+                if !self.eat(">") {
+                    return Err(self.error_at(self.index, "expected `>`"));
+                }
+                return match parent {
+                    Some((parent_name, _)) if parent_name == name => Ok(children),
+                    Some((parent_name, _)) => Err(self.error_at(
+                        start,
+                        format!("expected `</{}>`, found `</{}>`", parent_name, name),
+                    )),
+                    None => Err(self.error_at(start, format!("unexpected `</{}>`", name))),
+                };
+            }
+
+            if self.peek() == Some('<') {
+                children.push(self.parse_element()?);
+            } else {
+                return Err(self.error_at(
+                    start,
+                    "expected a tag (text must be set with an attribute, such as `content`)",
+                ));
+            }
+        }
+    }
+
+    fn parse_element(&mut self) -> Result<UiElement, UiAssetError> {
+        let start = self.index;
+        let (line, column) = self.position(start);
+        self.bump();
+        let widget = self.parse_name()?;
+
+        let mut attributes: Vec<UiAttribute> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok(UiElement {
+                    widget,
+                    attributes: UiAttributes::new(attributes),
+                    children: Vec::new(),
+                    line,
+                    column,
+                });
+            }
+            if self.eat(">") {
+                let children = self.parse_children(Some((widget.as_str(), start)))?;
+                return Ok(UiElement {
+                    widget,
+                    attributes: UiAttributes::new(attributes),
+                    children,
+                    line,
+                    column,
+                });
+            }
+            if self.rest().is_empty() {
+                return Err(self.error_at(start, format!("`<{}` is never closed", widget)));
+            }
+
+            let name_index = self.index;
+            let attribute = self.parse_attribute()?;
+            if attributes.iter().any(|other| other.name == attribute.name) {
+                return Err(self.error_at(
+                    name_index,
+                    format!("`{}` is set more than once", attribute.name),
+                ));
+            }
+            attributes.push(attribute);
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String, UiAssetError> {
+        let start = self.index;
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == ':' {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+
+        if self.index == start {
+            Err(self.error_at(start, "expected a name"))
+        } else {
+            Ok(self.source[start..self.index].to_string())
+        }
+    }
+
+    fn parse_attribute(&mut self) -> Result<UiAttribute, UiAssetError> {
+        let name_index = self.index;
+        let name = self.parse_name()?;
+        self.skip_whitespace();
+        if !self.eat("=") {
+            // Like in HTML, an attribute without a value is a flag
+            let (line, column) = self.position(name_index);
+            return Ok(UiAttribute {
+                name,
+                value: String::from("true"),
+                line,
+                column,
+            });
+        }
+
+        self.skip_whitespace();
+        let value_index = self.index;
+        let value = match self.peek() {
+            Some(quote @ ('"' | '\'')) => self.parse_quoted(quote)?,
+            Some('{') => self.parse_braced()?,
+            _ => return Err(self.error_at(value_index, format!("expected a value for `{}`", name))),
+        };
+        // Point past the opening quote or brace
+        let (line, column) = self.position(value_index + 1);
+        Ok(UiAttribute {
+            name,
+            value,
+            line,
+            column,
+        })
+    }
+
+    /// Parses a value wrapped in `"` or `'`, replacing any XML entities
+    fn parse_quoted(&mut self, quote: char) -> Result<String, UiAssetError> {
+        let start = self.index;
+        self.bump();
+
+        let mut value = String::new();
+        loop {
+            if let Some((entity, c)) = ENTITIES
+                .iter()
+                .find(|(entity, _)| self.rest().starts_with(entity))
+            {
+                self.index += entity.len();
+                value.push(*c);
+                continue;
+            }
+
+            match self.bump() {
+                Some(c) if c == quote => return Ok(value),
+                Some(c) => value.push(c),
+                None => return Err(self.error_at(start, "unterminated string")),
+            }
+        }
+    }
+
+    /// Parses a value wrapped in `{}`, as it would be written in `rsx`
+    ///
+    /// A string literal, like `{"Play"}`, is unquoted. Anything else is used as-is.
+    fn parse_braced(&mut self) -> Result<String, UiAssetError> {
+        let start = self.index;
+        self.bump();
+        let content_start = self.index;
+
+        let mut depth = 1;
+        let mut in_string = false;
+        while let Some(c) = self.bump() {
+            match c {
+                '\\' if in_string => {
+                    self.bump();
+                }
+                '"' => in_string = !in_string,
+                '{' if !in_string => depth += 1,
+                '}' if !in_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let content = self.source[content_start..self.index - 1].trim();
+                        return Ok(unquote(content));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err(self.error_at(start, "unterminated `{`"))
+    }
+}
+
+/// Removes the quotes and escapes from a Rust string literal, or returns the string as-is if it
+/// isn't one
+fn unquote(content: &str) -> String {
+    if content.len() < 2 || !content.starts_with('"') || !content.ends_with('"') {
+        return content.to_string();
+    }
+
+    let mut value = String::new();
+    let mut chars = content[1..content.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some(c) => value.push(c),
+            None => {}
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_elements;
+
+    #[test]
+    fn should_parse_nested_elements() {
+        let elements = parse_elements(
+            r#"
+            <!-- The main menu -->
+            <ElementBundle styles="padding: 10px">
+                <TextWidgetBundle content="Title" />
+                <KButtonBundle><TextWidgetBundle content={"Play"} /></KButtonBundle>
+            </ElementBundle>
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(elements.len(), 1);
+        let root = &elements[0];
+        assert_eq!(root.widget, "ElementBundle");
+        assert_eq!((root.line, root.column), (3, 13));
+        assert_eq!(root.attributes.get("styles"), Some("padding: 10px"));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[1].widget, "KButtonBundle");
+        assert_eq!(
+            root.children[1].children[0].attributes.get("content"),
+            Some("Play")
+        );
+    }
+
+    #[test]
+    fn should_parse_attribute_values() {
+        let elements =
+            parse_elements(r#"<Text a='1 &lt; 2' b={24} c={"say \"hi\""} draggable />"#).unwrap();
+        let attributes = &elements[0].attributes;
+        assert_eq!(attributes.get("a"), Some("1 < 2"));
+        assert_eq!(attributes.get("b"), Some("24"));
+        assert_eq!(attributes.get("c"), Some("say \"hi\""));
+        assert_eq!(attributes.get("draggable"), Some("true"));
+        assert_eq!(attributes.parse::<f32>("b").unwrap(), Some(24.0));
+    }
+
+    #[test]
+    fn should_report_mismatched_tags() {
+        let err = parse_elements("<A>\n  <B>\n  </A>\n</B>").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+        assert_eq!(err.message, "expected `</B>`, found `</A>`");
+
+        let err = parse_elements("<A>\n  <B />").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+    }
+
+    #[test]
+    fn should_report_style_errors_within_the_file() {
+        let elements = parse_elements("<A\n  styles=\"width: 1px; height: big\" />").unwrap();
+        let err = elements[0].attributes.styles().unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 31);
+    }
+}
@@ -0,0 +1,701 @@
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+
+use bevy::{
+    prelude::{
+        AssetServer, Bundle, Commands, Component, DespawnRecursiveExt, Entity, Handle, Query,
+        Resource, Vec2,
+    },
+    reflect::{FromReflect, Reflect, ReflectMut},
+    utils::{HashMap, HashSet},
+};
+use kayak_font::Alignment;
+
+use super::{UiAssetError, UiAttributes, UiElement};
+use crate::{
+    children::KChildren,
+    styles::{parse_color, Edge, KStyle},
+    widget::Widget,
+    widgets::{
+        Background, BackgroundBundle, Clip, ClipBundle, Element, ElementBundle, Image, ImageBundle,
+        KButton, KButtonBundle, KWindow, KayakThemeProvider, KayakThemeProviderBundle, NinePatch,
+        NinePatchBundle, ScrollBoxBundle, ScrollBoxProps, TextBoxBundle, TextBoxProps, TextProps,
+        TextWidgetBundle, TextureAtlas, TextureAtlasBundle, WindowBundle,
+    },
+};
+
+type BuildWidget = dyn Fn(&UiWidgetRegistry, &UiAttributes, &AssetServer) -> Result<InsertWidget, UiAssetError>
+    + Send
+    + Sync;
+
+/// Inserts the components of a built widget into its entity
+type InsertWidget = Box<dyn FnOnce(&mut Commands, Entity)>;
+
+type ParseValue = dyn Fn(&str, &AssetServer) -> Result<Box<dyn Reflect>, String> + Send + Sync;
+
+/// A widget registered under a tag name
+struct RegisteredWidget {
+    /// The [`WidgetName`](crate::prelude::WidgetName) of the widget, used to check that its
+    /// system has been registered
+    widget_name: String,
+    build: Box<BuildWidget>,
+}
+
+/// A widget built from a [`UiElement`], along with its children, that has yet to be spawned
+pub(crate) struct BuiltWidget {
+    insert: InsertWidget,
+    children: Vec<BuiltWidget>,
+}
+
+/// The widgets that can be used within a [`UiAsset`](super::UiAsset), keyed by tag name
+///
+/// Most widgets are registered with [`register_props`](Self::register_props), which sets the
+/// fields of the widget's props from the attributes of the same name, using reflection. Each
+/// field is parsed by the value parser registered for its type with
+/// [`register_value`](Self::register_value). The `styles` attribute sets the widget's
+/// [`KStyle`], and the widget's children are set as its [`KChildren`].
+///
+/// The widget's update system must be registered with
+/// [`register_widget`](crate::prelude::RegisterWidget::register_widget), and building an asset
+/// fails when it uses a widget with no registered system.
+///
+/// The built-in widgets are registered by default, under the same bundle names used in `rsx`,
+/// and so are parsers for strings, numbers, booleans, colors, vectors, edges, styles, text
+/// alignments, and image handles (loaded from a path).
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use kayak_ui::prelude::{KStyle, UiWidgetRegistry, Widget, WidgetName};
+/// #[derive(Component, Default, Reflect)]
+/// #[reflect(Component)]
+/// struct Panel {
+///     title: String,
+///     collapsed: bool,
+/// }
+///
+/// impl Widget for Panel {}
+///
+/// #[derive(Bundle)]
+/// struct PanelBundle {
+///     panel: Panel,
+///     styles: KStyle,
+///     widget_name: WidgetName,
+/// }
+///
+/// impl Default for PanelBundle {
+///     fn default() -> Self {
+///         Self {
+///             panel: Default::default(),
+///             styles: Default::default(),
+///             widget_name: Panel::default().get_name(),
+///         }
+///     }
+/// }
+///
+/// fn register_widgets(mut registry: ResMut<UiWidgetRegistry>) {
+///     // <PanelBundle title="Settings" collapsed>...</PanelBundle>
+///     registry.register_props::<Panel, PanelBundle>("PanelBundle", &[]);
+/// }
+/// ```
+#[derive(Resource)]
+pub struct UiWidgetRegistry {
+    widgets: HashMap<String, RegisteredWidget>,
+    /// The parsers of the field types that can be set from attributes
+    values: HashMap<TypeId, Arc<ParseValue>>,
+}
+
+/// What's needed to build the widgets of a [`UiAsset`](super::UiAsset)
+pub(crate) struct UiBuildContext<'a> {
+    pub asset_server: &'a AssetServer,
+    /// The names of the widgets whose systems have been registered
+    pub systems: &'a HashSet<String>,
+}
+
+impl Default for UiWidgetRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            widgets: HashMap::default(),
+            values: HashMap::default(),
+        };
+        registry.register_default_values();
+        registry.register_defaults();
+        registry
+    }
+}
+
+impl UiWidgetRegistry {
+    /// Registers the widget with the props `P` under the given tag name, replacing any widget
+    /// already registered under that name
+    ///
+    /// The widget is spawned with `B::default()`, whose props are then replaced with the ones
+    /// set from the element's attributes. Each attribute sets the field of `P` with the same
+    /// name, or with the name it's mapped to by `aliases`, given as `(attribute, field)` pairs.
+    /// The fields of tuple structs are named by their index.
+    pub fn register_props<P, B>(&mut self, name: impl Into<String>, aliases: &[(&str, &str)])
+    where
+        P: Widget + Component + Reflect + Default,
+        B: Bundle + Default,
+    {
+        let aliases = aliases
+            .iter()
+            .map(|(attribute, field)| (attribute.to_string(), field.to_string()))
+            .collect::<HashMap<_, _>>();
+        self.widgets.insert(
+            name.into(),
+            RegisteredWidget {
+                widget_name: P::default().get_name().0,
+                build: boxed(move |registry, attributes, asset_server| {
+                    let mut props = P::default();
+                    registry.set_props(&mut props, attributes, &aliases, asset_server)?;
+                    let styles = attributes.styles()?;
+                    Ok(inserter(move |commands, entity| {
+                        let mut widget = commands.entity(entity);
+                        widget.insert(B::default()).insert(props);
+                        if let Some(styles) = styles {
+                            widget.insert(styles);
+                        }
+                    }))
+                }),
+            },
+        );
+    }
+
+    /// Registers the widget `W` under the given tag name, replacing any widget already
+    /// registered under that name
+    ///
+    /// Unlike [`register_props`](Self::register_props), the bundle is built by hand from the
+    /// element's attributes, for widgets whose props can't be set field by field. The
+    /// [`WidgetName`](crate::prelude::WidgetName) in the bundle must be that of `W`.
+    pub fn register<W, B, F>(&mut self, name: impl Into<String>, build: F)
+    where
+        W: Widget + Default,
+        B: Bundle,
+        F: Fn(&UiAttributes, &AssetServer) -> Result<B, UiAssetError> + Send + Sync + 'static,
+    {
+        self.widgets.insert(
+            name.into(),
+            RegisteredWidget {
+                widget_name: W::default().get_name().0,
+                build: boxed(move |_, attributes, asset_server| {
+                    let bundle = build(attributes, asset_server)?;
+                    Ok(inserter(move |commands, entity| {
+                        commands.entity(entity).insert(bundle);
+                    }))
+                }),
+            },
+        );
+    }
+
+    /// Registers the parser used to set fields of the type `T` (or `Option<T>`) from attributes,
+    /// replacing any parser already registered for that type
+    ///
+    /// The parser is given the value of the attribute, and returns a message describing what's
+    /// wrong with it if it's invalid.
+    pub fn register_value<T, F>(&mut self, parse: F)
+    where
+        T: Reflect + FromReflect,
+        F: Fn(&str, &AssetServer) -> Result<T, String> + Send + Sync + 'static,
+    {
+        let parse = Arc::new(parse);
+        let parse_optional = parse.clone();
+        self.values.insert(
+            TypeId::of::<T>(),
+            parser(move |value, asset_server| {
+                parse(value, asset_server).map(|value| Box::new(value) as Box<dyn Reflect>)
+            }),
+        );
+        self.values.insert(
+            TypeId::of::<Option<T>>(),
+            parser(move |value, asset_server| {
+                parse_optional(value, asset_server)
+                    .map(|value| Box::new(Some(value)) as Box<dyn Reflect>)
+            }),
+        );
+    }
+
+    /// Returns true if a widget is registered under the given tag name
+    pub fn contains(&self, name: &str) -> bool {
+        self.widgets.contains_key(name)
+    }
+
+    /// Builds the widget for the given element, along with all of its children
+    ///
+    /// Nothing is spawned until the widget is passed to [`spawn`](Self::spawn), so an element
+    /// that fails to build leaves any widgets built from it previously untouched.
+    pub(crate) fn build(
+        &self,
+        element: &UiElement,
+        context: &UiBuildContext,
+    ) -> Result<BuiltWidget, UiAssetError> {
+        let widget = self.widgets.get(&element.widget).ok_or_else(|| {
+            UiAssetError::new(
+                element.line,
+                element.column,
+                format!("unknown widget `{}`", element.widget),
+            )
+        })?;
+        if !context.systems.contains(&widget.widget_name) {
+            return Err(UiAssetError::new(
+                element.line,
+                element.column,
+                format!(
+                    "`{}` is a `{}`, but no system has been registered for that widget",
+                    element.widget, widget.widget_name
+                ),
+            ));
+        }
+
+        Ok(BuiltWidget {
+            insert: (widget.build)(self, &element.attributes, context.asset_server)?,
+            children: element
+                .children
+                .iter()
+                .map(|child| self.build(child, context))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Spawns a built widget into the given entity, along with all of its children
+    ///
+    /// The children reuse the entities of the entity's previous children by position, like
+    /// widgets built with `rsx`, so spawning the same element again doesn't leak entities. Any
+    /// previous children that are no longer needed are despawned.
+    pub(crate) fn spawn(
+        commands: &mut Commands,
+        entity: Entity,
+        widget: BuiltWidget,
+        children: &Query<&KChildren>,
+    ) {
+        let previous_children = children
+            .get(entity)
+            .map(|children| children.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut widget_children = KChildren::new();
+        let child_count = widget.children.len();
+        for (index, child) in widget.children.into_iter().enumerate() {
+            let child_entity = match previous_children.get(index) {
+                Some(previous) => {
+                    commands.entity(*previous).despawn_recursive();
+                    commands.get_or_spawn(*previous).id()
+                }
+                None => commands.spawn_empty().id(),
+            };
+            Self::spawn(commands, child_entity, child, children);
+            widget_children.add(child_entity);
+        }
+        for previous in previous_children.iter().skip(child_count) {
+            Self::despawn(commands, *previous, children);
+        }
+
+        (widget.insert)(commands, entity);
+        if child_count > 0 {
+            commands.entity(entity).insert(widget_children);
+        }
+    }
+
+    /// Despawns a widget built from an element, along with all of its children
+    pub(crate) fn despawn(commands: &mut Commands, entity: Entity, children: &Query<&KChildren>) {
+        if let Ok(widget_children) = children.get(entity) {
+            for child in widget_children.iter() {
+                Self::despawn(commands, child, children);
+            }
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+
+    /// Sets the fields of the given props from the attributes with the same name
+    fn set_props(
+        &self,
+        props: &mut dyn Reflect,
+        attributes: &UiAttributes,
+        aliases: &HashMap<String, String>,
+        asset_server: &AssetServer,
+    ) -> Result<(), UiAssetError> {
+        for attribute in attributes.iter() {
+            if attribute.name == "styles" {
+                continue;
+            }
+            let error =
+                |message: String| UiAssetError::new(attribute.line, attribute.column, message);
+
+            let name = aliases
+                .get(&attribute.name)
+                .map_or(attribute.name.as_str(), String::as_str);
+            let field = match props.reflect_mut() {
+                ReflectMut::Struct(props) => props.field_mut(name),
+                ReflectMut::TupleStruct(props) => name
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| props.field_mut(index)),
+                _ => None,
+            }
+            .ok_or_else(|| error(format!("unknown attribute `{}`", attribute.name)))?;
+
+            let parse = self
+                .values
+                .get(&Any::type_id(field.as_any()))
+                .ok_or_else(|| {
+                    error(format!(
+                        "`{}` can't be set from an attribute, since no parser has been \
+                         registered for `{}`",
+                        attribute.name,
+                        field.type_name()
+                    ))
+                })?;
+            let value = parse(&attribute.value, asset_server).map_err(|message| {
+                error(format!(
+                    "invalid value for `{}`: {}",
+                    attribute.name, message
+                ))
+            })?;
+            if field.set(value).is_err() {
+                return Err(error(format!(
+                    "the parser for `{}` returned a value of the wrong type",
+                    field.type_name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn register_default_values(&mut self) {
+        fn parse<T: std::str::FromStr>(value: &str, _: &AssetServer) -> Result<T, String>
+        where
+            T::Err: std::fmt::Display,
+        {
+            value.trim().parse().map_err(|err: T::Err| err.to_string())
+        }
+
+        self.register_value(|value, _| Ok(value.to_string()));
+        self.register_value(parse::<bool>);
+        self.register_value(parse::<f32>);
+        self.register_value(parse::<f64>);
+        self.register_value(parse::<i32>);
+        self.register_value(parse::<u32>);
+        self.register_value(parse::<usize>);
+        self.register_value(|value, _| parse_vec2(value));
+        self.register_value(|value, _| parse_edge(value));
+        self.register_value(|value, _| parse_color(value).map_err(|err| err.message));
+        self.register_value(|value, _| KStyle::parse(value).map_err(|err| err.to_string()));
+        self.register_value(|value, _| match value.trim() {
+            "start" => Ok(Alignment::Start),
+            "middle" => Ok(Alignment::Middle),
+            "end" => Ok(Alignment::End),
+            value => Err(format!(
+                "expected `start`, `middle`, or `end`, found `{}`",
+                value
+            )),
+        });
+        self.register_value(|value, asset_server: &AssetServer| {
+            Ok::<Handle<bevy::prelude::Image>, _>(asset_server.load(value.trim()))
+        });
+    }
+
+    fn register_defaults(&mut self) {
+        self.register_props::<Element, ElementBundle>("ElementBundle", &[]);
+        self.register_props::<Background, BackgroundBundle>("BackgroundBundle", &[]);
+        self.register_props::<Clip, ClipBundle>("ClipBundle", &[]);
+        self.register_props::<KButton, KButtonBundle>("KButtonBundle", &[]);
+        self.register_props::<TextProps, TextWidgetBundle>("TextWidgetBundle", &[]);
+        self.register_props::<KWindow, WindowBundle>("WindowBundle", &[]);
+        self.register_props::<TextBoxProps, TextBoxBundle>("TextBoxBundle", &[]);
+        self.register_props::<ScrollBoxProps, ScrollBoxBundle>("ScrollBoxBundle", &[]);
+        self.register_props::<Image, ImageBundle>("ImageBundle", &[("path", "0")]);
+        self.register_props::<NinePatch, NinePatchBundle>("NinePatchBundle", &[("path", "handle")]);
+        self.register_props::<TextureAtlas, TextureAtlasBundle>(
+            "TextureAtlasBundle",
+            &[("path", "handle")],
+        );
+        // The theme itself can't be written as an attribute, so this provides the default theme
+        self.register_props::<KayakThemeProvider, KayakThemeProviderBundle>(
+            "KayakThemeProviderBundle",
+            &[],
+        );
+    }
+}
+
+/// Boxes a closure, giving it the signature of [`BuildWidget`]
+fn boxed<F>(build: F) -> Box<BuildWidget>
+where
+    F: Fn(&UiWidgetRegistry, &UiAttributes, &AssetServer) -> Result<InsertWidget, UiAssetError>
+        + Send
+        + Sync
+        + 'static,
+{
+    Box::new(build)
+}
+
+/// Boxes a closure, giving it the signature of [`InsertWidget`]
+fn inserter<F>(insert: F) -> InsertWidget
+where
+    F: FnOnce(&mut Commands, Entity) + 'static,
+{
+    Box::new(insert)
+}
+
+/// Wraps a closure, giving it the signature of [`ParseValue`]
+fn parser<F>(parse: F) -> Arc<ParseValue>
+where
+    F: Fn(&str, &AssetServer) -> Result<Box<dyn Reflect>, String> + Send + Sync + 'static,
+{
+    Arc::new(parse)
+}
+
+/// Parses a vector written as two numbers separated by a comma or whitespace, like `10, 20`
+fn parse_vec2(value: &str) -> Result<Vec2, String> {
+    match parse_numbers(value).as_deref() {
+        Some([x, y]) => Ok(Vec2::new(*x, *y)),
+        _ => Err("expected two numbers".to_string()),
+    }
+}
+
+/// Parses an edge written as one, two, or four numbers, in the same order as CSS
+fn parse_edge(value: &str) -> Result<Edge<f32>, String> {
+    match parse_numbers(value).as_deref() {
+        Some([all]) => Ok(Edge::all(*all)),
+        Some([vertical, horizontal]) => Ok(Edge::axis(*vertical, *horizontal)),
+        Some([top, right, bottom, left]) => Ok(Edge::new(*top, *right, *bottom, *left)),
+        _ => Err("expected one, two, or four numbers".to_string()),
+    }
+}
+
+/// Parses numbers separated by commas or whitespace, returning `None` if any are invalid
+fn parse_numbers(value: &str) -> Option<Vec<f32>> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse::<f32>().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::{AssetServer, FileAssetIo},
+        ecs::system::SystemState,
+        prelude::{Bundle, Color, Commands, Component, Entity, Query, Reflect, World},
+        utils::HashSet,
+    };
+
+    use super::{UiBuildContext, UiWidgetRegistry};
+    use crate::{
+        children::KChildren,
+        context::WidgetName,
+        styles::{parse_color, KStyle, StyleProp, Units},
+        ui_asset::{UiAsset, UiAssetError},
+        widget::Widget,
+        widgets::{Element, KButton, TextProps},
+    };
+
+    #[derive(Component, Default, Reflect)]
+    struct Badge {
+        label: String,
+        count: u32,
+        color: Option<Color>,
+    }
+
+    impl Widget for Badge {}
+
+    #[derive(Bundle)]
+    struct BadgeBundle {
+        badge: Badge,
+        styles: KStyle,
+        widget_name: WidgetName,
+    }
+
+    impl Default for BadgeBundle {
+        fn default() -> Self {
+            Self {
+                badge: Default::default(),
+                styles: Default::default(),
+                widget_name: Badge::default().get_name(),
+            }
+        }
+    }
+
+    fn registry() -> UiWidgetRegistry {
+        let mut registry = UiWidgetRegistry::default();
+        registry.register_props::<Badge, BadgeBundle>("BadgeBundle", &[]);
+        registry
+    }
+
+    fn systems() -> HashSet<String> {
+        [
+            Element::default().get_name().0,
+            KButton::default().get_name().0,
+            TextProps::default().get_name().0,
+            Badge::default().get_name().0,
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// Builds the first element of the source, then spawns it into the given entity, like
+    /// `update_ui_asset`
+    fn build(
+        world: &mut World,
+        entity: Entity,
+        source: &str,
+        systems: &HashSet<String>,
+    ) -> Result<(), UiAssetError> {
+        let registry = registry();
+        let asset = UiAsset::parse(source).unwrap();
+        let asset_server = AssetServer::new(FileAssetIo::new("assets", false));
+        let context = UiBuildContext {
+            asset_server: &asset_server,
+            systems,
+        };
+        let widget = registry.build(&asset.elements[0], &context)?;
+
+        let mut state = SystemState::<(Commands, Query<&KChildren>)>::new(world);
+        {
+            let (mut commands, children) = state.get_mut(world);
+            UiWidgetRegistry::spawn(&mut commands, entity, widget, &children);
+        }
+        state.apply(world);
+        Ok(())
+    }
+
+    #[test]
+    fn should_reuse_entities_when_rebuilt() {
+        let source = r#"
+            <ElementBundle>
+                <ElementBundle><TextWidgetBundle content="Title" /></ElementBundle>
+                <KButtonBundle />
+            </ElementBundle>
+        "#;
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+
+        build(&mut world, root, source, &systems()).unwrap();
+        assert_eq!(world.entities().len(), 4);
+        let children = world.get::<KChildren>(root).unwrap().clone();
+
+        build(&mut world, root, source, &systems()).unwrap();
+        assert_eq!(world.entities().len(), 4);
+        let rebuilt = world.get::<KChildren>(root).unwrap();
+        assert_eq!(
+            children.iter().collect::<Vec<_>>(),
+            rebuilt.iter().collect::<Vec<_>>()
+        );
+
+        // Removing elements despawns their widgets, along with any nested widgets
+        build(
+            &mut world,
+            root,
+            "<ElementBundle><KButtonBundle /></ElementBundle>",
+            &systems(),
+        )
+        .unwrap();
+        assert_eq!(world.entities().len(), 2);
+    }
+
+    #[test]
+    fn should_set_props_from_attributes() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+
+        build(
+            &mut world,
+            root,
+            r##"<BadgeBundle label="New" count="3" color="#ff0000" styles="width: 10px" />"##,
+            &systems(),
+        )
+        .unwrap();
+
+        let badge = world.get::<Badge>(root).unwrap();
+        assert_eq!(badge.label, "New");
+        assert_eq!(badge.count, 3);
+        assert_eq!(badge.color, Some(parse_color("#ff0000").unwrap()));
+        assert_eq!(
+            world.get::<KStyle>(root).unwrap().width,
+            StyleProp::Value(Units::Pixels(10.0))
+        );
+        assert_eq!(
+            world.get::<WidgetName>(root),
+            Some(&Badge::default().get_name())
+        );
+    }
+
+    #[test]
+    fn should_fail_for_unknown_attributes() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+
+        let err = build(
+            &mut world,
+            root,
+            "<BadgeBundle\n    size=\"3\" />",
+            &systems(),
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 11));
+        assert!(err.message.contains("unknown attribute `size`"));
+    }
+
+    #[test]
+    fn should_fail_for_invalid_values() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+
+        let err = build(
+            &mut world,
+            root,
+            "<BadgeBundle\n    count=\"three\" />",
+            &systems(),
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 12));
+        assert!(err.message.contains("invalid value for `count`"));
+    }
+
+    #[test]
+    fn should_keep_the_previous_widgets_when_a_build_fails() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        build(
+            &mut world,
+            root,
+            r#"<ElementBundle><BadgeBundle label="New" /></ElementBundle>"#,
+            &systems(),
+        )
+        .unwrap();
+        let children = world.get::<KChildren>(root).unwrap().clone();
+
+        // The error is in the last element, after the first has been built
+        build(
+            &mut world,
+            root,
+            r#"<ElementBundle><BadgeBundle label="Old" /><BadgeBundle count="-1" /></ElementBundle>"#,
+            &systems(),
+        )
+        .unwrap_err();
+
+        assert_eq!(world.entities().len(), 2);
+        let badge = children.iter().next().unwrap();
+        assert_eq!(world.get::<Badge>(badge).unwrap().label, "New");
+    }
+
+    #[test]
+    fn should_fail_for_widgets_without_a_system() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let mut systems = systems();
+        systems.remove(&KButton::default().get_name().0);
+
+        let err = build(
+            &mut world,
+            root,
+            "<ElementBundle>\n    <KButtonBundle />\n</ElementBundle>",
+            &systems,
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+        assert!(err.message.contains("no system has been registered"));
+    }
+}
@@ -0,0 +1,219 @@
+use bevy::prelude::{Resource, Window};
+
+/// How the size of the UI is determined, used by [`UiScale`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UiScaleMode {
+    /// Each UI pixel covers the given number of physical pixels
+    Fixed(f32),
+    /// Follows the scale factor reported by the OS, so a UI pixel is one logical pixel
+    MatchDpi,
+    /// Scales the UI so it covers the window as it would a window of the reference resolution
+    ReferenceResolution {
+        /// The width the UI was designed for, in pixels
+        width: f32,
+        /// The height the UI was designed for, in pixels
+        height: f32,
+        /// How much the width (`0.0`) or the height (`1.0`) of the window decides the scale
+        ///
+        /// Values in between blend the two, which keeps the UI usable as the aspect ratio of the
+        /// window changes.
+        match_width_or_height: f32,
+    },
+}
+
+impl Default for UiScaleMode {
+    fn default() -> Self {
+        UiScaleMode::MatchDpi
+    }
+}
+
+/// Sets the scale of the UI relative to the window
+///
+/// Layout, rendering, and input all happen in UI pixels, which are mapped to the window using the
+/// [`mode`](Self::mode) and then multiplied by the [`factor`](Self::factor), for things like an
+/// accessibility setting.
+///
+/// # Example
+///
+/// ```
+/// # use kayak_ui::prelude::{UiScale, UiScaleMode};
+/// // Lay out the UI for 1080p and scale it to fit whatever the window is
+/// let ui_scale = UiScale {
+///     mode: UiScaleMode::ReferenceResolution {
+///         width: 1920.0,
+///         height: 1080.0,
+///         match_width_or_height: 0.5,
+///     },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct UiScale {
+    /// How the UI is mapped to the window
+    pub mode: UiScaleMode,
+    /// An additional scale applied on top of the mode
+    pub factor: f32,
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self {
+            mode: UiScaleMode::default(),
+            factor: 1.0,
+        }
+    }
+}
+
+impl UiScale {
+    /// Returns the number of physical pixels covered by a UI pixel in the given window
+    pub fn scale_factor(&self, window: &Window) -> f32 {
+        let scale = match self.mode {
+            UiScaleMode::Fixed(scale) => scale,
+            UiScaleMode::MatchDpi => window.scale_factor() as f32,
+            UiScaleMode::ReferenceResolution {
+                width,
+                height,
+                match_width_or_height,
+            } => {
+                // Blend logarithmically so that doubling one axis and halving the other cancel out
+                let width_scale = (window.physical_width() as f32 / width).log2();
+                let height_scale = (window.physical_height() as f32 / height).log2();
+                let t = match_width_or_height.clamp(0.0, 1.0);
+                2.0_f32.powf(width_scale + (height_scale - width_scale) * t)
+            }
+        };
+        let scale = scale * self.factor;
+        if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the number of UI pixels covered by a logical pixel in the given window
+    ///
+    /// This converts positions reported by bevy, like the cursor position, into UI pixels.
+    pub fn logical_to_ui(&self, window: &Window) -> f32 {
+        window.scale_factor() as f32 / self.scale_factor(window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UiScale, UiScaleMode};
+    use bevy::window::{Window, WindowDescriptor, WindowId};
+
+    fn window(physical_width: u32, physical_height: u32, scale_factor: f64) -> Window {
+        Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            physical_width,
+            physical_height,
+            scale_factor,
+            None,
+            None,
+        )
+    }
+
+    fn reference_resolution(match_width_or_height: f32) -> UiScale {
+        UiScale {
+            mode: UiScaleMode::ReferenceResolution {
+                width: 1920.0,
+                height: 1080.0,
+                match_width_or_height,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fixed_should_ignore_the_window() {
+        let ui_scale = UiScale {
+            mode: UiScaleMode::Fixed(2.0),
+            ..Default::default()
+        };
+        assert_eq!(ui_scale.scale_factor(&window(1920, 1080, 1.0)), 2.0);
+        assert_eq!(ui_scale.scale_factor(&window(800, 600, 3.0)), 2.0);
+    }
+
+    #[test]
+    fn match_dpi_should_follow_the_window_scale_factor() {
+        let ui_scale = UiScale::default();
+        assert_eq!(ui_scale.scale_factor(&window(1920, 1080, 1.0)), 1.0);
+        assert_eq!(ui_scale.scale_factor(&window(1920, 1080, 1.5)), 1.5);
+    }
+
+    #[test]
+    fn reference_resolution_should_fit_the_window() {
+        let ui_scale = reference_resolution(0.5);
+        assert_eq!(ui_scale.scale_factor(&window(1920, 1080, 1.0)), 1.0);
+        assert_eq!(ui_scale.scale_factor(&window(3840, 2160, 1.0)), 2.0);
+        // The scale factor reported by the OS doesn't matter
+        assert_eq!(ui_scale.scale_factor(&window(960, 540, 2.0)), 0.5);
+    }
+
+    #[test]
+    fn reference_resolution_should_blend_width_and_height() {
+        // Twice as wide and half as high as the reference resolution
+        let wide = window(3840, 540, 1.0);
+        assert_eq!(reference_resolution(0.0).scale_factor(&wide), 2.0);
+        assert_eq!(reference_resolution(1.0).scale_factor(&wide), 0.5);
+        assert_eq!(reference_resolution(0.5).scale_factor(&wide), 1.0);
+        // Out of range values are clamped
+        assert_eq!(reference_resolution(-1.0).scale_factor(&wide), 2.0);
+        assert_eq!(reference_resolution(2.0).scale_factor(&wide), 0.5);
+    }
+
+    #[test]
+    fn factor_should_multiply_the_mode() {
+        let ui_scale = UiScale {
+            mode: UiScaleMode::MatchDpi,
+            factor: 1.5,
+        };
+        assert_eq!(ui_scale.scale_factor(&window(1920, 1080, 2.0)), 3.0);
+    }
+
+    #[test]
+    fn invalid_scales_should_fall_back_to_one() {
+        let window = window(1920, 1080, 2.0);
+        for ui_scale in [
+            UiScale {
+                mode: UiScaleMode::Fixed(0.0),
+                ..Default::default()
+            },
+            UiScale {
+                mode: UiScaleMode::Fixed(f32::NAN),
+                ..Default::default()
+            },
+            UiScale {
+                factor: -1.0,
+                ..Default::default()
+            },
+            UiScale {
+                mode: UiScaleMode::ReferenceResolution {
+                    width: 0.0,
+                    height: 0.0,
+                    match_width_or_height: 0.5,
+                },
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(ui_scale.scale_factor(&window), 1.0, "{:?}", ui_scale);
+        }
+    }
+
+    #[test]
+    fn logical_to_ui_should_undo_the_window_scale_factor() {
+        let window = window(3840, 2160, 2.0);
+        // A UI pixel is a logical pixel
+        assert_eq!(UiScale::default().logical_to_ui(&window), 1.0);
+        // A UI pixel is a physical pixel, so there are two in each logical pixel
+        let fixed = UiScale {
+            mode: UiScaleMode::Fixed(1.0),
+            ..Default::default()
+        };
+        assert_eq!(fixed.logical_to_ui(&window), 2.0);
+        // The window is twice the reference resolution, which cancels out the scale factor
+        assert_eq!(reference_resolution(0.5).logical_to_ui(&window), 1.0);
+    }
+}
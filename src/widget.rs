@@ -1,7 +1,177 @@
-use crate::context::WidgetName;
+use std::any::TypeId;
+
+use bevy::{
+    ecs::system::{IntoSystem, System},
+    log::error,
+    prelude::{App, Entity, Resource},
+    utils::{HashMap, HashSet},
+};
+
+use crate::{context::WidgetName, widget_context::WidgetContext};
 
 pub trait Widget: Send + Sync {
     fn get_name(&self) -> WidgetName {
         WidgetName(std::any::type_name::<Self>().into())
     }
 }
+
+/// Adds widget registration to [`App`]
+pub trait RegisterWidget {
+    /// Registers the system that updates widgets with the props `T`
+    ///
+    /// This can be used in place of [`Context::add_widget_system`](crate::prelude::Context::add_widget_system),
+    /// and works whenever the [`Context`](crate::prelude::Context) is created. The system is
+    /// added to the context before its widgets are next updated, keyed by the name returned by
+    /// [`Widget::get_name`].
+    ///
+    /// The system is ignored, with an error, if another widget type was registered with the same
+    /// name, or if the context was given a system for that name with `Context::add_widget_system`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a system has already been registered for `T`.
+    fn register_widget<T, Params>(
+        &mut self,
+        system: impl IntoSystem<(WidgetContext, Entity), bool, Params>,
+    ) -> &mut Self
+    where
+        T: Widget + Default + 'static;
+}
+
+impl RegisterWidget for App {
+    fn register_widget<T, Params>(
+        &mut self,
+        system: impl IntoSystem<(WidgetContext, Entity), bool, Params>,
+    ) -> &mut Self
+    where
+        T: Widget + Default + 'static,
+    {
+        let name = T::default().get_name().0;
+        let mut registered = self
+            .world
+            .get_resource_or_insert_with(RegisteredWidgets::default);
+        if registered.widgets.contains_key(&TypeId::of::<T>()) {
+            panic!("the widget `{}` has already been registered", name);
+        }
+        if registered
+            .widgets
+            .values()
+            .any(|widget| widget.name == name)
+        {
+            error!(
+                "Another widget has already been registered with the name `{}`, so the system \
+                 registered for `{}` is ignored. Give it a different name with `Widget::get_name`.",
+                name,
+                std::any::type_name::<T>()
+            );
+            return self;
+        }
+        registered.widgets.insert(
+            TypeId::of::<T>(),
+            RegisteredWidget {
+                name,
+                system: Some(Box::new(IntoSystem::into_system(system))),
+            },
+        );
+        self
+    }
+}
+
+/// A widget registered with [`RegisterWidget::register_widget`]
+struct RegisteredWidget {
+    /// The name the widget's system is added to the [`Context`](crate::prelude::Context) with
+    name: String,
+    /// The widget's system, until it's added to the [`Context`](crate::prelude::Context)
+    system: Option<Box<dyn System<In = (WidgetContext, Entity), Out = bool>>>,
+}
+
+/// The widgets registered with [`RegisterWidget::register_widget`]
+#[derive(Resource, Default)]
+pub(crate) struct RegisteredWidgets {
+    /// The registered widgets, keyed by their type
+    widgets: HashMap<TypeId, RegisteredWidget>,
+    /// The names of the widgets with a system in the [`Context`](crate::prelude::Context), as of
+    /// the start of the latest update
+    ///
+    /// Unlike the context, this is available to widget systems.
+    pub(crate) systems: HashSet<String>,
+}
+
+impl RegisteredWidgets {
+    /// Takes the systems that have yet to be added to the [`Context`](crate::prelude::Context),
+    /// along with the names of their widgets
+    pub(crate) fn take_pending(
+        &mut self,
+    ) -> Vec<(
+        String,
+        Box<dyn System<In = (WidgetContext, Entity), Out = bool>>,
+    )> {
+        self.widgets
+            .values_mut()
+            .filter_map(|widget| Some((widget.name.clone(), widget.system.take()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{App, Entity, In};
+
+    use super::{RegisterWidget, RegisteredWidgets, Widget};
+    use crate::{
+        context::WidgetName,
+        widget_context::WidgetContext,
+        widgets::{Element, KButton},
+    };
+
+    fn widget_update(In((_, _)): In<(WidgetContext, Entity)>) -> bool {
+        true
+    }
+
+    #[test]
+    fn different_widgets_should_be_registered() {
+        App::new()
+            .register_widget::<KButton, _>(widget_update)
+            .register_widget::<Element, _>(widget_update);
+    }
+
+    #[test]
+    #[should_panic(expected = "has already been registered")]
+    fn registering_a_widget_twice_should_panic() {
+        App::new()
+            .register_widget::<KButton, _>(widget_update)
+            .register_widget::<KButton, _>(widget_update);
+    }
+
+    #[derive(Default)]
+    struct First;
+
+    impl Widget for First {
+        fn get_name(&self) -> WidgetName {
+            WidgetName("Same".into())
+        }
+    }
+
+    #[derive(Default)]
+    struct Second;
+
+    impl Widget for Second {
+        fn get_name(&self) -> WidgetName {
+            WidgetName("Same".into())
+        }
+    }
+
+    #[test]
+    fn widgets_with_the_same_name_should_keep_the_first_system() {
+        let mut app = App::new();
+        app.register_widget::<First, _>(widget_update)
+            .register_widget::<Second, _>(widget_update);
+
+        let mut registered = app.world.resource_mut::<RegisteredWidgets>();
+        let pending = registered.take_pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, "Same");
+        // Pending systems are only taken once
+        assert!(registered.take_pending().is_empty());
+    }
+}
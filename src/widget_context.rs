@@ -1,10 +1,14 @@
 use std::sync::{Arc, RwLock};
 
-use bevy::{prelude::Entity, utils::HashMap};
+use bevy::{
+    prelude::Entity,
+    utils::{HashMap, HashSet},
+};
 use morphorm::Hierarchy;
 
 use crate::{
     context_entities::ContextEntities, layout::LayoutCache, node::WrappedIndex, prelude::Tree,
+    widget_keys::WidgetKeys,
 };
 
 #[derive(Clone)]
@@ -14,6 +18,9 @@ pub struct WidgetContext {
     context_entities: ContextEntities,
     layout_cache: Arc<RwLock<LayoutCache>>,
     index: Arc<RwLock<HashMap<Entity, usize>>>,
+    keys: WidgetKeys,
+    /// The keyed widgets that have been matched in this render
+    claimed: Arc<RwLock<HashSet<Entity>>>,
 }
 
 impl WidgetContext {
@@ -21,6 +28,7 @@ impl WidgetContext {
         old_tree: Arc<RwLock<Tree>>,
         context_entities: ContextEntities,
         layout_cache: Arc<RwLock<LayoutCache>>,
+        keys: WidgetKeys,
     ) -> Self {
         Self {
             old_tree,
@@ -28,6 +36,8 @@ impl WidgetContext {
             context_entities,
             layout_cache,
             index: Arc::new(RwLock::new(HashMap::default())),
+            keys,
+            claimed: Arc::new(RwLock::new(HashSet::default())),
         }
     }
 
@@ -79,6 +89,14 @@ impl WidgetContext {
         None
     }
 
+    /// Gets the context entity of the given type created by this widget, ignoring its ancestors.
+    pub(crate) fn get_own_context_entity<T: Default + 'static>(
+        &self,
+        entity: Entity,
+    ) -> Option<Entity> {
+        self.context_entities.get_context_entity::<T>(entity)
+    }
+
     pub(crate) fn copy_from_point(&self, other_tree: &Arc<RwLock<Tree>>, entity: WrappedIndex) {
         if let Ok(other_tree) = other_tree.read() {
             if let Ok(mut tree) = self.new_tree.write() {
@@ -131,14 +149,71 @@ impl WidgetContext {
         0
     }
 
+    /// Gets the next child of the given entity from the previous render, by position
+    ///
+    /// Children with a key are skipped, since they're only matched by their key.
     pub fn get_child_at(&self, entity: Option<Entity>) -> Option<Entity> {
         if let Some(entity) = entity {
-            let children = self.get_children_old(entity);
+            let children = self
+                .get_children_old(entity)
+                .into_iter()
+                .filter(|child| !self.keys.contains(*child))
+                .collect::<Vec<_>>();
             return children.get(self.get_and_add_index(entity)).cloned();
         }
         None
     }
 
+    /// Gets the child of the given entity that had the given key in the previous render
+    ///
+    /// This is used by `rsx!` for widgets with a `key` attribute. If siblings share a key, they
+    /// are matched in order. A child that was removed but is still playing its exit animation is
+    /// matched too, which brings it back.
+    pub fn get_child_with_key(&self, entity: Option<Entity>, key: &str) -> Option<Entity> {
+        let entity = entity?;
+        let mut claimed = self.claimed.write().ok()?;
+        let old_children = self.get_children_old(entity);
+        let matching = old_children
+            .iter()
+            .copied()
+            .filter(|child| self.keys.matches(*child, entity, key))
+            .collect::<Vec<_>>();
+        if matching.iter().any(|child| claimed.contains(child)) {
+            bevy::log::warn!(
+                "The key `{}` is used by more than one child of {:?}, so those children are \
+                 matched in order",
+                key,
+                entity
+            );
+        }
+
+        let child = matching
+            .into_iter()
+            .find(|child| !claimed.contains(child))
+            .or_else(|| {
+                // Only exiting widgets keep their key once they're no longer a child
+                self.keys
+                    .find(entity, key)
+                    .into_iter()
+                    .find(|child| !old_children.contains(child) && !claimed.contains(child))
+            });
+        if let Some(child) = child {
+            claimed.insert(child);
+        }
+        child
+    }
+
+    /// Sets the key of a widget within the given parent, so it can be matched with
+    /// [`get_child_with_key`](Self::get_child_with_key) in the next render
+    pub fn set_key(&self, parent: Option<Entity>, entity: Entity, key: String) {
+        if let Some(parent) = parent {
+            self.keys.insert(parent, entity, key);
+            if let Ok(mut claimed) = self.claimed.write() {
+                claimed.insert(entity);
+            }
+        }
+    }
+
     pub fn remove_children(&self, children_to_remove: Vec<Entity>) {
         if let Ok(mut tree) = self.new_tree.write() {
             for child in children_to_remove.iter() {
@@ -183,3 +258,95 @@ impl WidgetContext {
             .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use bevy::prelude::Entity;
+
+    use super::WidgetContext;
+    use crate::{
+        context_entities::ContextEntities, node::WrappedIndex, tree::Tree, widget_keys::WidgetKeys,
+    };
+
+    /// Creates a context whose previous render gave a root widget children with the given keys
+    fn rendered(keys: &[Option<&str>]) -> (WidgetContext, Entity, Vec<Entity>) {
+        let root = Entity::from_raw(0);
+        let mut tree = Tree::default();
+        tree.add(WrappedIndex(root), None);
+        let widget_keys = WidgetKeys::new();
+        let children = keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let child = Entity::from_raw(index as u32 + 1);
+                tree.add(WrappedIndex(child), Some(WrappedIndex(root)));
+                if let Some(key) = key {
+                    widget_keys.insert(root, child, key.to_string());
+                }
+                child
+            })
+            .collect();
+
+        let widget_context = WidgetContext::new(
+            Arc::new(RwLock::new(tree)),
+            ContextEntities::new(),
+            Default::default(),
+            widget_keys,
+        );
+        (widget_context, root, children)
+    }
+
+    #[test]
+    fn keyed_children_should_be_matched_by_key() {
+        let (widget_context, root, children) = rendered(&[Some("a"), Some("b"), Some("c")]);
+
+        // `c` moves to the front, `b` is removed, and `d` is added
+        assert_eq!(
+            widget_context.get_child_with_key(Some(root), "c"),
+            Some(children[2])
+        );
+        assert_eq!(
+            widget_context.get_child_with_key(Some(root), "a"),
+            Some(children[0])
+        );
+        assert_eq!(widget_context.get_child_with_key(Some(root), "d"), None);
+    }
+
+    #[test]
+    fn keys_should_be_scoped_to_their_parent() {
+        let (widget_context, root, children) = rendered(&[Some("a")]);
+        widget_context.set_key(Some(children[0]), children[0], "b".to_string());
+
+        assert_eq!(widget_context.get_child_with_key(Some(root), "b"), None);
+    }
+
+    #[test]
+    fn unkeyed_children_should_be_matched_by_position_among_unkeyed_children() {
+        let (widget_context, root, children) = rendered(&[None, Some("a"), None]);
+
+        assert_eq!(widget_context.get_child_at(Some(root)), Some(children[0]));
+        assert_eq!(widget_context.get_child_at(Some(root)), Some(children[2]));
+        assert_eq!(widget_context.get_child_at(Some(root)), None);
+        assert_eq!(
+            widget_context.get_child_with_key(Some(root), "a"),
+            Some(children[1])
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_should_be_matched_in_order() {
+        let (widget_context, root, children) = rendered(&[Some("a"), Some("a")]);
+
+        assert_eq!(
+            widget_context.get_child_with_key(Some(root), "a"),
+            Some(children[0])
+        );
+        assert_eq!(
+            widget_context.get_child_with_key(Some(root), "a"),
+            Some(children[1])
+        );
+        assert_eq!(widget_context.get_child_with_key(Some(root), "a"), None);
+    }
+}
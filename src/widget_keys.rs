@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use bevy::prelude::Entity;
+use dashmap::DashMap;
+
+/// The keys given to widgets with the `key` attribute of `rsx!`
+///
+/// A key is scoped to the parent of its widget, and lets the widget be matched with the one that
+/// had the same key in the previous render, wherever it moved among its siblings.
+#[derive(Debug, Clone)]
+pub struct WidgetKeys {
+    keys: Arc<DashMap<Entity, (Entity, String)>>,
+}
+
+impl WidgetKeys {
+    pub fn new() -> Self {
+        Self {
+            keys: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Sets the key of a widget within the given parent
+    pub fn insert(&self, parent: Entity, entity: Entity, key: String) {
+        self.keys.insert(entity, (parent, key));
+    }
+
+    /// Gets the parent and key of a widget, if it has a key
+    pub fn get(&self, entity: Entity) -> Option<(Entity, String)> {
+        self.keys.get(&entity).map(|entry| entry.value().clone())
+    }
+
+    /// Returns true if the given widget has a key
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.keys.contains_key(&entity)
+    }
+
+    /// Returns true if the given widget has the given key within the given parent
+    pub fn matches(&self, entity: Entity, parent: Entity, key: &str) -> bool {
+        self.keys.get(&entity).map_or(false, |entry| {
+            let (entry_parent, entry_key) = entry.value();
+            *entry_parent == parent && entry_key == key
+        })
+    }
+
+    /// Gets every widget with the given key within the given parent
+    pub fn find(&self, parent: Entity, key: &str) -> Vec<Entity> {
+        self.keys
+            .iter()
+            .filter(|entry| {
+                let (entry_parent, entry_key) = entry.value();
+                *entry_parent == parent && entry_key == key
+            })
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
+    /// Forgets the key of a widget
+    pub fn remove(&self, entity: Entity) {
+        self.keys.remove(&entity);
+    }
+}
@@ -1,18 +1,18 @@
-use bevy::{
-    prelude::{Bundle, Commands, Component, Entity, In, Or, Query, Res, With},
-    window::Windows,
+use bevy::prelude::{
+    Bundle, Commands, Component, Entity, In, Or, Query, Reflect, ReflectComponent, Res, With,
 };
-use morphorm::Units;
 
 use crate::{
     children::KChildren,
     context::{Mounted, WidgetName},
     prelude::WidgetContext,
-    styles::{KStyle, RenderCommand, StyleProp},
+    styles::{KStyle, RenderCommand, StyleProp, Units},
     widget::Widget,
+    WindowSize,
 };
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct KayakApp;
 
 impl Widget for KayakApp {}
@@ -40,18 +40,17 @@ impl Default for KayakAppBundle {
 pub fn app_update(
     In((widget_context, entity)): In<(WidgetContext, Entity)>,
     _: Commands,
-    windows: Res<Windows>,
+    window_size: Res<WindowSize>,
     mut query: Query<(&mut KStyle, &KChildren), Or<(With<KayakApp>, With<Mounted>)>>,
 ) -> bool {
     let mut has_changed = false;
-    let primary_window = windows.get_primary().unwrap();
     if let Ok((mut app_style, children)) = query.get_mut(entity) {
-        if app_style.width != StyleProp::Value(Units::Pixels(primary_window.width())) {
-            app_style.width = StyleProp::Value(Units::Pixels(primary_window.width()));
+        if app_style.width != StyleProp::Value(Units::Pixels(window_size.0)) {
+            app_style.width = StyleProp::Value(Units::Pixels(window_size.0));
             has_changed = true;
         }
-        if app_style.height != StyleProp::Value(Units::Pixels(primary_window.height())) {
-            app_style.height = StyleProp::Value(Units::Pixels(primary_window.height()));
+        if app_style.height != StyleProp::Value(Units::Pixels(window_size.1)) {
+            app_style.height = StyleProp::Value(Units::Pixels(window_size.1));
             has_changed = true;
         }
 
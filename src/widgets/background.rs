@@ -1,4 +1,6 @@
-use bevy::prelude::{Bundle, Changed, Commands, Component, Entity, In, Or, Query, With};
+use bevy::prelude::{
+    Bundle, Changed, Commands, Component, Entity, In, Or, Query, Reflect, ReflectComponent, With,
+};
 
 use crate::{
     children::KChildren,
@@ -9,7 +11,8 @@ use crate::{
     widget::Widget,
 };
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Background;
 
 impl Widget for Background {}
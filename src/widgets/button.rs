@@ -1,14 +1,22 @@
-use bevy::{prelude::{Bundle, Changed, Color, Commands, Component, Entity, In, Or, Query, With}, window::CursorIcon};
+use bevy::{
+    prelude::{
+        Bundle, Changed, Commands, Component, Entity, In, Or, ParamSet, Query, Reflect,
+        ReflectComponent, Res, With,
+    },
+    window::CursorIcon,
+};
 
 use crate::{
     context::{Mounted, WidgetName},
     on_event::OnEvent,
     prelude::{KChildren, Units, WidgetContext},
-    styles::{Corner, KStyle, RenderCommand, StyleProp, KCursorIcon},
+    styles::{Corner, KCursorIcon, KStyle, RenderCommand, StyleProp},
+    theme::{BaseStyle, KayakTheme},
     widget::Widget,
 };
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct KButton;
 
 #[derive(Bundle)]
@@ -36,29 +44,58 @@ impl Widget for KButton {}
 
 pub fn button_update(
     In((widget_context, entity)): In<(WidgetContext, Entity)>,
-    _: Commands,
-    mut query: Query<(&mut KStyle, &KChildren), Or<(Changed<KButton>, With<Mounted>)>>,
+    mut commands: Commands,
+    global_theme: Res<KayakTheme>,
+    theme_query: Query<&KayakTheme>,
+    changed_themes: Query<Entity, Changed<KayakTheme>>,
+    mut query: ParamSet<(
+        Query<Entity, Or<(Changed<KButton>, With<Mounted>)>>,
+        Query<(&mut KStyle, &KChildren, Option<&BaseStyle>)>,
+    )>,
 ) -> bool {
-    if let Ok((mut style, children)) = query.get_mut(entity) {
+    let props_changed = query.p0().get(entity).is_ok();
+    let theme_changed = KayakTheme::is_changed(
+        &widget_context,
+        entity,
+        &theme_query,
+        &changed_themes,
+        &global_theme,
+    );
+    if !props_changed && !theme_changed {
+        return false;
+    }
+
+    if let Ok((mut style, children, base_style)) = query.p1().get_mut(entity) {
+        let theme = KayakTheme::resolve(&widget_context, entity, &theme_query, &global_theme);
+
+        // Restyle from the styles given by the parent, not the ones merged with the old theme
+        let base_style = match base_style {
+            Some(base_style) if !props_changed => base_style.0.clone(),
+            _ => style.clone(),
+        };
+
         *style = KStyle::default()
             .with_style(KStyle {
                 render_command: StyleProp::Value(RenderCommand::Quad),
                 ..Default::default()
             })
-            .with_style(style.clone())
+            .with_style(&base_style)
             .with_style(KStyle {
                 render_command: StyleProp::Value(RenderCommand::Quad),
-                background_color: StyleProp::Value(Color::rgba(0.0781, 0.0898, 0.101, 1.0)),
-                border_radius: StyleProp::Value(Corner::all(5.0)),
-                height: StyleProp::Value(Units::Pixels(45.0)),
+                background_color: StyleProp::Value(theme.palette.surface),
+                border_radius: StyleProp::Value(Corner::all(theme.radii.button)),
+                color: StyleProp::Value(theme.palette.text),
+                height: StyleProp::Value(Units::Auto),
                 padding_left: StyleProp::Value(Units::Stretch(1.0)),
                 padding_right: StyleProp::Value(Units::Stretch(1.0)),
-                padding_bottom: StyleProp::Value(Units::Stretch(1.0)),
-                padding_top: StyleProp::Value(Units::Stretch(1.0)),
+                padding_bottom: StyleProp::Value(Units::Pixels(theme.spacing.button_padding)),
+                padding_top: StyleProp::Value(Units::Pixels(theme.spacing.button_padding)),
                 cursor: StyleProp::Value(KCursorIcon(CursorIcon::Hand)),
                 ..Default::default()
             });
 
+        commands.entity(entity).insert(BaseStyle(base_style));
+
         children.process(&widget_context, Some(entity));
 
         return true;
@@ -1,4 +1,6 @@
-use bevy::prelude::{Bundle, Changed, Commands, Component, Entity, In, Or, Query, With};
+use bevy::prelude::{
+    Bundle, Changed, Commands, Component, Entity, In, Or, Query, Reflect, ReflectComponent, With,
+};
 
 use crate::{
     children::KChildren,
@@ -8,7 +10,8 @@ use crate::{
     widget::Widget,
 };
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Clip;
 
 impl Widget for Clip {}
@@ -1,4 +1,6 @@
-use bevy::prelude::{Bundle, Changed, Component, Entity, Handle, In, Or, Query, With};
+use bevy::prelude::{
+    Bundle, Changed, Component, Entity, Handle, In, Or, Query, Reflect, ReflectComponent, With,
+};
 
 use crate::{
     context::{Mounted, WidgetName},
@@ -7,7 +9,8 @@ use crate::{
     widget::Widget,
 };
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct Image(pub Handle<bevy::prelude::Image>);
 
 impl Widget for Image {}
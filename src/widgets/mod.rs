@@ -11,6 +11,8 @@ mod scroll;
 mod text;
 mod text_box;
 mod texture_atlas;
+mod theme_provider;
+mod ui_asset;
 mod window;
 
 pub use app::{KayakApp, KayakAppBundle};
@@ -31,6 +33,8 @@ pub use scroll::{
 pub use text::{TextProps, TextWidgetBundle};
 pub use text_box::{TextBoxBundle, TextBoxProps};
 pub use texture_atlas::{TextureAtlas, TextureAtlasBundle};
+pub use theme_provider::{KayakThemeProvider, KayakThemeProviderBundle};
+pub use ui_asset::{UiAssetBundle, UiAssetProps};
 pub use window::{KWindow, WindowBundle};
 
 use app::app_update;
@@ -45,40 +49,77 @@ use scroll::{
     scroll_content::update_scroll_content, scroll_context::update_scroll_context,
 };
 use text::text_update;
-use text_box::update_text_box;
+use text_box::{update_text_box, TextBoxState};
 use texture_atlas::update_texture_atlas;
+use theme_provider::update_theme_provider;
+use ui_asset::{reload_ui_assets, update_ui_asset};
 use window::window_update;
 
-use crate::{context::Context, widget::Widget};
+use crate::{
+    styles::KStyle,
+    theme::{KayakTheme, ThemeFontSizes, ThemePalette, ThemeRadii, ThemeSpacing},
+    ui_asset::{UiAsset, UiAssetLoader, UiWidgetRegistry},
+    widget::RegisterWidget,
+};
 
 pub struct KayakWidgets;
 
 impl Plugin for KayakWidgets {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_startup_system_to_stage(StartupStage::PostStartup, add_widget_systems);
+        app.init_resource::<KayakTheme>()
+            .init_resource::<UiWidgetRegistry>()
+            .add_asset::<UiAsset>()
+            .init_asset_loader::<UiAssetLoader>()
+            .add_system(reload_ui_assets)
+            .register_widget::<KayakApp, _>(app_update)
+            .register_widget::<KButton, _>(button_update)
+            .register_widget::<TextProps, _>(text_update)
+            .register_widget::<KWindow, _>(window_update)
+            .register_widget::<Background, _>(update_background)
+            .register_widget::<Clip, _>(update_clip)
+            .register_widget::<Image, _>(update_image)
+            .register_widget::<TextureAtlas, _>(update_texture_atlas)
+            .register_widget::<NinePatch, _>(update_nine_patch)
+            .register_widget::<Element, _>(update_element)
+            .register_widget::<ScrollBarProps, _>(update_scroll_bar)
+            .register_widget::<ScrollContentProps, _>(update_scroll_content)
+            .register_widget::<ScrollBoxProps, _>(update_scroll_box)
+            .register_widget::<ScrollContextProvider, _>(update_scroll_context)
+            .register_widget::<TextBoxProps, _>(update_text_box)
+            .register_widget::<KayakThemeProvider, _>(update_theme_provider)
+            .register_widget::<UiAssetProps, _>(update_ui_asset);
+        register_types(app);
     }
 }
 
-fn add_widget_systems(mut context: ResMut<Context>) {
-    context.add_widget_system(KayakApp::default().get_name(), app_update);
-    context.add_widget_system(KButton::default().get_name(), button_update);
-    context.add_widget_system(TextProps::default().get_name(), text_update);
-    context.add_widget_system(KWindow::default().get_name(), window_update);
-    context.add_widget_system(Background::default().get_name(), update_background);
-    context.add_widget_system(Clip::default().get_name(), update_clip);
-    context.add_widget_system(Image::default().get_name(), update_image);
-    context.add_widget_system(TextureAtlas::default().get_name(), update_texture_atlas);
-    context.add_widget_system(NinePatch::default().get_name(), update_nine_patch);
-    context.add_widget_system(Element::default().get_name(), update_element);
-    context.add_widget_system(ScrollBarProps::default().get_name(), update_scroll_bar);
-    context.add_widget_system(
-        ScrollContentProps::default().get_name(),
-        update_scroll_content,
-    );
-    context.add_widget_system(ScrollBoxProps::default().get_name(), update_scroll_box);
-    context.add_widget_system(
-        ScrollContextProvider::default().get_name(),
-        update_scroll_context,
-    );
-    context.add_widget_system(TextBoxProps::default().get_name(), update_text_box);
+/// Registers the props, states, and contexts of the built-in widgets for reflection
+pub(crate) fn register_types(app: &mut App) {
+    app.register_type::<KayakApp>()
+        .register_type::<Background>()
+        .register_type::<KButton>()
+        .register_type::<Clip>()
+        .register_type::<Element>()
+        .register_type::<Image>()
+        .register_type::<NinePatch>()
+        .register_type::<TextProps>()
+        .register_type::<TextBoxProps>()
+        .register_type::<TextBoxState>()
+        .register_type::<TextureAtlas>()
+        .register_type::<KayakThemeProvider>()
+        .register_type::<UiAssetProps>()
+        .register_type::<KWindow>()
+        .register_type::<ScrollBarProps>()
+        .register_type::<ScrollBoxProps>()
+        .register_type::<ScrollContentProps>()
+        .register_type::<ScrollContext>()
+        .register_type::<ScrollMode>()
+        .register_type::<ScrollContextProvider>()
+        .register_type::<KayakTheme>()
+        .register_type::<ThemePalette>()
+        .register_type::<ThemeFontSizes>()
+        .register_type::<ThemeRadii>()
+        .register_type::<ThemeSpacing>()
+        .register_type::<Option<KStyle>>()
+        .register_type::<Option<Color>>()
+        .register_type::<Option<String>>();
 }
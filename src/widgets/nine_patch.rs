@@ -1,5 +1,6 @@
 use bevy::prelude::{
-    Bundle, Changed, Commands, Component, Entity, Handle, Image, In, Or, Query, With,
+    Bundle, Changed, Commands, Component, Entity, Handle, Image, In, Or, Query, Reflect,
+    ReflectComponent, With,
 };
 
 use crate::{
@@ -10,7 +11,8 @@ use crate::{
     widget::Widget,
 };
 
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
 pub struct NinePatch {
     /// The handle to image
     pub handle: Handle<Image>,
@@ -1,5 +1,6 @@
 use bevy::prelude::{
-    Bundle, Changed, Color, Commands, Component, Entity, In, Or, ParamSet, Query, With,
+    Bundle, Changed, Color, Commands, Component, Entity, In, Or, ParamSet, Query, Reflect,
+    ReflectComponent, Res, With,
 };
 use kayak_ui_macros::rsx;
 
@@ -10,6 +11,7 @@ use crate::{
     on_event::OnEvent,
     prelude::{KChildren, WidgetContext},
     styles::{Corner, Edge, KStyle, PositionType, RenderCommand, Units},
+    theme::KayakTheme,
     widget::Widget,
     widgets::{BackgroundBundle, ClipBundle},
 };
@@ -17,7 +19,8 @@ use crate::{
 use super::{map_range, scroll_context::ScrollContext};
 
 /// Props used by the [`ScrollBar`] widget
-#[derive(Component, Default, Debug, PartialEq, Clone)]
+#[derive(Component, Default, Debug, PartialEq, Clone, Reflect)]
+#[reflect(Component)]
 pub struct ScrollBarProps {
     /// If true, disables the ability to drag
     pub disabled: bool,
@@ -26,10 +29,14 @@ pub struct ScrollBarProps {
     /// The thickness of the scrollbar in pixels
     pub thickness: f32,
     /// The color of the scrollbar thumb
+    ///
+    /// Defaults to the theme's `scrollbar_thumb` color
     pub thumb_color: Option<Color>,
     /// The styles of the scrollbar thumb
     pub thumb_styles: Option<KStyle>,
     /// The color of the scrollbar track
+    ///
+    /// Defaults to the theme's `scrollbar_track` color
     pub track_color: Option<Color>,
     /// The styles of the scrollbar track
     pub track_styles: Option<KStyle>,
@@ -57,13 +64,23 @@ impl Default for ScrollBarBundle {
 pub fn update_scroll_bar(
     In((widget_context, entity)): In<(WidgetContext, Entity)>,
     mut commands: Commands,
+    global_theme: Res<KayakTheme>,
+    theme_query: Query<&KayakTheme>,
+    changed_themes: Query<Entity, Changed<KayakTheme>>,
     mut query: ParamSet<(
         Query<Entity, Or<(Changed<ScrollBarProps>, With<Mounted>)>>,
         Query<(&ScrollBarProps, &mut KStyle)>,
     )>,
     mut context_query: ParamSet<(Query<Entity, Changed<ScrollContext>>, Query<&ScrollContext>)>,
 ) -> bool {
-    if !context_query.p0().is_empty() | !query.p0().is_empty() {
+    let theme_changed = KayakTheme::is_changed(
+        &widget_context,
+        entity,
+        &theme_query,
+        &changed_themes,
+        &global_theme,
+    );
+    if !context_query.p0().is_empty() | !query.p0().is_empty() | theme_changed {
         if let Ok((scrollbar, mut styles)) = query.p1().get_mut(entity) {
             if let Some(context_entity) = widget_context.get_context_entity::<ScrollContext>(entity)
             {
@@ -76,6 +93,8 @@ pub fn update_scroll_bar(
                     let scrollable_height = scroll_context.scrollable_height();
 
                     let layout = widget_context.get_layout(entity).unwrap_or_default();
+                    let theme =
+                        KayakTheme::resolve(&widget_context, entity, &theme_query, &global_theme);
 
                     // === Configuration === //
                     // let disabled = scrollbar.disabled;
@@ -84,11 +103,11 @@ pub fn update_scroll_bar(
                     let thickness = scrollbar.thickness;
                     let thumb_color = scrollbar
                         .thumb_color
-                        .unwrap_or_else(|| Color::rgba(0.2981, 0.3098, 0.321, 0.95));
+                        .unwrap_or(theme.palette.scrollbar_thumb);
                     let thumb_styles = scrollbar.thumb_styles.clone();
                     let track_color = scrollbar
                         .track_color
-                        .unwrap_or_else(|| Color::rgba(0.1581, 0.1758, 0.191, 0.15));
+                        .unwrap_or(theme.palette.scrollbar_track);
                     let track_styles = scrollbar.track_styles.clone();
                     // The size of the thumb as a percentage
                     let thumb_size_percent = (if scrollbar.horizontal {
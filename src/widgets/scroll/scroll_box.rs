@@ -1,5 +1,6 @@
 use bevy::prelude::{
-    Bundle, Changed, Color, Commands, Component, Entity, In, Or, ParamSet, Query, With,
+    Bundle, Changed, Color, Commands, Component, Entity, In, Or, ParamSet, Query, Reflect,
+    ReflectComponent, With,
 };
 
 use crate::{
@@ -11,7 +12,7 @@ use crate::{
     layout::{GeometryChanged, LayoutEvent},
     on_event::OnEvent,
     on_layout::OnLayout,
-    prelude::{constructor, rsx, WidgetContext},
+    prelude::{rsx, WidgetContext},
     styles::{KStyle, LayoutType, PositionType, RenderCommand, Units},
     widget::Widget,
     widgets::{
@@ -25,7 +26,8 @@ use crate::{
 
 use super::scroll_context::ScrollContext;
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct ScrollBoxProps {
     /// If true, always shows scrollbars even when there's nothing to scroll
     ///
@@ -234,36 +236,32 @@ pub fn update_scroll_box(
                                     />
                                 </ClipBundle>
                                 {if !hide_horizontal {
-                                    constructor! {
-                                        <ScrollBarBundle
-                                            scrollbar_props={ScrollBarProps {
-                                                disabled: disable_horizontal,
-                                                horizontal: true,
-                                                thickness: hori_thickness,
-                                                thumb_color: thumb_color,
-                                                thumb_styles: thumb_styles.clone(),
-                                                track_color: track_color,
-                                                track_styles: track_styles.clone(),
-                                                ..Default::default()
-                                            }}
-                                        />
-                                    }
-                                }}
-                            </ElementBundle>
-                            {if !hide_vertical {
-                                constructor! {
                                     <ScrollBarBundle
                                         scrollbar_props={ScrollBarProps {
-                                            disabled: disable_vertical,
+                                            disabled: disable_horizontal,
+                                            horizontal: true,
                                             thickness: hori_thickness,
-                                            thumb_color: thumb_color.clone(),
+                                            thumb_color: thumb_color,
                                             thumb_styles: thumb_styles.clone(),
                                             track_color: track_color,
                                             track_styles: track_styles.clone(),
                                             ..Default::default()
                                         }}
                                     />
-                                }
+                                }}
+                            </ElementBundle>
+                            {if !hide_vertical {
+                                <ScrollBarBundle
+                                    scrollbar_props={ScrollBarProps {
+                                        disabled: disable_vertical,
+                                        thickness: hori_thickness,
+                                        thumb_color: thumb_color.clone(),
+                                        thumb_styles: thumb_styles.clone(),
+                                        track_color: track_color,
+                                        track_styles: track_styles.clone(),
+                                        ..Default::default()
+                                    }}
+                                />
                             }}
                         </ElementBundle>
                     }
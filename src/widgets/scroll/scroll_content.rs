@@ -1,4 +1,6 @@
-use bevy::prelude::{Bundle, Changed, Component, Entity, In, Or, ParamSet, Query, With};
+use bevy::prelude::{
+    Bundle, Changed, Component, Entity, In, Or, ParamSet, Query, Reflect, ReflectComponent, With,
+};
 
 use crate::{
     children::KChildren,
@@ -13,7 +15,8 @@ use crate::{
 
 use super::scroll_context::ScrollContext;
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct ScrollContentProps;
 
 impl Widget for ScrollContentProps {}
@@ -1,4 +1,7 @@
-use bevy::prelude::{Bundle, Changed, Commands, Component, Entity, In, Or, Query, Vec2, With};
+use bevy::prelude::{
+    Bundle, Changed, Commands, Component, Entity, FromReflect, In, Or, Query, Reflect,
+    ReflectComponent, Vec2, With,
+};
 
 use crate::{
     children::KChildren,
@@ -9,7 +12,8 @@ use crate::{
 };
 
 /// Context data provided by a [`ScrollBox`](crate::ScrollBox) widget
-#[derive(Component, Default, Debug, Copy, Clone, PartialEq)]
+#[derive(Component, Default, Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
+#[reflect(Component)]
 pub struct ScrollContext {
     pub(super) scroll_x: f32,
     pub(super) scroll_y: f32,
@@ -26,7 +30,7 @@ pub struct ScrollContext {
 }
 
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, FromReflect)]
 pub enum ScrollMode {
     /// Clamps the scroll offset to stay within the scroll range
     Clamped,
@@ -134,7 +138,8 @@ impl ScrollContext {
     }
 }
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct ScrollContextProvider {
     initial_value: ScrollContext,
 }
@@ -8,7 +8,8 @@ use crate::{
     widget::Widget,
 };
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct TextProps {
     /// The string to display
     pub content: String,
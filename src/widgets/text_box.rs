@@ -1,5 +1,6 @@
 use bevy::prelude::{
-    Bundle, Changed, Color, Commands, Component, Entity, In, Or, ParamSet, Query, With,
+    Bundle, Changed, Commands, Component, Entity, In, Or, ParamSet, Query, Reflect,
+    ReflectComponent, Res, With,
 };
 use kayak_ui_macros::rsx;
 
@@ -11,6 +12,7 @@ use crate::{
     on_layout::OnLayout,
     prelude::{KChildren, OnChange, WidgetContext},
     styles::{Corner, KStyle, RenderCommand, StyleProp, Units},
+    theme::{BaseStyle, KayakTheme},
     widget::Widget,
     widgets::{
         text::{TextProps, TextWidgetBundle},
@@ -20,7 +22,8 @@ use crate::{
 };
 
 /// Props used by the [`TextBox`] widget
-#[derive(Component, Default, Debug, PartialEq, Clone)]
+#[derive(Component, Default, Debug, PartialEq, Clone, Reflect)]
+#[reflect(Component)]
 pub struct TextBoxProps {
     /// If true, prevents the widget from being focused (and consequently edited)
     pub disabled: bool,
@@ -33,7 +36,8 @@ pub struct TextBoxProps {
     pub value: String,
 }
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct TextBoxState {
     pub focused: bool,
 }
@@ -83,14 +87,34 @@ impl Default for TextBoxBundle {
 pub fn update_text_box(
     In((widget_context, entity)): In<(WidgetContext, Entity)>,
     mut commands: Commands,
+    global_theme: Res<KayakTheme>,
+    theme_query: Query<&KayakTheme>,
+    changed_themes: Query<Entity, Changed<KayakTheme>>,
     mut query: ParamSet<(
         Query<Entity, Or<(Changed<TextBoxProps>, Changed<KStyle>, With<Mounted>)>>,
-        Query<(&mut KStyle, &TextBoxProps, &mut OnEvent, &OnChange)>,
+        Query<(
+            &mut KStyle,
+            &TextBoxProps,
+            &mut OnEvent,
+            &OnChange,
+            Option<&BaseStyle>,
+        )>,
+        Query<Entity, Or<(Changed<TextBoxProps>, With<Mounted>)>>,
     )>,
     mut context_query: ParamSet<(Query<Entity, Changed<TextBoxState>>, Query<&TextBoxState>)>,
 ) -> bool {
-    if !query.p0().is_empty() || !context_query.p0().is_empty() {
-        if let Ok((mut styles, text_box, mut on_event, on_change)) = query.p1().get_mut(entity) {
+    let theme_changed = KayakTheme::is_changed(
+        &widget_context,
+        entity,
+        &theme_query,
+        &changed_themes,
+        &global_theme,
+    );
+    if !query.p0().is_empty() || !context_query.p0().is_empty() || theme_changed {
+        let props_changed = query.p2().get(entity).is_ok();
+        if let Ok((mut styles, text_box, mut on_event, on_change, base_style)) =
+            query.p1().get_mut(entity)
+        {
             let state_entity = widget_context.get_context_entity::<TextBoxState>(entity);
             if state_entity.is_none() {
                 let state_entity = commands.spawn(TextBoxState::default()).id();
@@ -100,6 +124,14 @@ pub fn update_text_box(
 
             let state_entity = state_entity.unwrap();
 
+            let theme = KayakTheme::resolve(&widget_context, entity, &theme_query, &global_theme);
+
+            // Restyle from the styles given by the parent, not the ones merged with the old theme
+            let base_style = match base_style {
+                Some(base_style) if !props_changed => base_style.0.clone(),
+                _ => styles.clone(),
+            };
+
             *styles = KStyle::default()
                 // Required styles
                 .with_style(KStyle {
@@ -107,22 +139,24 @@ pub fn update_text_box(
                     ..Default::default()
                 })
                 // Apply any prop-given styles
-                .with_style(&*styles)
+                .with_style(&base_style)
                 // If not set by props, apply these styles
                 .with_style(KStyle {
                     top: Units::Pixels(0.0).into(),
                     bottom: Units::Pixels(0.0).into(),
-                    height: Units::Pixels(26.0).into(),
+                    height: Units::Auto.into(),
                     // cursor: CursorIcon::Text.into(),
                     ..Default::default()
                 });
 
+            commands.entity(entity).insert(BaseStyle(base_style));
+
             let background_styles = KStyle {
-                background_color: StyleProp::Value(Color::rgba(0.176, 0.196, 0.215, 1.0)),
-                border_radius: Corner::all(5.0).into(),
-                height: Units::Pixels(26.0).into(),
-                padding_left: Units::Pixels(5.0).into(),
-                padding_right: Units::Pixels(5.0).into(),
+                background_color: StyleProp::Value(theme.palette.input),
+                border_radius: Corner::all(theme.radii.text_box).into(),
+                height: Units::Auto.into(),
+                padding_left: Units::Pixels(theme.spacing.text_box_padding).into(),
+                padding_right: Units::Pixels(theme.spacing.text_box_padding).into(),
                 ..Default::default()
             };
 
@@ -177,21 +211,24 @@ pub fn update_text_box(
             rsx! {
                 <BackgroundBundle styles={background_styles}>
                     <ClipBundle styles={KStyle {
-                        height: Units::Pixels(26.0).into(),
+                        height: Units::Auto.into(),
                         padding_left: StyleProp::Value(Units::Stretch(0.0)),
                         padding_right: StyleProp::Value(Units::Stretch(0.0)),
-                        padding_bottom: StyleProp::Value(Units::Stretch(1.0)),
-                        padding_top: StyleProp::Value(Units::Stretch(1.0)),
+                        padding_bottom: Units::Pixels(theme.spacing.text_box_vertical_padding).into(),
+                        padding_top: Units::Pixels(theme.spacing.text_box_vertical_padding).into(),
                         ..Default::default()
                     }}>
                         <TextWidgetBundle
                             text={TextProps {
                                 content: text_box.value.clone(),
-                                size: 14.0,
-                                line_height: Some(18.0),
+                                size: theme.font_sizes.body,
+                                line_height: Some(theme.font_sizes.body_line_height),
+                                ..Default::default()
+                            }}
+                            styles={KStyle {
+                                color: theme.palette.text.into(),
                                 ..Default::default()
                             }}
-                            // styles={text_styles}
                         />
                     </ClipBundle>
                 </BackgroundBundle>
@@ -1,4 +1,7 @@
-use bevy::prelude::{Bundle, Changed, Component, Entity, Handle, Image, In, Or, Query, Vec2, With};
+use bevy::prelude::{
+    Bundle, Changed, Component, Entity, Handle, Image, In, Or, Query, Reflect, ReflectComponent,
+    Vec2, With,
+};
 
 use crate::{
     context::{Mounted, WidgetName},
@@ -22,7 +25,8 @@ use crate::{
 /// | `on_layout` | ✅        |
 /// | `focusable` | ✅        |
 ///
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
 pub struct TextureAtlas {
     /// The handle to image
     pub handle: Handle<Image>,
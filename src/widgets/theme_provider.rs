@@ -0,0 +1,77 @@
+use bevy::prelude::{
+    Bundle, Changed, Commands, Component, Entity, In, Or, Query, Reflect, ReflectComponent, With,
+};
+
+use crate::{
+    children::KChildren,
+    context::{Mounted, WidgetName},
+    prelude::WidgetContext,
+    styles::KStyle,
+    theme::KayakTheme,
+    widget::Widget,
+};
+
+/// Overrides the [`KayakTheme`] for all of its descendants
+#[derive(Component, Default, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct KayakThemeProvider {
+    /// The theme to provide
+    pub theme: KayakTheme,
+}
+
+impl Widget for KayakThemeProvider {}
+
+#[derive(Bundle)]
+pub struct KayakThemeProviderBundle {
+    pub theme_provider: KayakThemeProvider,
+    pub children: KChildren,
+    pub styles: KStyle,
+    pub widget_name: WidgetName,
+}
+
+impl Default for KayakThemeProviderBundle {
+    fn default() -> Self {
+        Self {
+            theme_provider: Default::default(),
+            children: KChildren::default(),
+            styles: Default::default(),
+            widget_name: KayakThemeProvider::default().get_name(),
+        }
+    }
+}
+
+pub fn update_theme_provider(
+    In((widget_context, entity)): In<(WidgetContext, Entity)>,
+    mut commands: Commands,
+    query: Query<
+        (&KayakThemeProvider, &KChildren),
+        Or<(
+            Changed<KayakThemeProvider>,
+            Changed<KChildren>,
+            With<Mounted>,
+        )>,
+    >,
+    mut theme_query: Query<&mut KayakTheme>,
+) -> bool {
+    if let Ok((theme_provider, children)) = query.get(entity) {
+        // Only reuse this widget's own context entity so we never overwrite an ancestor's theme
+        let existing = widget_context.get_own_context_entity::<KayakTheme>(entity);
+
+        match existing.and_then(|theme_entity| theme_query.get_mut(theme_entity).ok()) {
+            Some(mut theme) => {
+                if *theme != theme_provider.theme {
+                    *theme = theme_provider.theme.clone();
+                }
+            }
+            None => {
+                let theme_entity = commands.spawn(theme_provider.theme.clone()).id();
+                widget_context.set_context_entity::<KayakTheme>(Some(entity), theme_entity);
+            }
+        }
+
+        children.process(&widget_context, Some(entity));
+        return true;
+    }
+
+    false
+}
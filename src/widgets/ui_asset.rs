@@ -0,0 +1,121 @@
+use bevy::prelude::{
+    AssetEvent, AssetServer, Assets, Bundle, Changed, Commands, Component, DespawnRecursiveExt,
+    Entity, EventReader, Handle, In, Or, Query, Reflect, ReflectComponent, Res, With,
+};
+
+use crate::{
+    children::KChildren,
+    context::{Mounted, WidgetName},
+    prelude::WidgetContext,
+    styles::{KStyle, RenderCommand, StyleProp},
+    ui_asset::{UiAsset, UiBuildContext, UiWidgetRegistry},
+    widget::{RegisteredWidgets, Widget},
+};
+
+/// Displays the widgets described by a [`UiAsset`]
+///
+/// The widgets are rebuilt whenever the asset is modified, so edits to the file show up without
+/// restarting the app when the asset server is watching for changes.
+#[derive(Component, Default, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct UiAssetProps {
+    /// The asset to display
+    pub handle: Handle<UiAsset>,
+}
+
+impl Widget for UiAssetProps {}
+
+#[derive(Bundle)]
+pub struct UiAssetBundle {
+    pub asset: UiAssetProps,
+    pub styles: KStyle,
+    pub widget_name: WidgetName,
+}
+
+impl Default for UiAssetBundle {
+    fn default() -> Self {
+        Self {
+            asset: Default::default(),
+            styles: Default::default(),
+            widget_name: UiAssetProps::default().get_name(),
+        }
+    }
+}
+
+pub(crate) fn update_ui_asset(
+    In((widget_context, entity)): In<(WidgetContext, Entity)>,
+    mut commands: Commands,
+    assets: Res<Assets<UiAsset>>,
+    asset_server: Res<AssetServer>,
+    registry: Res<UiWidgetRegistry>,
+    registered: Res<RegisteredWidgets>,
+    children_query: Query<&KChildren>,
+    mut query: Query<
+        (&mut KStyle, &UiAssetProps),
+        Or<(Changed<KStyle>, Changed<UiAssetProps>, With<Mounted>)>,
+    >,
+) -> bool {
+    if let Ok((mut style, props)) = query.get_mut(entity) {
+        style.render_command = StyleProp::Value(RenderCommand::Layout);
+
+        // Nothing is displayed until the asset has loaded
+        if let Some(asset) = assets.get(&props.handle) {
+            let context = UiBuildContext {
+                asset_server: &asset_server,
+                systems: &registered.systems,
+            };
+            // Every widget is built before any are spawned, so that an asset that fails to build
+            // keeps displaying the widgets from its last successful build
+            let widgets = match asset
+                .elements
+                .iter()
+                .map(|element| registry.build(element, &context))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(widgets) => widgets,
+                Err(err) => {
+                    bevy::log::error!("Failed to build UI asset: {}", err);
+                    return false;
+                }
+            };
+
+            let parent_id = Some(entity);
+            for widget in widgets {
+                let child = if let Some(child) = widget_context.get_child_at(parent_id) {
+                    commands.entity(child).despawn_recursive();
+                    commands.get_or_spawn(child).id()
+                } else {
+                    commands.spawn_empty().id()
+                };
+                UiWidgetRegistry::spawn(&mut commands, child, widget, &children_query);
+                widget_context.add_widget(parent_id, child);
+            }
+
+            // Despawn the widgets built for elements that have since been removed
+            while let Some(child) = widget_context.get_child_at(parent_id) {
+                UiWidgetRegistry::despawn(&mut commands, child, &children_query);
+            }
+        }
+
+        return true;
+    }
+    false
+}
+
+/// Re-renders the widgets displaying a [`UiAsset`] once it has loaded or been modified
+pub(super) fn reload_ui_assets(
+    mut events: EventReader<AssetEvent<UiAsset>>,
+    mut query: Query<&mut UiAssetProps>,
+) {
+    for event in events.iter() {
+        let handle = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+        for mut props in query.iter_mut() {
+            if props.handle == *handle {
+                props.set_changed();
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::{
-    Bundle, Changed, Color, Commands, Component, Entity, In, Or, Query, Vec2, With,
+    Bundle, Changed, Commands, Component, DespawnRecursiveExt, Entity, In, Or, ParamSet, Query,
+    Reflect, ReflectComponent, Res, Vec2, With,
 };
 
 use crate::{
@@ -10,6 +11,7 @@ use crate::{
     on_event::OnEvent,
     prelude::WidgetContext,
     styles::{Corner, Edge, KStyle, PositionType, RenderCommand, StyleProp, Units},
+    theme::KayakTheme,
     widget::Widget,
 };
 
@@ -19,7 +21,8 @@ use super::{
     text::{TextProps, TextWidgetBundle},
 };
 
-#[derive(Component, Debug, Default)]
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
 pub struct KWindow {
     /// If true, allows the window to be draggable by its title bar
     pub draggable: bool,
@@ -60,23 +63,43 @@ impl Default for WindowBundle {
 pub fn window_update(
     In((widget_context, window_entity)): In<(WidgetContext, Entity)>,
     mut commands: Commands,
-    mut query: Query<
-        (&mut KStyle, &KChildren, &mut KWindow),
-        Or<(
-            Changed<KWindow>,
-            Changed<KStyle>,
-            Changed<KChildren>,
-            With<Mounted>,
-        )>,
-    >,
+    global_theme: Res<KayakTheme>,
+    theme_query: Query<&KayakTheme>,
+    changed_themes: Query<Entity, Changed<KayakTheme>>,
+    mut query: ParamSet<(
+        Query<
+            Entity,
+            Or<(
+                Changed<KWindow>,
+                Changed<KStyle>,
+                Changed<KChildren>,
+                With<Mounted>,
+            )>,
+        >,
+        Query<(&mut KStyle, &KChildren, &mut KWindow)>,
+    )>,
 ) -> bool {
+    let theme_changed = KayakTheme::is_changed(
+        &widget_context,
+        window_entity,
+        &theme_query,
+        &changed_themes,
+        &global_theme,
+    );
+    if query.p0().get(window_entity).is_err() && !theme_changed {
+        return false;
+    }
+
     let mut has_changed = false;
-    if let Ok((mut window_style, children, mut window)) = query.get_mut(window_entity) {
+    if let Ok((mut window_style, children, mut window)) = query.p1().get_mut(window_entity) {
+        let theme =
+            KayakTheme::resolve(&widget_context, window_entity, &theme_query, &global_theme);
+
         *window_style = KStyle {
-            background_color: StyleProp::Value(Color::rgba(0.125, 0.125, 0.125, 1.0)),
-            border_color: StyleProp::Value(Color::rgba(0.0781, 0.0898, 0.101, 1.0)),
-            border: StyleProp::Value(Edge::all(4.0)),
-            border_radius: StyleProp::Value(Corner::all(5.0)),
+            background_color: StyleProp::Value(theme.palette.background),
+            border_color: StyleProp::Value(theme.palette.border),
+            border: StyleProp::Value(Edge::all(theme.spacing.window_border)),
+            border_radius: StyleProp::Value(Corner::all(theme.radii.window)),
             render_command: StyleProp::Value(RenderCommand::Quad),
             position_type: StyleProp::Value(PositionType::SelfDirected),
             left: StyleProp::Value(Units::Pixels(window.position.x)),
@@ -88,47 +111,55 @@ pub fn window_update(
             ..window_style.clone()
         };
 
-        if window.title_bar_entity.is_none() {
+        // Rebuild the title bar and content when the theme changes so they pick up the new values,
+        // reusing the entities from the previous render
+        if window.title_bar_entity.is_none() || theme_changed {
             let title = window.title.clone();
+            let title_background_entity =
+                reuse_or_spawn(&mut commands, &widget_context, window_entity);
+            let title_entity =
+                reuse_or_spawn(&mut commands, &widget_context, title_background_entity);
+            let clip_entity = reuse_or_spawn(&mut commands, &widget_context, window_entity);
 
             let mut title_children = KChildren::new();
             // Spawn title children
-            let title_entity = commands
-                .spawn(TextWidgetBundle {
-                    text: TextProps {
-                        content: title.clone(),
-                        size: 16.0,
-                        line_height: Some(25.0),
-                        ..Default::default()
-                    },
-                    styles: KStyle {
-                        height: StyleProp::Value(Units::Pixels(25.0)),
-                        ..KStyle::default()
-                    },
+            commands.entity(title_entity).insert(TextWidgetBundle {
+                text: TextProps {
+                    content: title.clone(),
+                    size: theme.font_sizes.title,
+                    line_height: Some(theme.font_sizes.title_line_height),
                     ..Default::default()
-                })
-                .id();
+                },
+                styles: KStyle {
+                    color: StyleProp::Value(theme.palette.text),
+                    height: StyleProp::Value(Units::Pixels(theme.font_sizes.title_line_height)),
+                    ..KStyle::default()
+                },
+                ..Default::default()
+            });
             title_children.add(title_entity);
 
-            let title_background_entity = commands
-                .spawn(BackgroundBundle {
+            commands
+                .entity(title_background_entity)
+                .insert(BackgroundBundle {
                     styles: KStyle {
                         render_command: StyleProp::Value(RenderCommand::Quad),
-                        background_color: StyleProp::Value(Color::rgba(0.0781, 0.0898, 0.101, 1.0)),
-                        border_radius: StyleProp::Value(Corner::all(5.0)),
-                        height: StyleProp::Value(Units::Pixels(24.0)),
+                        background_color: StyleProp::Value(theme.palette.surface),
+                        border_radius: StyleProp::Value(Corner::all(theme.radii.window)),
+                        height: StyleProp::Value(Units::Pixels(theme.spacing.title_bar_height)),
                         width: StyleProp::Value(Units::Stretch(1.0)),
                         left: StyleProp::Value(Units::Pixels(0.0)),
                         right: StyleProp::Value(Units::Pixels(0.0)),
                         top: StyleProp::Value(Units::Pixels(0.0)),
                         bottom: StyleProp::Value(Units::Pixels(0.0)),
-                        padding_left: StyleProp::Value(Units::Pixels(5.0)),
+                        padding_left: StyleProp::Value(Units::Pixels(
+                            theme.spacing.title_bar_padding,
+                        )),
                         ..KStyle::default()
                     },
                     children: title_children,
                     ..BackgroundBundle::default()
-                })
-                .id();
+                });
             window.title_bar_entity = Some(title_background_entity);
 
             if window.draggable {
@@ -179,9 +210,10 @@ pub fn window_update(
                 children: children.clone(),
                 ..ClipBundle::default()
             };
-            clip_bundle.styles.padding = StyleProp::Value(Edge::all(Units::Pixels(10.0)));
+            clip_bundle.styles.padding =
+                StyleProp::Value(Edge::all(Units::Pixels(theme.spacing.window_padding)));
 
-            let clip_entity = commands.spawn(clip_bundle).id();
+            commands.entity(clip_entity).insert(clip_bundle);
             widget_context.add_widget(Some(window_entity), clip_entity);
             // let children = widget_context.get_children(window_entity);
             has_changed = true;
@@ -190,3 +222,18 @@ pub fn window_update(
 
     has_changed
 }
+
+/// Reuses the entity of the parent's next child from the previous render, or spawns a new one
+fn reuse_or_spawn(
+    commands: &mut Commands,
+    widget_context: &WidgetContext,
+    parent: Entity,
+) -> Entity {
+    match widget_context.get_child_at(Some(parent)) {
+        Some(entity) => {
+            commands.entity(entity).despawn_recursive();
+            commands.get_or_spawn(entity).id()
+        }
+        None => commands.spawn_empty().id(),
+    }
+}
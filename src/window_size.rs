@@ -3,7 +3,9 @@ use bevy::{
     window::{WindowCreated, WindowResized},
 };
 
-/// Tracks the bevy window size.
+use crate::UiScale;
+
+/// Tracks the bevy window size, in UI pixels (see [`UiScale`]).
 #[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
 pub struct WindowSize(pub f32, pub f32);
 
@@ -11,9 +13,17 @@ pub fn update_window_size(
     mut window_resized_events: EventReader<WindowResized>,
     mut window_created_events: EventReader<WindowCreated>,
     windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
     mut window_size: ResMut<WindowSize>,
 ) {
     let mut changed_window_ids = Vec::new();
+    // A new scale changes how many UI pixels fit in the window
+    if ui_scale.is_changed() {
+        if let Some(window) = windows.get_primary() {
+            changed_window_ids.push(window.id());
+        }
+    }
+
     // handle resize events. latest events are handled first because we only want to resize each
     // window once
     for event in window_resized_events.iter().rev() {
@@ -36,8 +46,9 @@ pub fn update_window_size(
 
     for window_id in changed_window_ids {
         if let Some(window) = windows.get(window_id) {
-            let width = window.width();
-            let height = window.height();
+            let scale = ui_scale.scale_factor(window);
+            let width = window.physical_width() as f32 / scale;
+            let height = window.physical_height() as f32 / scale;
             *window_size = WindowSize(width, height);
         }
     }